@@ -0,0 +1,62 @@
+//! Compares creating many small partitions with a `SmallPartitionVec<T, N>` and a `PartitionVec<T>`.
+//!
+//! Run with `cargo bench --bench small_partition_vec`.
+
+extern crate partitions;
+
+use partitions::{PartitionVec, SmallPartitionVec};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// The amount of partitions that are created for each measurement.
+const PARTITIONS: usize = 100_000;
+
+/// Builds the sets `{0, 2, 4, 6}`, `{1, 3}`, `{5}` and `{7}` and returns how many sets there are.
+fn small_partition() -> usize {
+    let mut small = SmallPartitionVec::<u32, 8>::new();
+    small.extend(0..8);
+    for index in (2..8).step_by(2) {
+        small.union(0, index);
+    }
+    small.union(1, 3);
+
+    small.amount_of_sets()
+}
+
+/// Builds the same sets as `small_partition` in a `PartitionVec<u32>`.
+fn partition_vec() -> usize {
+    let mut partition_vec = PartitionVec::<u32>::new();
+    partition_vec.extend(0..8);
+    for index in (2..8).step_by(2) {
+        partition_vec.union(0, index);
+    }
+    partition_vec.union(1, 3);
+
+    partition_vec.amount_of_sets()
+}
+
+/// Returns the fastest of a few runs of creating `PARTITIONS` partitions with `build`.
+fn measure(build: fn() -> usize) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..PARTITIONS {
+                assert!(black_box(build()) == 4);
+            }
+
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let small = measure(small_partition);
+    let heap = measure(partition_vec);
+
+    println!("{PARTITIONS} partitions of 8 elements:");
+    println!("SmallPartitionVec<u32, 8>: {small:?}");
+    println!("PartitionVec<u32>:         {heap:?}");
+}
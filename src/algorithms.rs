@@ -0,0 +1,557 @@
+//! Algorithms that are built on top of a [`PartitionVec<T>`].
+//!
+//! [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+
+use {
+    disjoint_sets::{
+        partition_vec::{PartitionVec, Root},
+        union_find::UnionFind,
+    },
+    std::fmt,
+};
+
+/// The error returned when the children lists given to an algorithm do not form a tree.
+///
+/// This is returned by [`offline_lca`].
+///
+/// [`offline_lca`]: fn.offline_lca.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TreeError {
+    /// The root, a child or a query index is not below the amount of nodes.
+    IndexOutOfBounds(usize),
+    /// The node is reached a second time, so it has multiple parents or is part of a cycle.
+    MultipleParents(usize),
+    /// The node can not be reached from the root.
+    Unreachable(usize),
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TreeError::IndexOutOfBounds(index) => {
+                write!(formatter, "the index {index} is out of bounds")
+            }
+            TreeError::MultipleParents(index) => {
+                write!(formatter, "the node {index} has multiple parents")
+            }
+            TreeError::Unreachable(index) => {
+                write!(
+                    formatter,
+                    "the node {index} can not be reached from the root"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+/// The error returned when a graph has a cycle of odd length and can not be colored with
+/// two colors.
+///
+/// This is returned by [`two_color`].
+///
+/// [`two_color`]: fn.two_color.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OddCycle {
+    /// The index of the first edge that closes a cycle of odd length with the edges before it.
+    pub edge: usize,
+}
+
+impl fmt::Display for OddCycle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the edge {} closes an odd cycle", self.edge)
+    }
+}
+
+impl std::error::Error for OddCycle {}
+
+/// Finds the lowest common ancestor of every pair of nodes in `queries` with Tarjan's offline
+/// lowest common ancestor algorithm.
+///
+/// The tree is given as the list of children of every node, so `tree[i]` contains the children
+/// of node `i`, and every node should be reachable from `root` in exactly one way.
+/// The answer at index `i` is the lowest common ancestor of the nodes in `queries[i]`.
+///
+/// The nodes are visited depth first and every finished subtree is joined with its parent in
+/// a [`PartitionVec<usize>`] that stores the current ancestor of every set at its
+/// representative.
+/// This function will be executed in `O((n + q) α(n))` time where `q` is the amount of
+/// queries.
+///
+/// Unlike the [`CycleDetector`] this can not be used with any [`UnionFind`] structure, the
+/// ancestor of a set is read from its representative and the trait does not give access to
+/// the representatives.
+///
+/// # Errors
+///
+/// If `tree` is not a tree with `root` as its root, or if an index in `queries` is out of
+/// bounds, the error describes the first problem that is found.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::algorithms::{offline_lca, TreeError};
+///
+/// //     0
+/// //    / \
+/// //   1   2
+/// //  / \   \
+/// // 3   4   5
+/// let tree = vec![vec![1, 2], vec![3, 4], vec![5], vec![], vec![], vec![]];
+///
+/// let answers = offline_lca(&tree, 0, &[(3, 4), (4, 5), (1, 3), (5, 5)]);
+/// assert!(answers == Ok(vec![1, 0, 1, 5]));
+///
+/// let cycle = vec![vec![1], vec![0]];
+/// assert!(offline_lca(&cycle, 0, &[]) == Err(TreeError::MultipleParents(0)));
+/// ```
+///
+/// [`PartitionVec<usize>`]: ../partition_vec/struct.PartitionVec.html
+/// [`CycleDetector`]: struct.CycleDetector.html
+/// [`UnionFind`]: ../union_find/trait.UnionFind.html
+pub fn offline_lca(
+    tree: &[Vec<usize>],
+    root: usize,
+    queries: &[(usize, usize)],
+) -> Result<Vec<usize>, TreeError> {
+    check_tree(tree, root)?;
+
+    let len = tree.len();
+    // The queries of every node together with the other node and the index of the answer.
+    let mut questions = vec![Vec::new(); len];
+    for (query, &(first, second)) in queries.iter().enumerate() {
+        for &index in &[first, second] {
+            if index >= len {
+                return Err(TreeError::IndexOutOfBounds(index));
+            }
+        }
+        questions[first].push((second, query));
+        questions[second].push((first, query));
+    }
+
+    // The value at the representative of a set is the ancestor of the whole set.
+    let mut ancestors: PartitionVec<usize> = (0..len).collect();
+    let mut finished = bit_vec![false; len];
+    let mut answers = vec![0; queries.len()];
+
+    // Every node on the stack is stored with the amount of children that are visited.
+    let mut stack = vec![(root, 0)];
+    while let Some(&mut (node, ref mut visited)) = stack.last_mut() {
+        if let Some(&child) = tree[node].get(*visited) {
+            *visited += 1;
+            stack.push((child, 0));
+            continue;
+        }
+
+        stack.pop();
+        finished.set(node, true);
+        for &(other, query) in &questions[node] {
+            if finished[other] {
+                answers[query] = ancestors[Root(other)];
+            }
+        }
+
+        if let Some(&(parent, _)) = stack.last() {
+            ancestors.union(parent, node);
+            ancestors[Root(parent)] = parent;
+        }
+    }
+
+    Ok(answers)
+}
+
+/// Finds the edges that close a cycle in a graph whose edges are given one at a time.
+///
+/// Every edge that does not close a cycle joins the sets of its nodes in a [`UnionFind`]
+/// structure, so an edge closes a cycle exactly when both nodes already share a set.
+/// A self-loop always closes a cycle.
+/// This is a [`PartitionVec<()>`] by default, [`from_components`] takes any other structure
+/// like a [`PartitionArray<T, N>`] that does not need an allocator.
+/// No memory is allocated after the `CycleDetector` is constructed.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::algorithms::CycleDetector;
+///
+/// let mut cycles = CycleDetector::new(4);
+///
+/// // These edges form a forest.
+/// assert!(!cycles.adds_cycle(0, 1));
+/// assert!(!cycles.adds_cycle(2, 3));
+/// assert!(!cycles.adds_cycle(1, 2));
+///
+/// assert!(cycles.adds_cycle(3, 0));
+/// assert!(cycles.adds_cycle(2, 2));
+/// ```
+///
+/// [`UnionFind`]: ../union_find/trait.UnionFind.html
+/// [`PartitionVec<()>`]: ../partition_vec/struct.PartitionVec.html
+/// [`PartitionArray<T, N>`]: ../partition_array/struct.PartitionArray.html
+/// [`from_components`]: #method.from_components
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector<U = PartitionVec<()>> {
+    /// The connected components of the edges so far.
+    components: U,
+}
+
+impl CycleDetector {
+    /// Constructs a new `CycleDetector` for a graph with `len` nodes and no edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cycles = partitions::algorithms::CycleDetector::new(3);
+    ///
+    /// assert!(cycles.len() == 3);
+    /// ```
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            components: PartitionVec::from(vec![(); len]),
+        }
+    }
+}
+
+impl<U: UnionFind> CycleDetector<U> {
+    /// Constructs a new `CycleDetector` that uses `components` for the connected components.
+    ///
+    /// The nodes that already share a set in `components` count as connected by earlier
+    /// edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::{algorithms::CycleDetector, PartitionArray};
+    ///
+    /// let mut cycles = CycleDetector::from_components(PartitionArray::new([(); 3]));
+    ///
+    /// assert!(!cycles.adds_cycle(0, 1));
+    /// assert!(cycles.adds_cycle(1, 0));
+    /// ```
+    #[must_use]
+    pub fn from_components(components: U) -> Self {
+        Self { components }
+    }
+
+    /// Returns the structure that stores the connected components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::{algorithms::CycleDetector, PartitionArray, UnionFind};
+    ///
+    /// let mut cycles = CycleDetector::from_components(PartitionArray::new([(); 3]));
+    /// cycles.adds_cycle(0, 2);
+    ///
+    /// assert!(cycles.into_components().amount_of_sets() == 2);
+    /// ```
+    #[must_use]
+    pub fn into_components(self) -> U {
+        self.components
+    }
+
+    /// Returns the amount of nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let cycles = partitions::algorithms::CycleDetector::new(3);
+    ///
+    /// assert!(cycles.len() == 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(partitions::algorithms::CycleDetector::new(0).is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Adds the edge between `first_node` and `second_node` and returns `true` if it closes
+    /// a cycle with the earlier edges.
+    ///
+    /// An edge that closes a cycle does not change the components.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_node` or `second_node` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut cycles = partitions::algorithms::CycleDetector::new(3);
+    ///
+    /// assert!(!cycles.adds_cycle(0, 1));
+    /// assert!(!cycles.adds_cycle(1, 2));
+    /// assert!(cycles.adds_cycle(2, 0));
+    /// ```
+    pub fn adds_cycle(&mut self, first_node: usize, second_node: usize) -> bool {
+        !self.components.union(first_node, second_node)
+    }
+
+    /// Returns `true` if `first_node` and `second_node` are connected by the edges so far.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_node` or `second_node` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut cycles = partitions::algorithms::CycleDetector::new(3);
+    /// cycles.adds_cycle(0, 2);
+    ///
+    /// assert!(cycles.connected(2, 0));
+    /// assert!(!cycles.connected(0, 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn connected(&self, first_node: usize, second_node: usize) -> bool {
+        self.components.same_set(first_node, second_node)
+    }
+
+    /// Adds the `edges` one at a time and returns the index of the first edge that closes
+    /// a cycle, or `None` if no edge does.
+    ///
+    /// The edges after the one that closes a cycle are not added.
+    /// This method will be executed in `O(m α(n))` time where `m` is the amount of edges and
+    /// `α` is the inverse Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If an index in `edges` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::{algorithms::CycleDetector, PartitionArray};
+    ///
+    /// let mut cycles = CycleDetector::from_components(PartitionArray::new([(); 4]));
+    ///
+    /// assert!(cycles.first_cycle_edge(&[(0, 1), (2, 3)]) == None);
+    /// assert!(cycles.first_cycle_edge(&[(1, 2), (3, 0), (0, 1)]) == Some(1));
+    /// ```
+    pub fn first_cycle_edge(&mut self, edges: &[(usize, usize)]) -> Option<usize> {
+        edges
+            .iter()
+            .position(|&(first, second)| self.adds_cycle(first, second))
+    }
+}
+
+/// Returns the index of the first edge that closes a cycle with the edges before it,
+/// or `None` if the edges form a forest.
+///
+/// The graph has a node for every index up to the largest index in `edges`.
+/// A self-loop always closes a cycle.
+/// Use [`CycleDetector::first_cycle_edge`] to find the edge with another [`UnionFind`]
+/// structure.
+///
+/// This function will be executed in `O(n + m α(n))` time where `m` is the amount of edges and
+/// `α` is the inverse Ackermann function.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::algorithms::first_cycle_edge;
+///
+/// assert!(first_cycle_edge(&[(0, 1), (1, 2), (3, 1)]) == None);
+/// assert!(first_cycle_edge(&[(0, 1), (1, 2), (2, 0), (0, 3)]) == Some(2));
+/// assert!(first_cycle_edge(&[(0, 1), (4, 4)]) == Some(1));
+/// ```
+///
+/// [`CycleDetector::first_cycle_edge`]: struct.CycleDetector.html#method.first_cycle_edge
+/// [`UnionFind`]: ../union_find/trait.UnionFind.html
+#[must_use]
+pub fn first_cycle_edge(edges: &[(usize, usize)]) -> Option<usize> {
+    let len = edges
+        .iter()
+        .map(|&(first, second)| first.max(second) + 1)
+        .max()
+        .unwrap_or(0);
+    CycleDetector::new(len).first_cycle_edge(edges)
+}
+
+/// Returns `true` if the nodes of the graph with `len` nodes and the given `edges` can be
+/// colored with two colors such that every edge connects two different colors.
+///
+/// This is the same as checking that [`two_color`] does not return an error.
+/// A self-loop makes any graph not bipartite.
+///
+/// This function will be executed in `O(n + m α(n))` time where `m` is the amount of edges and
+/// `α` is the inverse Ackermann function.
+///
+/// # Panics
+///
+/// If an index in `edges` is not below `len`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::algorithms::is_bipartite;
+///
+/// // A square is bipartite but a triangle is not.
+/// assert!(is_bipartite(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]));
+/// assert!(!is_bipartite(3, &[(0, 1), (1, 2), (2, 0)]));
+/// assert!(!is_bipartite(2, &[(1, 1)]));
+/// ```
+///
+/// [`two_color`]: fn.two_color.html
+#[must_use]
+pub fn is_bipartite(len: usize, edges: &[(usize, usize)]) -> bool {
+    two_color(len, edges).is_ok()
+}
+
+/// Colors the nodes of the graph with `len` nodes and the given `edges` with two colors such
+/// that every edge connects two different colors.
+///
+/// The color of node `i` is the value at index `i` of the returned `Vec`.
+/// Every connected component is colored separately so different components can have nodes
+/// of the same color.
+///
+/// The edges are joined one by one in a union-find structure that stores for every element
+/// if it has the same color as its parent, path compression updates these parities while
+/// shortening the paths.
+/// This function will be executed in `O(n + m α(n))` time where `m` is the amount of edges and
+/// `α` is the inverse Ackermann function.
+///
+/// # Errors
+///
+/// If the graph contains a cycle of odd length, including a self-loop, the error contains
+/// the index of the first edge that closes such a cycle.
+///
+/// # Panics
+///
+/// If an index in `edges` is not below `len`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::algorithms::{two_color, OddCycle};
+///
+/// let edges = [(0, 1), (1, 2), (3, 4)];
+/// let colors = two_color(5, &edges).unwrap();
+/// for &(first, second) in &edges {
+///     assert!(colors[first] != colors[second]);
+/// }
+///
+/// // The last edge closes the cycle 0, 1, 2.
+/// assert!(two_color(5, &[(0, 1), (1, 2), (3, 4), (0, 2)]) == Err(OddCycle { edge: 3 }));
+/// ```
+pub fn two_color(len: usize, edges: &[(usize, usize)]) -> Result<Vec<bool>, OddCycle> {
+    let mut sets = ParitySets::new(len);
+    for (edge, &(first, second)) in edges.iter().enumerate() {
+        if !sets.union_different(first, second) {
+            return Err(OddCycle { edge });
+        }
+    }
+
+    Ok((0..len).map(|index| sets.find(index).1).collect())
+}
+
+/// A union-find structure that stores for every element if it has a different color
+/// than its parent.
+struct ParitySets {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+    /// If the element has a different color than its parent.
+    parity: Vec<bool>,
+}
+
+impl ParitySets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            parity: vec![false; len],
+        }
+    }
+
+    /// Returns the root of `index` and if `index` has a different color than the root.
+    fn find(&mut self, index: usize) -> (usize, bool) {
+        let parent = self.parent[index];
+        if parent == index {
+            return (index, false);
+        }
+
+        // This function is recursive so each parent on the way to the root is updated.
+        let (root, parent_parity) = self.find(parent);
+        self.parent[index] = root;
+        self.parity[index] ^= parent_parity;
+
+        (root, self.parity[index])
+    }
+
+    /// Makes sure `first_index` and `second_index` have different colors and returns `false`
+    /// if they already have the same color.
+    fn union_different(&mut self, first_index: usize, second_index: usize) -> bool {
+        let (i, first_parity) = self.find(first_index);
+        let (j, second_parity) = self.find(second_index);
+
+        if i == j {
+            return first_parity != second_parity;
+        }
+
+        let (child, root) = if self.rank[i] < self.rank[j] {
+            (i, j)
+        } else {
+            (j, i)
+        };
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        // The parities of both indices relative to the new root have to differ.
+        self.parity[child] = first_parity == second_parity;
+
+        true
+    }
+}
+
+/// Checks that every node of `tree` can be reached from `root` in exactly one way.
+fn check_tree(tree: &[Vec<usize>], root: usize) -> Result<(), TreeError> {
+    let len = tree.len();
+    if root >= len {
+        return Err(TreeError::IndexOutOfBounds(root));
+    }
+
+    let mut seen = bit_vec![false; len];
+    seen.set(root, true);
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        for &child in &tree[node] {
+            if child >= len {
+                return Err(TreeError::IndexOutOfBounds(child));
+            }
+            if seen[child] {
+                return Err(TreeError::MultipleParents(child));
+            }
+
+            seen.set(child, true);
+            stack.push(child);
+        }
+    }
+
+    match seen.iter().position(|seen| !seen) {
+        Some(index) => Err(TreeError::Unreachable(index)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,372 @@
+use {
+    crate::disjoint_sets::metadata::Metadata,
+    core::{cmp::Ordering, iter::FusedIterator},
+};
+
+/// Gives the representative of the set that `index` belongs to and compresses the path to it.
+///
+/// # Panics
+///
+/// If `index` is out of bounds.
+pub(crate) fn find_in(meta: &[Metadata], index: usize) -> usize {
+    // If the node is its own parent we have found the root.
+    if meta[index].parent() == index {
+        index
+    } else {
+        // This function is recursive so each parent on the way to the root is updated.
+        let root = find_in(meta, meta[index].parent());
+
+        // We update the parent to the root for a lower tree.
+        meta[index].set_parent(root);
+
+        root
+    }
+}
+
+/// Gives the representative of the set that `index` belongs to without changing any parents.
+///
+/// # Panics
+///
+/// If `index` is out of bounds.
+#[inline]
+pub(crate) fn find_final_in(meta: &[Metadata], mut index: usize) -> usize {
+    while index != meta[index].parent() {
+        index = meta[index].parent();
+    }
+
+    index
+}
+
+/// Joins the sets of `first_index` and `second_index` in `meta` and returns the roots the two
+/// sets had before they were joined, or `None` if they already shared a set.
+///
+/// # Panics
+///
+/// If `first_index` or `second_index` is out of bounds.
+pub(crate) fn union_roots_in(
+    meta: &[Metadata],
+    first_index: usize,
+    second_index: usize,
+) -> Option<(usize, usize)> {
+    let i = find_in(meta, first_index);
+    let j = find_in(meta, second_index);
+
+    if i == j {
+        return None;
+    }
+
+    // We swap the values of the links.
+    let link_i = meta[i].link();
+    let link_j = meta[j].link();
+    meta[i].set_link(link_j);
+    meta[j].set_link(link_i);
+
+    // We add to the tree with the highest rank.
+    match Ord::cmp(&meta[i].rank(), &meta[j].rank()) {
+        Ordering::Less => {
+            meta[i].set_parent(j);
+        }
+        Ordering::Equal => {
+            // We add the first tree to the second tree.
+            meta[i].set_parent(j);
+            // The second tree becomes larger.
+            meta[j].set_rank(meta[j].rank() + 1);
+        }
+        Ordering::Greater => {
+            meta[j].set_parent(i);
+        }
+    }
+
+    Some((i, j))
+}
+
+/// Removes `index` from its set in `meta` and gives it its own set.
+///
+/// # Panics
+///
+/// If `index` is out of bounds.
+pub(crate) fn make_singleton_in(meta: &[Metadata], index: usize) {
+    let mut current = meta[index].link();
+
+    if current != index {
+        // We make this the new root.
+        let root = current;
+        meta[root].set_rank(1);
+
+        // All parents except for the last are updated.
+        // The other elements become leaves so their rank is reset.
+        while meta[current].link() != index {
+            meta[current].set_parent(root);
+            if current != root {
+                meta[current].set_rank(0);
+            }
+
+            current = meta[current].link();
+        }
+
+        // We change the last parent and link.
+        meta[current].set_parent(root);
+        meta[current].set_link(root);
+        if current != root {
+            meta[current].set_rank(0);
+        }
+    }
+
+    meta[index].set_parent(index);
+    meta[index].set_link(index);
+    meta[index].set_rank(0);
+}
+
+/// Raises every parent and link at or above `index` by one to make room for a new element at
+/// `index`.
+#[cfg(feature = "std")]
+pub(crate) fn shift_up_in(meta: &[Metadata], index: usize) {
+    for node in meta {
+        let parent = node.parent();
+        if parent >= index {
+            node.set_parent(parent + 1);
+        }
+
+        let link = node.link();
+        if link >= index {
+            node.set_link(link + 1);
+        }
+    }
+}
+
+/// Lowers every parent and link above `index` by one after the singleton at `index` is removed.
+#[cfg(feature = "std")]
+pub(crate) fn shift_down_in(meta: &[Metadata], index: usize) {
+    for node in meta {
+        let parent = node.parent();
+        if parent > index {
+            node.set_parent(parent - 1);
+        }
+
+        let link = node.link();
+        if link > index {
+            node.set_link(link - 1);
+        }
+    }
+}
+
+/// Changes the first `new_len` elements of `meta` so they keep their sets without referring to
+/// the elements after them, which can then be removed.
+#[cfg(feature = "std")]
+pub(crate) fn truncate_in(meta: &[Metadata], new_len: usize) {
+    // Every kept element whose parent is removed makes itself the root of its set and walks
+    // the whole ring once. The walk points every member at the new root and makes every run
+    // of removed elements in the ring be skipped by the kept element just before it, so a
+    // set can cross the cut any number of times. After the walk the other kept members of
+    // the set have a kept parent and are not walked again. A kept element with a kept
+    // parent only needs its own link to skip the removed elements after it.
+    // Any set with a removed root has a kept member with a removed parent on the path of
+    // every kept member, so no kept element can end up pointing past `new_len`.
+    for i in 0..new_len {
+        let parent = meta[i].parent();
+        let mut current = meta[i].link();
+        if parent >= new_len {
+            // We make `i` the new root.
+            meta[i].set_parent(i);
+            meta[i].set_rank(1);
+
+            let mut previous = i;
+            // The last index we saw before we went out of the new bounds.
+            let mut index_before_oob = if current >= new_len {
+                Some(previous)
+            } else {
+                None
+            };
+
+            while current != i {
+                if current >= new_len {
+                    // If the current is above the new length we update this value if needed.
+                    if index_before_oob.is_none() {
+                        index_before_oob = Some(previous);
+                    }
+                } else if let Some(index) = index_before_oob {
+                    // If we are back in bounds for the first time we update the link.
+                    meta[index].set_link(current);
+                    index_before_oob = None;
+                }
+
+                meta[current].set_parent(i);
+                meta[current].set_rank(0);
+
+                previous = current;
+                current = meta[current].link();
+            }
+
+            if let Some(index) = index_before_oob {
+                meta[index].set_link(i);
+            }
+        } else if current >= new_len {
+            while current >= new_len {
+                current = meta[current].link();
+            }
+            meta[i].set_link(current);
+        }
+    }
+}
+
+/// Writes `data` as a list of `value => label` entries like the `Debug` output of a
+/// `PartitionVec<T>`, the labels count the sets in the order they first appear.
+///
+/// `labels` has room for a label for every index and is `None` everywhere.
+pub(crate) fn fmt_labeled<T: core::fmt::Debug>(
+    data: &[T],
+    meta: &[Metadata],
+    labels: &mut [Option<usize>],
+    formatter: &mut core::fmt::Formatter,
+) -> core::fmt::Result {
+    let mut builder = formatter.debug_list();
+    let mut next_label = 0;
+
+    for (index, value) in data.iter().enumerate() {
+        let root = find_final_in(meta, index);
+        let label = *labels[root].get_or_insert_with(|| {
+            next_label += 1;
+            next_label - 1
+        });
+        builder.entry(&format_args!("{value:?} => {label}"));
+    }
+
+    builder.finish()
+}
+
+/// An iterator over the indices of a set, it starts at the root and follows the links.
+///
+/// [`Set`] pairs these indices with their values, the iterator over a set of a
+/// `PartitionVecDeque<T>` first turns them into positions.
+#[derive(Clone, Debug)]
+pub(crate) struct Members<'a> {
+    meta: &'a [Metadata],
+    current: Option<usize>,
+    root: usize,
+    /// If the parents of the visited elements are set to the root.
+    compress: bool,
+}
+
+impl<'a> Members<'a> {
+    /// Creates an iterator over the indices in the set of `root`.
+    pub(crate) fn new(meta: &'a [Metadata], root: usize) -> Self {
+        Self {
+            meta,
+            current: Some(root),
+            root,
+            compress: true,
+        }
+    }
+
+    /// Returns the index of the representative of the set.
+    pub(crate) fn root(&self) -> usize {
+        self.root
+    }
+}
+
+impl Iterator for Members<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let current = self.current?;
+
+        // We compress the path of every element we visit.
+        // This only lowers the height of the tree so the rank of the root stays
+        // a valid upper bound and does not need to be changed.
+        if self.compress {
+            self.meta[current].set_parent(self.root);
+        }
+
+        let next = self.meta[current].link();
+
+        // We started at the root.
+        self.current = if next == self.root { None } else { Some(next) };
+
+        Some(current)
+    }
+}
+
+impl FusedIterator for Members<'_> {}
+
+/// An iterator over a set in a `PartitionVec<T>`.
+///
+/// This struct is created by the [`set`] method on [`PartitionVec<T>`] and on
+/// [`PartitionArray<T, N>`].
+/// See its documentation for more.
+///
+/// [`set`]: ../partition_vec/struct.PartitionVec.html#method.set
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+/// [`PartitionArray<T, N>`]: ../partition_array/struct.PartitionArray.html
+#[derive(Debug)]
+pub struct Set<'a, T: 'a> {
+    data: &'a [T],
+    members: Members<'a>,
+}
+
+impl<'a, T> Set<'a, T> {
+    /// Creates an iterator over the set of `root` in `data`.
+    pub(crate) fn new(data: &'a [T], meta: &'a [Metadata], root: usize) -> Self {
+        Self {
+            data,
+            members: Members::new(meta, root),
+        }
+    }
+
+    /// Creates an iterator over the set of `root` in `data` that never changes `meta`.
+    #[cfg(feature = "std")]
+    pub(crate) fn read_only(data: &'a [T], meta: &'a [Metadata], root: usize) -> Self {
+        Self {
+            data,
+            members: Members {
+                compress: false,
+                ..Members::new(meta, root)
+            },
+        }
+    }
+
+    /// Returns the index of the representative of the set.
+    ///
+    /// This is the same index for every iterator over this set until the partition changes,
+    /// and it does not change while iterating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_array = partitions::PartitionArray::new(['a', 'b', 'c']);
+    /// partition_array.union(0, 2);
+    ///
+    /// let set = partition_array.set(2);
+    /// assert!(set.root() == partition_array.set(0).root());
+    /// assert!(set.root() != partition_array.set(1).root());
+    ///
+    /// // The representative is a member of the set.
+    /// let root = set.root();
+    /// assert!(set.map(|(index, _)| index).any(|index| index == root));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn root(&self) -> usize {
+        self.members.root()
+    }
+}
+
+impl<T> Clone for Set<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Set<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        let current = self.members.next()?;
+
+        Some((current, &self.data[current]))
+    }
+}
+
+impl<T> FusedIterator for Set<'_, T> {}
@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use core::cell::Cell;
 
 /// This provides additional information about a given value in the `DisjointSets`.
 ///
@@ -59,7 +59,7 @@ impl Metadata {
 }
 
 #[cfg(feature = "compact")]
-const USIZE_BITS: usize = 8 * ::std::mem::size_of::<usize>();
+const USIZE_BITS: usize = 8 * core::mem::size_of::<usize>();
 // The least amount of elements you need in a set to get a rank of 0 is 1.
 // For a given n > 0 the least amount of elements you need to get a rank of n is
 // double the least amount to get a rank of n - 1.
@@ -74,25 +74,31 @@ const USIZE_BITS: usize = 8 * ::std::mem::size_of::<usize>();
 // To store this rank we need a maximum of B + 3 bits.
 // Because we devide these bits over the parent and link we need a maximum of (B + 3) / 2 bits
 // rounded up which is B / 2 + 2 bits rounded down.
-#[cfg(all(feature = "compact", target_pointer_width = "8"))]
-const RANK_BITS: usize = 2;
-#[cfg(all(feature = "compact", target_pointer_width = "16"))]
-const RANK_BITS: usize = 2;
-#[cfg(all(feature = "compact", target_pointer_width = "32"))]
-const RANK_BITS: usize = 3;
-#[cfg(all(feature = "compact", target_pointer_width = "64"))]
-const RANK_BITS: usize = 3;
-#[cfg(all(feature = "compact", target_pointer_width = "128"))]
-const RANK_BITS: usize = 4;
-#[cfg(all(feature = "compact", target_pointer_width = "256"))]
-const RANK_BITS: usize = 4;
-// TODO: When possible replace with:
-// const RANK_BITS: usize = std::mem::size_of::<usize>().trailing_zeros() as usize / 2 + 2;
+#[cfg(feature = "compact")]
+#[allow(clippy::cast_possible_truncation)]
+const RANK_BITS: usize = core::mem::size_of::<usize>().trailing_zeros() as usize / 2 + 2;
 #[cfg(feature = "compact")]
 const MASK: usize = (1 << RANK_BITS) - 1;
 #[cfg(feature = "compact")]
 const MAX: usize = (1 << (USIZE_BITS - RANK_BITS)) - 2;
 
+/// The maximum rank of an element.
+///
+/// A rank of `n` needs at least `2 ^ n` elements so this can never be reached.
+#[cfg(feature = "std")]
+pub(crate) const MAX_RANK: usize = 8 * core::mem::size_of::<usize>() - 1;
+
+/// The maximum amount of values a `PartitionVec<T>` can hold.
+///
+/// The index `!0` is reserved to mark lazily removed values.
+#[cfg(all(feature = "std", not(feature = "compact")))]
+pub(crate) const MAX_LEN: usize = !0;
+/// The maximum amount of values a `PartitionVec<T>` can hold.
+///
+/// Every index has to fit next to the rank bits.
+#[cfg(all(feature = "std", feature = "compact"))]
+pub(crate) const MAX_LEN: usize = MAX + 1;
+
 /// This provides additional information about a given value in the `DisjointSets`.
 ///
 /// For each value in the `DisjointSets` we store a `Metadata`.
@@ -116,9 +122,7 @@ impl Metadata {
     /// Panics if the index is above the maximum amount of values a `PartitionVec<T>` can store
     /// with the compact representation.
     pub(crate) fn new(index: usize) -> Self {
-        if index > MAX {
-            panic!("A PartitionVec can only hold {} values.", MAX)
-        }
+        assert!(index <= MAX, "A PartitionVec can only hold {} values.", MAX);
 
         Self {
             parent: Cell::new(index << RANK_BITS),
@@ -133,6 +137,12 @@ impl Metadata {
 
     /// Set the `parent` variable.
     pub(crate) fn set_parent(&self, value: usize) {
+        debug_assert!(
+            value <= MAX,
+            "The index {} does not fit in the compact representation.",
+            value
+        );
+
         let old = self.parent.get();
         self.parent.set((old & MASK) | (value << RANK_BITS));
     }
@@ -144,26 +154,40 @@ impl Metadata {
 
     /// Set the `link` variable.
     pub(crate) fn set_link(&self, value: usize) {
+        debug_assert!(
+            value <= MAX,
+            "The index {} does not fit in the compact representation.",
+            value
+        );
+
         let old = self.link.get();
         self.link.set((old & MASK) | (value << RANK_BITS));
     }
 
     /// Return the `rank` variable.
     pub(crate) fn rank(&self) -> usize {
-        let left = self.link.get() & RANK_BITS;
-        let right = self.parent.get() & RANK_BITS;
-        (left << RANK_BITS) | right
+        // The high bits are stored in `parent` and the low bits in `link`.
+        let high = self.parent.get() & MASK;
+        let low = self.link.get() & MASK;
+        (high << RANK_BITS) | low
     }
 
     /// Set the `rank` variable.
     pub(crate) fn set_rank(&self, value: usize) {
+        debug_assert!(
+            value >> (2 * RANK_BITS) == 0,
+            "The rank {} does not fit.",
+            value
+        );
+
         let old = self.parent.get();
         self.parent.set((old & !MASK) | (value >> RANK_BITS));
         let old = self.link.get();
-        self.link.set((old & !MASK) | (value & RANK_BITS));
+        self.link.set((old & !MASK) | (value & MASK));
     }
 }
 
+#[cfg(feature = "std")]
 impl Metadata {
     pub(crate) fn is_marked(&self) -> bool {
         self.parent.get() == !0
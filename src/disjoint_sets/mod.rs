@@ -2,5 +2,23 @@
 //!
 //! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
 
+mod forest;
 mod metadata;
+#[cfg(feature = "std")]
+pub mod offline_connectivity;
+pub mod partition_array;
+#[cfg(feature = "std")]
+pub mod partition_grid;
+#[cfg(feature = "std")]
+pub mod partition_slab;
+#[cfg(feature = "std")]
 pub mod partition_vec;
+#[cfg(feature = "std")]
+pub mod partition_vec_deque;
+#[cfg(feature = "std")]
+pub mod small_partition_vec;
+#[cfg(feature = "std")]
+pub mod timestamped_partition;
+#[cfg(feature = "std")]
+pub mod typed_partition_vec;
+pub mod union_find;
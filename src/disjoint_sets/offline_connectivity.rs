@@ -0,0 +1,384 @@
+//! Answers connectivity queries on a timeline of added and removed unions.
+//!
+//! See [`OfflineConnectivity`] for more information.
+//!
+//! [`OfflineConnectivity`]: struct.OfflineConnectivity.html
+
+use std::collections::HashMap;
+
+/// An event on the timeline of an `OfflineConnectivity`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Event {
+    /// An union between two indices is added.
+    Union(usize, usize),
+    /// An earlier union between two indices is removed.
+    Remove(usize, usize),
+    /// It is asked whether two indices share a set.
+    Query(usize, usize),
+}
+
+/// Answers `same_set` queries on a timeline where unions are added and removed again.
+///
+/// A [disjoint-sets/union-find] structure can not undo a single union, so the whole timeline
+/// is recorded first and the queries are all answered at once by [`answer`].
+/// This uses the offline technique where every union is stored in the nodes of a segment tree
+/// over the queries during which it exists.
+/// The segment tree is then walked with a union-find structure where the unions can be
+/// rolled back once a node is left.
+/// For a timeline of `k` events this takes `O(k log k log n)` time.
+///
+/// The unions are not ordered, removing the union of `(a, b)` also removes an union of `(b, a)`.
+/// When the same union is added multiple times every addition has to be removed separately.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::OfflineConnectivity;
+///
+/// let mut timeline = OfflineConnectivity::new(4);
+/// timeline.union(0, 1);
+/// timeline.union(1, 2);
+/// let before = timeline.same_set(0, 2);
+/// timeline.remove(2, 1);
+/// let after = timeline.same_set(0, 2);
+/// let other = timeline.same_set(0, 1);
+///
+/// let answers = timeline.answer();
+/// assert!(answers[before]);
+/// assert!(!answers[after]);
+/// assert!(answers[other]);
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`answer`]: struct.OfflineConnectivity.html#method.answer
+#[derive(Clone, Debug, Default)]
+pub struct OfflineConnectivity {
+    /// The amount of elements.
+    len: usize,
+    /// The events in the order they were recorded.
+    events: Vec<Event>,
+    /// The amount of queries in `events`.
+    queries: usize,
+    /// The amount of times every union is currently added.
+    active: HashMap<(usize, usize), usize>,
+}
+
+impl OfflineConnectivity {
+    /// Constructs a new `OfflineConnectivity` with `len` elements and an empty timeline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let timeline = partitions::OfflineConnectivity::new(3);
+    ///
+    /// assert!(timeline.len() == 3);
+    /// assert!(timeline.answer().is_empty());
+    /// ```
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            events: Vec::new(),
+            queries: 0,
+            active: HashMap::new(),
+        }
+    }
+
+    /// Returns the amount of elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let timeline = partitions::OfflineConnectivity::new(5);
+    ///
+    /// assert!(timeline.len() == 5);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(partitions::OfflineConnectivity::new(0).is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds an union between `first_index` and `second_index` to the timeline.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut timeline = partitions::OfflineConnectivity::new(3);
+    /// timeline.union(0, 2);
+    /// let query = timeline.same_set(2, 0);
+    ///
+    /// assert!(timeline.answer()[query]);
+    /// ```
+    pub fn union(&mut self, first_index: usize, second_index: usize) {
+        let key = self.key(first_index, second_index);
+        *self.active.entry(key).or_insert(0) += 1;
+        self.events.push(Event::Union(key.0, key.1));
+    }
+
+    /// Removes an earlier union between `first_index` and `second_index` from the timeline.
+    ///
+    /// # Panics
+    ///
+    /// If there is no union between `first_index` and `second_index` that was not
+    /// removed already.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut timeline = partitions::OfflineConnectivity::new(3);
+    /// timeline.union(0, 2);
+    /// timeline.union(0, 2);
+    /// timeline.remove(2, 0);
+    /// let query = timeline.same_set(0, 2);
+    ///
+    /// assert!(timeline.answer()[query]);
+    /// ```
+    pub fn remove(&mut self, first_index: usize, second_index: usize) {
+        let key = self.key(first_index, second_index);
+        match self.active.get_mut(&key) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => panic!(
+                "There is no union between {} and {} to remove.",
+                first_index, second_index
+            ),
+        }
+        self.events.push(Event::Remove(key.0, key.1));
+    }
+
+    /// Asks whether `first_index` and `second_index` share a set at this point of the timeline.
+    ///
+    /// Returns the index of the answer in the `Vec` returned by [`answer`].
+    /// The queries are numbered in order so the first query has index `0`.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut timeline = partitions::OfflineConnectivity::new(3);
+    ///
+    /// assert!(timeline.same_set(0, 1) == 0);
+    /// timeline.union(0, 1);
+    /// assert!(timeline.same_set(0, 1) == 1);
+    ///
+    /// assert!(timeline.answer() == [false, true]);
+    /// ```
+    ///
+    /// [`answer`]: struct.OfflineConnectivity.html#method.answer
+    pub fn same_set(&mut self, first_index: usize, second_index: usize) -> usize {
+        let key = self.key(first_index, second_index);
+        self.events.push(Event::Query(key.0, key.1));
+        self.queries += 1;
+
+        self.queries - 1
+    }
+
+    /// Answers every query of the timeline.
+    ///
+    /// The answer at index `i` belongs to the query for which [`same_set`] returned `i`.
+    ///
+    /// This method will be executed in `O(k log k log n)` time where `k` is the amount of
+    /// events in the timeline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut timeline = partitions::OfflineConnectivity::new(4);
+    /// timeline.union(0, 1);
+    /// timeline.union(2, 3);
+    /// timeline.same_set(0, 3);
+    /// timeline.union(1, 2);
+    /// timeline.same_set(0, 3);
+    /// timeline.remove(0, 1);
+    /// timeline.same_set(0, 3);
+    /// timeline.same_set(1, 3);
+    ///
+    /// assert!(timeline.answer() == [false, true, false, true]);
+    /// ```
+    ///
+    /// [`same_set`]: struct.OfflineConnectivity.html#method.same_set
+    #[must_use]
+    pub fn answer(&self) -> Vec<bool> {
+        let queries = self.queries;
+
+        // Every union is active from the first query after it was added up to the first
+        // query after it was removed.
+        let mut tree = vec![Vec::new(); 2 * queries.next_power_of_two()];
+        let mut started: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        let mut questions = Vec::with_capacity(queries);
+        for &event in &self.events {
+            let now = questions.len();
+            match event {
+                Event::Union(first, second) => {
+                    started.entry((first, second)).or_default().push(now);
+                }
+                Event::Remove(first, second) => {
+                    // `remove` checked that the union was added so there always is a start.
+                    if let Some(start) = started.get_mut(&(first, second)).and_then(Vec::pop) {
+                        insert(&mut tree, 1, 0, queries, start, now, (first, second));
+                    }
+                }
+                Event::Query(first, second) => questions.push((first, second)),
+            }
+        }
+        for (&union, starts) in &started {
+            for &start in starts {
+                insert(&mut tree, 1, 0, queries, start, queries, union);
+            }
+        }
+
+        let mut answers = vec![false; queries];
+        if queries > 0 {
+            let mut sets = RollbackSets::new(self.len);
+            walk(&tree, 1, 0, queries, &questions, &mut sets, &mut answers);
+        }
+
+        answers
+    }
+
+    /// Checks the bounds of both indices and returns them in order.
+    fn key(&self, first_index: usize, second_index: usize) -> (usize, usize) {
+        assert!(
+            first_index < self.len && second_index < self.len,
+            "The index is out of bounds."
+        );
+
+        (first_index.min(second_index), first_index.max(second_index))
+    }
+}
+
+/// Adds `union` to the nodes of the segment tree that cover the queries `start..end`.
+///
+/// The node `node` covers the queries `low..high`.
+fn insert(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    start: usize,
+    end: usize,
+    union: (usize, usize),
+) {
+    if end <= low || high <= start {
+        return;
+    }
+    if start <= low && high <= end {
+        tree[node].push(union);
+        return;
+    }
+
+    let middle = low + (high - low) / 2;
+    insert(tree, 2 * node, low, middle, start, end, union);
+    insert(tree, 2 * node + 1, middle, high, start, end, union);
+}
+
+/// Answers the queries `low..high` covered by `node` and rolls back the unions made there.
+fn walk(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    questions: &[(usize, usize)],
+    sets: &mut RollbackSets,
+    answers: &mut [bool],
+) {
+    let checkpoint = sets.checkpoint();
+    for &(first, second) in &tree[node] {
+        sets.union(first, second);
+    }
+
+    if high - low == 1 {
+        let (first, second) = questions[low];
+        answers[low] = sets.find(first) == sets.find(second);
+    } else {
+        let middle = low + (high - low) / 2;
+        walk(tree, 2 * node, low, middle, questions, sets, answers);
+        walk(tree, 2 * node + 1, middle, high, questions, sets, answers);
+    }
+
+    sets.rollback(checkpoint);
+}
+
+/// A union-find structure without path compression where unions can be undone in reverse order.
+struct RollbackSets {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+    /// The roots that were given a parent, together with whether the rank of the new
+    /// parent was raised.
+    history: Vec<(usize, bool)>,
+}
+
+impl RollbackSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            history: Vec::new(),
+        }
+    }
+
+    fn find(&self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            index = self.parent[index];
+        }
+
+        index
+    }
+
+    fn union(&mut self, first_index: usize, second_index: usize) {
+        let i = self.find(first_index);
+        let j = self.find(second_index);
+
+        if i == j {
+            return;
+        }
+
+        let (child, root) = if self.rank[i] < self.rank[j] {
+            (i, j)
+        } else {
+            (j, i)
+        };
+        let raised = self.rank[child] == self.rank[root];
+        if raised {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        self.history.push((child, raised));
+    }
+
+    /// Returns a value that can be given to `rollback` to undo all later unions.
+    fn checkpoint(&self) -> usize {
+        self.history.len()
+    }
+
+    fn rollback(&mut self, checkpoint: usize) {
+        while self.history.len() > checkpoint {
+            let (child, raised) = self.history.pop().unwrap();
+            let root = self.parent[child];
+            if raised {
+                self.rank[root] -= 1;
+            }
+            self.parent[child] = child;
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! A [disjoint-sets/union-find] implementation of a fixed size array partitioned in sets.
+//!
+//! See [`PartitionArray<T, N>`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`PartitionArray<T, N>`]: struct.PartitionArray.html
+
+use {
+    crate::disjoint_sets::{
+        forest::{find_final_in, find_in, fmt_labeled, make_singleton_in, union_roots_in},
+        metadata::Metadata,
+        union_find::UnionFind,
+    },
+    core::ops,
+};
+
+pub use crate::disjoint_sets::forest::Set;
+
+/// A [disjoint-sets/union-find] implementation of an array partitioned in sets.
+///
+/// This has the same sets as a [`PartitionVec<T>`] but stores its values and metadata
+/// in arrays of length `N` instead of on the heap.
+/// This makes it useful for small partitions that are created often.
+/// The length is fixed so there are no methods that add or remove values.
+///
+/// This is also available when the default `std` feature is disabled.
+/// The crate is `no_std` then and a `PartitionArray<T, N>` needs no allocator.
+///
+/// Unlike the `PartitionVec<T>` this exposes the `find` method because the representative
+/// is useful as a cheap key for small fixed size problems.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::PartitionArray;
+///
+/// let mut partition_array = PartitionArray::new(['a', 'b', 'c', 'd']);
+/// partition_array.union(1, 2);
+/// partition_array.union(2, 3);
+///
+/// assert!(partition_array.same_set(1, 3));
+///
+/// for (index, &value) in partition_array.set(1) {
+///     assert!(index >= 1);
+///     assert!(index <= 3);
+///     assert!(value != 'a');
+/// }
+///
+/// // Debugging shows a label for each set, counting up from the first element.
+/// assert!(format!("{partition_array:?}") == "['a' => 0, 'b' => 1, 'c' => 1, 'd' => 1]");
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+pub struct PartitionArray<T, const N: usize> {
+    /// Each index has a value.
+    data: [T; N],
+    /// The metadata for each value.
+    meta: [Metadata; N],
+}
+
+/// A [`PartitionArray<T, N>`] without values that only partitions the indices `0..N`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::partition_array::PartitionArrayIndices;
+///
+/// let mut indices = PartitionArrayIndices::<5>::default();
+/// indices.union(0, 4);
+///
+/// assert!(indices.same_set(4, 0));
+/// assert!(indices.amount_of_sets() == 4);
+/// ```
+///
+/// [`PartitionArray<T, N>`]: struct.PartitionArray.html
+pub type PartitionArrayIndices<const N: usize> = PartitionArray<(), N>;
+
+impl<T, const N: usize> PartitionArray<T, N> {
+    /// Constructs a new `PartitionArray<T, N>` where every value has its own set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let partition_array = PartitionArray::new([1, 2, 3]);
+    ///
+    /// assert!(partition_array.len() == 3);
+    /// assert!(partition_array.is_singleton(0));
+    /// assert!(partition_array.is_singleton(2));
+    /// ```
+    #[must_use]
+    pub fn new(data: [T; N]) -> Self {
+        Self {
+            data,
+            meta: core::array::from_fn(Metadata::new),
+        }
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index`.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// partition_array.union(1, 2);
+    /// partition_array.union(2, 3);
+    ///
+    /// assert!(partition_array.len_of_set(0) == 1);
+    /// assert!(partition_array.len_of_set(1) == 3);
+    /// ```
+    pub fn union(&mut self, first_index: usize, second_index: usize) {
+        union_roots_in(&self.meta, first_index, second_index);
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` are out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// partition_array.union(1, 3);
+    /// partition_array.union(0, 1);
+    ///
+    /// assert!(partition_array.same_set(0, 3));
+    /// assert!(!partition_array.same_set(1, 2));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        self.find(first_index) == self.find(second_index)
+    }
+
+    /// Returns the index of the representative of the set that `index` belongs to.
+    ///
+    /// Two indices share a set exactly when they have the same representative.
+    /// The representative of a set can change when it is joined with another set or
+    /// when its representative is made a singleton.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// assert!(partition_array.find(2) == 2);
+    ///
+    /// partition_array.union(0, 2);
+    /// let root = partition_array.find(0);
+    /// assert!(root == 0 || root == 2);
+    /// assert!(partition_array.find(2) == root);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn find(&self, index: usize) -> usize {
+        find_in(&self.meta, index)
+    }
+
+    /// Removes the element at `index` from its set and gives it its own set.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// partition_array.union(1, 2);
+    /// partition_array.union(2, 3);
+    /// partition_array.make_singleton(2);
+    ///
+    /// assert!(partition_array.is_singleton(2));
+    /// assert!(partition_array.same_set(1, 3));
+    /// ```
+    pub fn make_singleton(&mut self, index: usize) {
+        make_singleton_in(&self.meta, index);
+    }
+
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// partition_array.union(1, 3);
+    ///
+    /// assert!(partition_array.is_singleton(0));
+    /// assert!(!partition_array.is_singleton(1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: usize) -> bool {
+        self.meta[index].link() == index
+    }
+
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    ///
+    /// partition_array.union(0, 3);
+    ///
+    /// assert!(partition_array.len_of_set(3) == 2);
+    /// assert!(partition_array.len_of_set(1) == 1);
+    /// ```
+    #[must_use]
+    pub fn len_of_set(&self, index: usize) -> usize {
+        self.set(index).count()
+    }
+
+    /// Returns the amount of sets.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([(); 4]);
+    /// assert!(partition_array.amount_of_sets() == 4);
+    ///
+    /// partition_array.union(0, 3);
+    /// partition_array.union(3, 1);
+    /// assert!(partition_array.amount_of_sets() == 2);
+    ///
+    /// assert!(PartitionArray::new([(); 0]).amount_of_sets() == 0);
+    /// ```
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        (0..N)
+            .filter(|&index| find_in(&self.meta, index) == index)
+            .count()
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &value)`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new(['a', 'b', 'c', 'd']);
+    ///
+    /// partition_array.union(0, 2);
+    ///
+    /// let mut set: Vec<_> = partition_array.set(2).collect();
+    /// set.sort_unstable();
+    /// assert!(set == [(0, &'a'), (2, &'c')]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn set(&self, index: usize) -> Set<'_, T> {
+        Set::new(&self.data, &self.meta, find_final_in(&self.meta, index))
+    }
+
+    /// Returns the values as an array, dropping the sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionArray;
+    ///
+    /// let mut partition_array = PartitionArray::new([1, 2, 3]);
+    /// partition_array.union(0, 1);
+    ///
+    /// assert!(partition_array.into_inner() == [1, 2, 3]);
+    /// ```
+    #[must_use]
+    pub fn into_inner(self) -> [T; N] {
+        self.data
+    }
+}
+
+impl<T, const N: usize> Default for PartitionArray<T, N>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(core::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> Clone for PartitionArray<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            meta: self.meta.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> core::fmt::Debug for PartitionArray<T, N>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt_labeled(&self.data, &self.meta, &mut [None; N], formatter)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for PartitionArray<T, N> {
+    fn from(data: [T; N]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<T, const N: usize> ops::Deref for PartitionArray<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for PartitionArray<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T, const N: usize> UnionFind for PartitionArray<T, N> {
+    #[inline]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn union(&mut self, first_index: usize, second_index: usize) -> bool {
+        union_roots_in(&self.meta, first_index, second_index).is_some()
+    }
+
+    #[inline]
+    fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        PartitionArray::same_set(self, first_index, second_index)
+    }
+
+    #[inline]
+    fn make_singleton(&mut self, index: usize) {
+        PartitionArray::make_singleton(self, index);
+    }
+
+    #[inline]
+    fn amount_of_sets(&self) -> usize {
+        PartitionArray::amount_of_sets(self)
+    }
+}
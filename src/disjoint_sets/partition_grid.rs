@@ -0,0 +1,487 @@
+//! A [disjoint-sets/union-find] implementation of a 2D grid partitioned in regions.
+//!
+//! See [`PartitionGrid<T>`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`PartitionGrid<T>`]: struct.PartitionGrid.html
+
+use {
+    crate::disjoint_sets::partition_vec::{PartitionVec, Set},
+    std::iter::FusedIterator,
+};
+
+/// Which cells of a grid are neighbors of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// The cells above, below, left and right of a cell are its neighbors.
+    Four,
+    /// The four cells of `Four` and the four diagonal cells are the neighbors of a cell.
+    Eight,
+}
+
+impl Connectivity {
+    /// The offsets of the neighbors of a cell.
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            Connectivity::Eight => &[
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ],
+        }
+    }
+}
+
+/// A [disjoint-sets/union-find] implementation of a 2D grid of values partitioned in regions.
+///
+/// The cells are stored row by row in a [`PartitionVec<T>`], the cell at `(x, y)` has index
+/// `y * width + x`.
+/// Initially every cell is its own region and regions can be joined with the neighbors of a
+/// cell or all at once with [`label_regions`] to find the connected regions of an image or map.
+///
+/// Methods that take coordinates panic if these are outside of the grid,
+/// except for [`get`] and [`get_mut`] which return `None`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::partition_grid::{Connectivity, PartitionGrid};
+///
+/// let mut grid = PartitionGrid::from_rows(&[
+///     &[1, 1, 0],
+///     &[0, 1, 0],
+///     &[1, 0, 1],
+/// ]);
+///
+/// grid.label_regions(|first, second| first == second, Connectivity::Four);
+/// assert!(grid.amount_of_regions() == 6);
+/// assert!(grid.same_region((0, 0), (1, 1)));
+/// assert!(!grid.same_region((2, 0), (2, 2)));
+///
+/// let mut grid = PartitionGrid::from_rows(&[
+///     &[1, 1, 0],
+///     &[0, 1, 0],
+///     &[1, 0, 1],
+/// ]);
+///
+/// grid.label_regions(|first, second| first == second, Connectivity::Eight);
+/// assert!(grid.amount_of_regions() == 2);
+/// assert!(grid.same_region((0, 2), (2, 2)));
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+/// [`label_regions`]: struct.PartitionGrid.html#method.label_regions
+/// [`get`]: struct.PartitionGrid.html#method.get
+/// [`get_mut`]: struct.PartitionGrid.html#method.get_mut
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartitionGrid<T> {
+    /// The amount of cells in every row.
+    width: usize,
+    /// The amount of rows.
+    height: usize,
+    /// The cells row by row.
+    cells: PartitionVec<T>,
+}
+
+impl<T> PartitionGrid<T> {
+    /// Constructs a new `PartitionGrid<T>` of `width` by `height` cells that all have the
+    /// value `fill` and their own region.
+    ///
+    /// # Panics
+    ///
+    /// If the amount of cells overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid = partitions::partition_grid::PartitionGrid::new(3, 2, '.');
+    ///
+    /// assert!(grid.width() == 3);
+    /// assert!(grid.height() == 2);
+    /// assert!(grid.get(2, 1) == Some(&'.'));
+    /// assert!(grid.amount_of_regions() == 6);
+    /// ```
+    #[must_use]
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let len = width
+            .checked_mul(height)
+            .expect("The amount of cells of the grid overflows a usize.");
+
+        Self {
+            width,
+            height,
+            cells: PartitionVec::from(vec![fill; len]),
+        }
+    }
+
+    /// Constructs a new `PartitionGrid<T>` with the values of `rows`, every cell has its own
+    /// region.
+    ///
+    /// # Panics
+    ///
+    /// If the rows do not all have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid = partitions::partition_grid::PartitionGrid::from_rows(&[&['a', 'b'], &['c', 'd']]);
+    ///
+    /// assert!(grid.get(1, 0) == Some(&'b'));
+    /// assert!(grid.get(0, 1) == Some(&'c'));
+    /// ```
+    #[must_use]
+    pub fn from_rows<R>(rows: &[R]) -> Self
+    where
+        R: AsRef<[T]>,
+        T: Clone,
+    {
+        let width = rows.first().map_or(0, |row| row.as_ref().len());
+        let mut values = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            assert!(
+                row.as_ref().len() == width,
+                "The rows of the grid have different lengths {} and {}.",
+                width,
+                row.as_ref().len(),
+            );
+            values.extend_from_slice(row.as_ref());
+        }
+
+        Self {
+            width,
+            height: rows.len(),
+            cells: PartitionVec::from(values),
+        }
+    }
+
+    /// Returns the amount of cells in every row.
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the amount of rows.
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if `(x, y)` is a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid = partitions::partition_grid::PartitionGrid::new(3, 2, ());
+    ///
+    /// assert!(grid.contains(2, 1));
+    /// assert!(!grid.contains(3, 1));
+    /// assert!(!grid.contains(0, 2));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Returns the index of `(x, y)` in the [`PartitionVec<T>`] of the cells or `None` if it is
+    /// not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid = partitions::partition_grid::PartitionGrid::new(3, 2, ());
+    ///
+    /// assert!(grid.index(1, 1) == Some(4));
+    /// assert!(grid.index(3, 0) == None);
+    /// ```
+    ///
+    /// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+    #[inline]
+    #[must_use]
+    pub fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if self.contains(x, y) {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value at `(x, y)` or `None` if it is not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid = partitions::partition_grid::PartitionGrid::from_rows(&[&[1, 2], &[3, 4]]);
+    ///
+    /// assert!(grid.get(1, 1) == Some(&4));
+    /// assert!(grid.get(2, 1) == None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        let index = self.index(x, y)?;
+
+        Some(&self.cells[index])
+    }
+
+    /// Returns a mutable reference to the value at `(x, y)` or `None` if it is not a cell of
+    /// the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut grid = partitions::partition_grid::PartitionGrid::new(2, 2, 0);
+    ///
+    /// if let Some(value) = grid.get_mut(0, 1) {
+    ///     *value = 5;
+    /// }
+    /// assert!(grid.get(0, 1) == Some(&5));
+    /// assert!(grid.get_mut(0, 2).is_none());
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        let index = self.index(x, y)?;
+
+        Some(&mut self.cells[index])
+    }
+
+    /// Joins the regions of the cells `first` and `second`, given as `(x, y)`.
+    ///
+    /// The cells do not have to be neighbors.
+    ///
+    /// # Panics
+    ///
+    /// If `first` or `second` is not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut grid = partitions::partition_grid::PartitionGrid::new(3, 3, ());
+    /// grid.union((0, 0), (2, 2));
+    ///
+    /// assert!(grid.same_region((2, 2), (0, 0)));
+    /// ```
+    pub fn union(&mut self, first: (usize, usize), second: (usize, usize)) {
+        let first = self.expect_index(first);
+        let second = self.expect_index(second);
+
+        self.cells.union(first, second);
+    }
+
+    /// Joins the region of the cell at `(x, y)` with the regions of all its neighbors.
+    ///
+    /// Neighbors outside of the grid are skipped.
+    ///
+    /// # Panics
+    ///
+    /// If `(x, y)` is not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::partition_grid::{Connectivity, PartitionGrid};
+    ///
+    /// let mut grid = PartitionGrid::new(3, 3, ());
+    /// grid.union_with_neighbors(0, 0, Connectivity::Four);
+    /// assert!(grid.region(0, 0).count() == 3);
+    ///
+    /// grid.union_with_neighbors(1, 1, Connectivity::Eight);
+    /// assert!(grid.amount_of_regions() == 1);
+    /// ```
+    pub fn union_with_neighbors(&mut self, x: usize, y: usize, connectivity: Connectivity) {
+        let index = self.expect_index((x, y));
+
+        for &(dx, dy) in connectivity.offsets() {
+            if let Some(neighbor) = self.neighbor(x, y, dx, dy) {
+                self.cells.union(index, neighbor);
+            }
+        }
+    }
+
+    /// Joins the regions of all neighboring cells for which `same` returns `true`.
+    ///
+    /// Afterwards two cells share a region if they can be reached from each other with steps
+    /// between such neighbors, or if they shared a region before.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `n` is the amount of cells and
+    /// `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::partition_grid::{Connectivity, PartitionGrid};
+    ///
+    /// let mut grid = PartitionGrid::from_rows(&[
+    ///     b"#.#",
+    ///     b"#.#",
+    ///     b"###",
+    /// ]);
+    ///
+    /// grid.label_regions(|first, second| first == second, Connectivity::Four);
+    /// assert!(grid.amount_of_regions() == 2);
+    /// assert!(grid.region(0, 0).count() == 7);
+    /// ```
+    pub fn label_regions<F>(&mut self, same: F, connectivity: Connectivity)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        // Every pair of neighbors is visited once from the cell that is first in row order.
+        let offsets: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(1, 0), (0, 1)],
+            Connectivity::Eight => &[(1, 0), (-1, 1), (0, 1), (1, 1)],
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                for &(dx, dy) in offsets {
+                    if let Some(neighbor) = self.neighbor(x, y, dx, dy) {
+                        if same(&self.cells[index], &self.cells[neighbor]) {
+                            self.cells.union(index, neighbor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the cells `first` and `second`, given as `(x, y)`, share a region.
+    ///
+    /// # Panics
+    ///
+    /// If `first` or `second` is not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut grid = partitions::partition_grid::PartitionGrid::new(2, 1, ());
+    ///
+    /// assert!(!grid.same_region((0, 0), (1, 0)));
+    /// grid.union((0, 0), (1, 0));
+    /// assert!(grid.same_region((0, 0), (1, 0)));
+    /// ```
+    #[must_use]
+    pub fn same_region(&self, first: (usize, usize), second: (usize, usize)) -> bool {
+        self.cells
+            .same_set(self.expect_index(first), self.expect_index(second))
+    }
+
+    /// Returns the amount of regions.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `n` is the amount of cells and
+    /// `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut grid = partitions::partition_grid::PartitionGrid::new(2, 2, ());
+    /// grid.union((0, 0), (1, 1));
+    ///
+    /// assert!(grid.amount_of_regions() == 3);
+    /// ```
+    #[must_use]
+    pub fn amount_of_regions(&self) -> usize {
+        self.cells.amount_of_sets()
+    }
+
+    /// Returns an iterator over the cells of the region of the cell at `(x, y)`.
+    ///
+    /// The iterator returned yields pairs `((x, y), &value)`.
+    /// The order the cells are returned in is not specified.
+    ///
+    /// # Panics
+    ///
+    /// If `(x, y)` is not a cell of the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut grid = partitions::partition_grid::PartitionGrid::from_rows(&[&['a', 'b'], &['c', 'd']]);
+    /// grid.union((0, 0), (1, 1));
+    ///
+    /// let mut region: Vec<_> = grid.region(1, 1).collect();
+    /// region.sort_unstable();
+    /// assert!(region == [((0, 0), &'a'), ((1, 1), &'d')]);
+    /// ```
+    #[must_use]
+    pub fn region(&self, x: usize, y: usize) -> Region<'_, T> {
+        Region {
+            set: self.cells.set(self.expect_index((x, y))),
+            width: self.width,
+        }
+    }
+
+    /// Returns the cells as a [`PartitionVec<T>`] in row order.
+    ///
+    /// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+    #[inline]
+    #[must_use]
+    pub fn as_partition_vec(&self) -> &PartitionVec<T> {
+        &self.cells
+    }
+
+    /// Converts the grid into a [`PartitionVec<T>`] with the cells in row order.
+    ///
+    /// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+    #[inline]
+    #[must_use]
+    pub fn into_partition_vec(self) -> PartitionVec<T> {
+        self.cells
+    }
+
+    /// Returns the index of `(x + dx, y + dy)` if it is a cell of the grid.
+    fn neighbor(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<usize> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+
+        self.index(x, y)
+    }
+
+    /// Returns the index of the cell `(x, y)` and panics if it is not a cell of the grid.
+    fn expect_index(&self, (x, y): (usize, usize)) -> usize {
+        match self.index(x, y) {
+            Some(index) => index,
+            None => panic!(
+                "The cell ({}, {}) is outside of the grid of {} by {} cells.",
+                x, y, self.width, self.height
+            ),
+        }
+    }
+}
+
+/// An iterator over the cells of a region in a `PartitionGrid<T>`.
+///
+/// This struct is created by the [`region`] method on [`PartitionGrid<T>`].
+/// See its documentation for more.
+///
+/// [`region`]: struct.PartitionGrid.html#method.region
+/// [`PartitionGrid<T>`]: struct.PartitionGrid.html
+#[derive(Debug)]
+pub struct Region<'a, T: 'a> {
+    set: Set<'a, T>,
+    width: usize,
+}
+
+impl<'a, T> Iterator for Region<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<((usize, usize), &'a T)> {
+        let (index, value) = self.set.next()?;
+
+        Some(((index % self.width, index / self.width), value))
+    }
+}
+
+impl<T> FusedIterator for Region<'_, T> {}
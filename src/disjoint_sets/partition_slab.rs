@@ -0,0 +1,477 @@
+//! A [disjoint-sets/union-find] implementation of a slab with stable keys partitioned in sets.
+//!
+//! See [`PartitionSlab<T>`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`PartitionSlab<T>`]: struct.PartitionSlab.html
+
+use {
+    crate::disjoint_sets::partition_vec::{PartitionVec, Set},
+    std::{collections::HashMap, fmt},
+};
+
+/// A [disjoint-sets/union-find] implementation of a slab partitioned in sets.
+///
+/// Inserting a value returns a key that stays the same until the value is removed,
+/// removing a value does not move any other value.
+/// The slot of a removed value is vacant and reused by a later insert,
+/// vacant slots are never visible through the methods of the slab.
+/// Removing a value takes `O(m)` time where `m` is the size of its set,
+/// all other values keep their sets.
+///
+/// Vacant slots keep their memory, [`compact`] moves the values together and gives them
+/// new keys to reclaim it.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::PartitionSlab;
+///
+/// let mut slab = PartitionSlab::new();
+/// let a = slab.insert('a');
+/// let b = slab.insert('b');
+/// let c = slab.insert('c');
+/// slab.union(a, c);
+/// slab.union(c, b);
+///
+/// assert!(slab.remove(c) == Some('c'));
+/// assert!(slab.get(c).is_none());
+/// assert!(slab.same_set(a, b));
+///
+/// // The vacant slot is reused.
+/// let d = slab.insert('d');
+/// assert!(d == c);
+/// assert!(slab.is_singleton(d));
+///
+/// // Debugging shows every key with its value and a label for its set.
+/// assert!(format!("{slab:?}") == "{0: 'a' => 0, 1: 'b' => 0, 2: 'd' => 1}");
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`compact`]: struct.PartitionSlab.html#method.compact
+pub struct PartitionSlab<T> {
+    /// The values with lazily removed values at the vacant slots.
+    vec: PartitionVec<T>,
+    /// The last vacant slot, every vacant slot stores the vacant slot before it.
+    last_removed: usize,
+    /// The amount of values that are not removed.
+    len: usize,
+}
+
+impl<T> PartitionSlab<T> {
+    /// Constructs a new, empty `PartitionSlab<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let slab = partitions::PartitionSlab::<()>::new();
+    ///
+    /// assert!(slab.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            vec: PartitionVec::new(),
+            last_removed: !0,
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty `PartitionSlab<T>` with room for `capacity` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let slab = partitions::PartitionSlab::<()>::with_capacity(10);
+    ///
+    /// assert!(slab.capacity() >= 10);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: PartitionVec::with_capacity(capacity),
+            last_removed: !0,
+            len: 0,
+        }
+    }
+
+    /// Returns the amount of values, not counting vacant slots.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no values.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots that can be used without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Inserts `value` as a singleton and returns its key.
+    ///
+    /// The key is the last vacant slot if there is one and otherwise a new slot.
+    ///
+    /// # Panics
+    ///
+    /// If the number of slots overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    ///
+    /// assert!(slab.insert('a') == 0);
+    /// assert!(slab.insert('b') == 1);
+    /// slab.remove(0);
+    /// assert!(slab.insert('c') == 0);
+    /// ```
+    pub fn insert(&mut self, value: T) -> usize {
+        self.len += 1;
+
+        if self.last_removed == !0 {
+            self.vec.push(value);
+            self.vec.len() - 1
+        } else {
+            let key = self.last_removed;
+            // The slot of `last_removed` is vacant so its value was moved out.
+            self.last_removed = unsafe { self.vec.insert_over_lazy_removed(key, value) };
+
+            key
+        }
+    }
+
+    /// Removes the value of `key` from its set and returns it,
+    /// or `None` if there is no value for `key`.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set of `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// let a = slab.insert('a');
+    ///
+    /// assert!(slab.remove(a) == Some('a'));
+    /// assert!(slab.remove(a) == None);
+    /// assert!(slab.remove(7) == None);
+    /// ```
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        if !self.contains(key) {
+            return None;
+        }
+
+        self.len -= 1;
+        let last_removed = self.last_removed;
+        self.last_removed = key;
+        // The slot of `key` holds a value that is moved out and marked as vacant.
+        unsafe { Some(self.vec.lazy_remove(key, last_removed)) }
+    }
+
+    /// Returns `true` if there is a value for `key`.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, key: usize) -> bool {
+        key < self.vec.len() && !self.vec.is_lazy_removed(key)
+    }
+
+    /// Returns a reference to the value of `key` or `None` if there is no value for `key`.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, key: usize) -> Option<&T> {
+        if self.contains(key) {
+            Some(&self.vec[key])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the value of `key` or `None` if there is no value
+    /// for `key`.
+    #[inline]
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        if self.contains(key) {
+            Some(&mut self.vec[key])
+        } else {
+            None
+        }
+    }
+
+    /// Joins the sets of the values of `first_key` and `second_key`.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `first_key` or `second_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// let a = slab.insert(1);
+    /// let b = slab.insert(2);
+    /// slab.union(a, b);
+    ///
+    /// assert!(slab.len_of_set(a) == 2);
+    /// ```
+    pub fn union(&mut self, first_key: usize, second_key: usize) {
+        self.assert_contains(first_key);
+        self.assert_contains(second_key);
+
+        self.vec.union(first_key, second_key);
+    }
+
+    /// Returns `true` if the values of `first_key` and `second_key` are in the same set.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `first_key` or `second_key`.
+    #[must_use]
+    pub fn same_set(&self, first_key: usize, second_key: usize) -> bool {
+        self.assert_contains(first_key);
+        self.assert_contains(second_key);
+
+        self.vec.same_set(first_key, second_key)
+    }
+
+    /// Returns `true` if the values of `first_key` and `second_key` are in different sets.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `first_key` or `second_key`.
+    #[inline]
+    #[must_use]
+    pub fn other_sets(&self, first_key: usize, second_key: usize) -> bool {
+        !self.same_set(first_key, second_key)
+    }
+
+    /// Removes the value of `key` from its set and gives it its own set.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `key`.
+    pub fn make_singleton(&mut self, key: usize) {
+        self.assert_contains(key);
+
+        self.vec.make_singleton(key);
+    }
+
+    /// Returns `true` if the value of `key` is the only value of its set.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `key`.
+    #[must_use]
+    pub fn is_singleton(&self, key: usize) -> bool {
+        self.assert_contains(key);
+
+        self.vec.is_singleton(key)
+    }
+
+    /// Returns the amount of values in the set of `key`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `key`.
+    #[must_use]
+    pub fn len_of_set(&self, key: usize) -> usize {
+        self.set(key).count()
+    }
+
+    /// Returns the amount of sets.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `n` is the amount of slots
+    /// and `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// let a = slab.insert(());
+    /// let b = slab.insert(());
+    /// slab.insert(());
+    /// slab.union(a, b);
+    /// slab.remove(a);
+    ///
+    /// assert!(slab.amount_of_sets() == 2);
+    /// ```
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        (0..self.vec.len())
+            .filter(|&key| self.contains(key) && self.vec.find(key) == key)
+            .count()
+    }
+
+    /// Returns an iterator over the values in the set of `key`.
+    ///
+    /// The iterator returned yields pairs `(key, &value)`, vacant slots are never in a set.
+    ///
+    /// # Panics
+    ///
+    /// If there is no value for `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// let a = slab.insert('a');
+    /// let b = slab.insert('b');
+    /// let c = slab.insert('c');
+    /// slab.union(a, b);
+    /// slab.union(b, c);
+    /// slab.remove(b);
+    ///
+    /// let mut set: Vec<_> = slab.set(c).collect();
+    /// set.sort_unstable();
+    /// assert!(set == [(a, &'a'), (c, &'c')]);
+    /// ```
+    #[must_use]
+    pub fn set(&self, key: usize) -> Set<'_, T> {
+        self.assert_contains(key);
+
+        self.vec.set(key)
+    }
+
+    /// Returns an iterator over all values together with their key in order of their key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// slab.insert('a');
+    /// slab.insert('b');
+    /// slab.insert('c');
+    /// slab.remove(1);
+    ///
+    /// assert!(slab.iter().collect::<Vec<_>>() == [(0, &'a'), (2, &'c')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        (0..self.vec.len())
+            .filter(move |&key| self.contains(key))
+            .map(move |key| (key, &self.vec[key]))
+    }
+
+    /// Removes all values, the memory of the slots is kept.
+    pub fn clear(&mut self) {
+        self.vec.clear_lazy_removed();
+        self.last_removed = !0;
+        self.len = 0;
+    }
+
+    /// Moves all values together in order of their key and removes the vacant slots to
+    /// reclaim their memory.
+    ///
+    /// This changes the keys of the values but keeps their sets.
+    /// Returns the new key for every old key, or `None` for the old keys of vacant slots.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `n` is the amount of slots
+    /// and `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut slab = partitions::PartitionSlab::new();
+    /// let a = slab.insert('a');
+    /// let b = slab.insert('b');
+    /// let c = slab.insert('c');
+    /// slab.union(a, c);
+    /// slab.remove(b);
+    ///
+    /// let keys = slab.compact();
+    /// assert!(keys == [Some(0), None, Some(1)]);
+    /// assert!(slab.iter().collect::<Vec<_>>() == [(0, &'a'), (1, &'c')]);
+    /// assert!(slab.same_set(0, 1));
+    /// ```
+    pub fn compact(&mut self) -> Vec<Option<usize>> {
+        let mut keys = vec![None; self.vec.len()];
+        let mut roots = Vec::with_capacity(self.len);
+        let mut values = Vec::with_capacity(self.len);
+
+        for (key, new_key) in keys.iter_mut().enumerate() {
+            if self.contains(key) {
+                *new_key = Some(values.len());
+                roots.push(self.vec.find(key));
+                // The value is moved out and the length of `vec` is set to zero below
+                // without dropping any value.
+                values.push(unsafe { std::ptr::read(&raw const self.vec[key]) });
+            }
+        }
+        unsafe {
+            self.vec.set_len(0);
+        }
+
+        let mut vec = PartitionVec::from(values);
+        for (index, root) in roots.into_iter().enumerate() {
+            if let Some(new_root) = keys[root] {
+                vec.union(index, new_root);
+            }
+        }
+
+        self.vec = vec;
+        self.last_removed = !0;
+
+        keys
+    }
+
+    /// Panics if there is no value for `key`.
+    fn assert_contains(&self, key: usize) {
+        assert!(self.contains(key), "There is no value for the key {}.", key);
+    }
+}
+
+impl<T> Default for PartitionSlab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for PartitionSlab<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut clone = Self {
+            vec: PartitionVec::with_capacity(self.vec.len()),
+            last_removed: self.last_removed,
+            len: self.len,
+        };
+        clone.vec.clone_lazy_removed_from(&self.vec);
+
+        clone
+    }
+}
+
+impl<T> fmt::Debug for PartitionSlab<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = formatter.debug_map();
+        let mut labels = HashMap::new();
+
+        for (key, value) in self.iter() {
+            let root = self.vec.find_final(key);
+            let next_label = labels.len();
+            let label = *labels.entry(root).or_insert(next_label);
+            builder.entry(&key, &format_args!("{value:?} => {label}"));
+        }
+
+        builder.finish()
+    }
+}
+
+impl<T> Drop for PartitionSlab<T> {
+    fn drop(&mut self) {
+        self.vec.clear_lazy_removed();
+    }
+}
@@ -7,17 +7,30 @@
 
 #[cfg(feature = "proptest")]
 use proptest::prelude::*;
+#[cfg(feature = "rand")]
+use rand::Rng;
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 use {
-    crate::{disjoint_sets::metadata::Metadata, extend_mut},
+    crate::disjoint_sets::{
+        forest::{
+            find_final_in, find_in, make_singleton_in, shift_down_in, shift_up_in, truncate_in,
+            union_roots_in,
+        },
+        metadata::{Metadata, MAX_LEN, MAX_RANK},
+        union_find::UnionFind,
+    },
     std::{
+        borrow::{Borrow, BorrowMut},
         cmp::Ordering,
         iter::{FromIterator, FusedIterator},
+        marker::PhantomData,
         ops,
     },
 };
 
+pub use crate::disjoint_sets::forest::Set;
+
 /// A [disjoint-sets/union-find] implementation of a vector partitioned in sets.
 ///
 /// Most methods that are defined on a `Vec` also work on a `PartitionVec`.
@@ -55,7 +68,6 @@ use {
 /// ```
 ///
 /// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
-#[derive(Clone)]
 pub struct PartitionVec<T> {
     /// Each index has a value.
     /// We store these in a separate `Vec` so we can easily dereference it to a slice.
@@ -142,12 +154,24 @@ pub struct PartitionVec<T> {
 /// [`PartitionVec`]: partition_vec/struct.PartitionVec.html
 #[macro_export]
 macro_rules! partition_vec {
+    // We count the amount of expressions given to the macro for the capacity.
+    // These rules are internal and should not be used directly.
+    (@count) => { 0usize };
+    (@count $_single: expr) => { 1usize };
+    // Even amount of expressions.
+    (@count $($first: expr, $_second: expr),*) => {
+        ($crate::partition_vec![@count $($first),*] << 1usize)
+    };
+    // Odd amount of expressions.
+    (@count $_single: expr, $($first: expr, $_second: expr),*) => {
+        ($crate::partition_vec![@count $($first),*] << 1usize) | 1
+    };
     ($elem: expr; $len: expr) => {
-        $crate::PartitionVec::from_elem($elem, $len);
+        $crate::PartitionVec::from_elem($elem, $len)
     };
     ($($elem: expr),*) => {
         {
-            let len = partitions_count_expr![$($elem),*];
+            let len = $crate::partition_vec![@count $($elem),*];
             let mut partition_vec = $crate::PartitionVec::with_capacity(len);
 
             $(
@@ -158,11 +182,11 @@ macro_rules! partition_vec {
         }
     };
     ($($elem: expr,)*) => {
-        partition_vec![$($elem),*];
+        $crate::partition_vec![$($elem),*]
     };
     ($($elem: expr => $set: expr),*) => {
         {
-            let len = partitions_count_expr![$($elem),*];
+            let len = $crate::partition_vec![@count $($elem),*];
             let mut partition_vec = $crate::PartitionVec::with_capacity(len);
             let mut map = ::std::collections::HashMap::new();
 
@@ -181,8 +205,8 @@ macro_rules! partition_vec {
         }
     };
     ($($elem: expr => $set: expr,)*) => {
-        partition_vec![$($elem => $set),*];
-    }
+        $crate::partition_vec![$($elem => $set),*]
+    };
 }
 
 impl<T> PartitionVec<T> {
@@ -240,6 +264,130 @@ impl<T> PartitionVec<T> {
         }
     }
 
+    /// Constructs a new `PartitionVec<T>` where each range of indices forms a set.
+    ///
+    /// The ranges have to be given in order and have to cover all indices from `0` up to the
+    /// length without gaps or overlap, so each range starts where the previous range ended.
+    /// Each element will be a clone of `value`.
+    ///
+    /// This will take `O(n)` time where `n` is the total length.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges do not start at `0`, overlap or leave a gap between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionVec;
+    ///
+    /// let partition_vec = PartitionVec::from_ranges('x', &[0 .. 3, 3 .. 5, 5 .. 6]);
+    ///
+    /// assert!(partition_vec.len() == 6);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// assert!(partition_vec.same_set(0, 2));
+    /// assert!(partition_vec.same_set(3, 4));
+    /// assert!(partition_vec.other_sets(2, 3));
+    /// assert!(partition_vec.is_singleton(5));
+    /// ```
+    ///
+    /// ```should_panic
+    /// use partitions::PartitionVec;
+    ///
+    /// // The second range overlaps the first.
+    /// let partition_vec = PartitionVec::from_ranges((), &[0 .. 3, 2 .. 5]);
+    /// ```
+    #[must_use]
+    pub fn from_ranges(value: T, sets: &[ops::Range<usize>]) -> Self
+    where
+        T: Clone,
+    {
+        let mut len = 0;
+        for set in sets {
+            assert!(
+                set.start == len,
+                "The range {:?} does not start at {}, where the previous range ended.",
+                set,
+                len,
+            );
+            assert!(set.start <= set.end, "The range {:?} is decreasing.", set);
+
+            len = set.end;
+        }
+
+        let meta = (0..len).map(Metadata::new).collect::<Vec<_>>();
+        for set in sets {
+            if set.end - set.start > 1 {
+                // We make the first element the root and link every element to the next.
+                for i in set.clone() {
+                    meta[i].set_parent(set.start);
+                    meta[i].set_link(i + 1);
+                }
+                meta[set.end - 1].set_link(set.start);
+                meta[set.start].set_rank(1);
+            }
+        }
+
+        Self {
+            data: vec![value; len],
+            meta,
+        }
+    }
+
+    /// Constructs a new `PartitionVec<T>` from an iterator of pairs `(value, key)`.
+    ///
+    /// The values are stored in the order of the iterator and values with the same key
+    /// will be in the same set.
+    /// This is the equivalent of the `value => key` form of the `partition_vec!` macro.
+    /// The keys will only be used during construction and will not be stored further.
+    ///
+    /// This will take `O(n α(n))` time where `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionVec;
+    ///
+    /// let words = vec!["apple", "avocado", "banana", "cherry", "blueberry"];
+    /// let partition_vec = PartitionVec::from_iter_with_sets(
+    ///     words.into_iter().map(|word| (word, word.chars().next())),
+    /// );
+    ///
+    /// assert!(partition_vec.len() == 5);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// assert!(partition_vec.same_set(0, 1));
+    /// assert!(partition_vec.same_set(2, 4));
+    /// assert!(partition_vec.is_singleton(3));
+    /// ```
+    pub fn from_iter_with_sets<K, I>(iter: I) -> Self
+    where
+        K: Eq + std::hash::Hash,
+        I: IntoIterator<Item = (T, K)>,
+    {
+        use std::collections::hash_map;
+
+        let iter = iter.into_iter();
+        let mut partition_vec = Self::with_capacity(iter.size_hint().0);
+
+        // We map a `key` to an `index` of that set.
+        let mut map = hash_map::HashMap::new();
+
+        for (index, (value, key)) in iter.enumerate() {
+            partition_vec.push(value);
+
+            match map.entry(key) {
+                hash_map::Entry::Occupied(occupied) => {
+                    partition_vec.union(index, *occupied.get());
+                }
+                hash_map::Entry::Vacant(vacant) => {
+                    vacant.insert(index);
+                }
+            }
+        }
+
+        partition_vec
+    }
+
     /// Joins the sets of the `first_index` and the `second_index`.
     ///
     /// This method will be executed in `O(α(n))` time where `α` is the inverse
@@ -283,169 +431,189 @@ impl<T> PartitionVec<T> {
     /// # }
     /// ```
     pub fn union(&mut self, first_index: usize, second_index: usize) {
-        let i = self.find(first_index);
-        let j = self.find(second_index);
-
-        if i == j {
-            return;
-        }
-
-        // We swap the values of the links.
-        let link_i = self.meta[i].link();
-        let link_j = self.meta[j].link();
-        self.meta[i].set_link(link_j);
-        self.meta[j].set_link(link_i);
-
-        // We add to the tree with the highest rank.
-        match Ord::cmp(&self.meta[i].rank(), &self.meta[j].rank()) {
-            Ordering::Less => {
-                self.meta[i].set_parent(j);
-            }
-            Ordering::Equal => {
-                // We add the first tree to the second tree.
-                self.meta[i].set_parent(j);
-                // The second tree becomes larger.
-                self.meta[j].set_rank(self.meta[j].rank() + 1);
-            }
-            Ordering::Greater => {
-                self.meta[j].set_parent(i);
-            }
-        }
+        self.union_roots(first_index, second_index);
     }
 
-    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    /// Joins the sets of the `first_index` and the `second_index`
+    /// and returns the roots the two sets had before they were joined.
+    ///
+    /// The roots are returned in the same order as the indices they were found from.
+    /// If both indices were already in the same set nothing changes and `None` is returned.
+    /// The root of the joined set is one of the two returned roots.
     ///
     /// This method will be executed in `O(α(n))` time where `α` is the inverse
     /// Ackermann function.
     ///
     /// # Panics
     ///
-    /// If `first_index` or `second_index` are out of bounds.
+    /// If `first_index` or `second_index` is out of bounds.
     ///
     /// # Examples
     ///
     /// ```
     /// # #[macro_use]
     /// # extern crate partitions;
+    /// #
     /// # fn main() {
     /// let mut partition_vec = partition_vec![(); 4];
     ///
-    /// partition_vec.union(1, 3);
-    /// partition_vec.union(0, 1);
+    /// // Singletons are their own roots.
+    /// assert!(partition_vec.union_roots(2, 0) == Some((2, 0)));
+    /// assert!(partition_vec.union_roots(0, 2) == None);
     ///
-    /// assert!(partition_vec.same_set(0, 1));
-    /// assert!(!partition_vec.same_set(0, 2));
-    /// assert!(partition_vec.same_set(0, 3));
-    /// assert!(!partition_vec.same_set(1, 2));
-    /// assert!(partition_vec.same_set(1, 3));
-    /// assert!(!partition_vec.same_set(2, 3));
+    /// // The set of 2 now has 0 as its root.
+    /// assert!(partition_vec.union_roots(3, 2) == Some((3, 0)));
+    /// assert!(partition_vec.len_of_set(0) == 3);
     /// # }
     /// ```
-    #[inline]
-    #[must_use]
-    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
-        self.find(first_index) == self.find(second_index)
+    pub fn union_roots(
+        &mut self,
+        first_index: usize,
+        second_index: usize,
+    ) -> Option<(usize, usize)> {
+        union_roots_in(&self.meta, first_index, second_index)
     }
 
-    /// Returns `true` if `first_index` and `second_index` are in different sets.
+    /// Joins the sets of `keep` and `absorb` so that the representative of the set of `keep`
+    /// becomes the representative of the joined set.
+    ///
+    /// This allows storing data for a set at its representative with [`Root`] indexing
+    /// while knowing which value survives a union.
+    /// If the absorbed tree has the same or a higher rank the rank of the kept root is raised
+    /// above it, so trees can become higher than with `union` when the larger set is absorbed.
+    /// When the rank would become too large to store, every member of the joined set is
+    /// made a direct child of the representative instead.
+    /// If both indices are already in the same set nothing changes.
     ///
     /// This method will be executed in `O(α(n))` time where `α` is the inverse
-    /// Ackermann function.
+    /// Ackermann function as long as the larger set is kept most of the time,
+    /// absorbing larger sets can make later calls slower.
     ///
     /// # Panics
     ///
-    /// If `first_index` or `second_index` are out of bounds.
+    /// If `keep` or `absorb` is out of bounds.
     ///
     /// # Examples
     ///
     /// ```
     /// # #[macro_use]
     /// # extern crate partitions;
+    /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![(); 4];
+    /// use partitions::partition_vec::Root;
     ///
-    /// partition_vec.union(1, 3);
+    /// let mut partition_vec = partition_vec![1, 2, 3, 4];
     /// partition_vec.union(0, 1);
     ///
-    /// assert!(!partition_vec.other_sets(0, 1));
-    /// assert!(partition_vec.other_sets(0, 2));
-    /// assert!(!partition_vec.other_sets(0, 3));
-    /// assert!(partition_vec.other_sets(1, 2));
-    /// assert!(!partition_vec.other_sets(1, 3));
-    /// assert!(partition_vec.other_sets(2, 3));
+    /// // The representative of 2 is 2 itself and it stays the representative.
+    /// partition_vec.union_prefer(2, 0);
+    /// assert!(partition_vec[Root(1)] == 3);
+    /// assert!(partition_vec.len_of_set(2) == 3);
+    ///
+    /// partition_vec.union_prefer(3, 1);
+    /// assert!(partition_vec[Root(0)] == 4);
     /// # }
     /// ```
-    #[inline]
-    #[must_use]
-    pub fn other_sets(&self, first_index: usize, second_index: usize) -> bool {
-        self.find(first_index) != self.find(second_index)
+    ///
+    /// [`Root`]: struct.Root.html
+    pub fn union_prefer(&mut self, keep: usize, absorb: usize) {
+        let i = self.find(keep);
+        let j = self.find(absorb);
+
+        if i == j {
+            return;
+        }
+
+        // We swap the values of the links.
+        let link_i = self.meta[i].link();
+        let link_j = self.meta[j].link();
+        self.meta[i].set_link(link_j);
+        self.meta[j].set_link(link_i);
+
+        self.meta[j].set_parent(i);
+        let rank = self.meta[j].rank() + 1;
+        if rank > MAX_RANK {
+            // The rank can not be raised any further so we flatten the whole tree instead.
+            let mut current = i;
+            loop {
+                self.meta[current].set_parent(i);
+                self.meta[current].set_rank(0);
+
+                current = self.meta[current].link();
+                if current == i {
+                    break;
+                }
+            }
+            self.meta[i].set_rank(1);
+        } else if self.meta[i].rank() < rank {
+            self.meta[i].set_rank(rank);
+        }
     }
 
-    /// Will remove `index` from its set while leaving the other members in it.
+    /// Joins the sets of the `first_index` and the `second_index` and lets `prefer` decide
+    /// which representative is kept.
     ///
-    /// After this `index` will be the only element of its set.
-    /// This won't change the `PartitionVec<T>` if `index` is already the only element.
-    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
+    /// The function `prefer` is called with the representatives of both sets, in the order of
+    /// the arguments, and the greater of the two becomes the representative of the joined set.
+    /// If they are equal the ranks decide like with `union`.
+    /// If both indices are already in the same set nothing changes and `prefer` is not called.
     ///
-    /// # Examples
+    /// This overrides the choice by rank, so like [`union_prefer`] the trees can become higher
+    /// than with `union` and the `O(α(n))` bound is lost when `prefer` often keeps the
+    /// representative of the smaller set.
     ///
-    /// ```
-    /// # #[macro_use]
-    /// # extern crate partitions;
-    /// #
-    /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     () => 'a',
-    ///     () => 'a',
-    ///     () => 'a',
-    ///     () => 'b',
-    /// ];
+    /// # Panics
     ///
-    /// // 0, 1, and 2 share a set.
-    /// assert!(partition_vec.len_of_set(0) == 3);
-    /// assert!(partition_vec.len_of_set(1) == 3);
-    /// assert!(partition_vec.len_of_set(2) == 3);
-    /// assert!(partition_vec.len_of_set(3) == 1);
+    /// If `first_index` or `second_index` is out of bounds.
     ///
-    /// partition_vec.make_singleton(2);
+    /// # Examples
     ///
-    /// // Now 2 has its own set and 1, and 2 still share a set.
-    /// assert!(partition_vec.len_of_set(0) == 2);
-    /// assert!(partition_vec.len_of_set(1) == 2);
-    /// assert!(partition_vec.len_of_set(2) == 1);
-    /// assert!(partition_vec.len_of_set(3) == 1);
-    /// # }
     /// ```
-    pub fn make_singleton(&mut self, index: usize) {
-        let mut current = self.meta[index].link();
-
-        if current != index {
-            // We make this the new root.
-            let root = current;
-            self.meta[root].set_rank(1);
-
-            // All parents except for the last are updated.
-            while self.meta[current].link() != index {
-                self.meta[current].set_parent(root);
-
-                current = self.meta[current].link();
-            }
+    /// // The degree of every vertex of a graph.
+    /// let degrees = [1, 3, 2, 5];
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 4]);
+    ///
+    /// let by_degree = |first: usize, second: usize| degrees[first].cmp(&degrees[second]);
+    /// partition_vec.union_by_external_rank(0, 2, by_degree);
+    /// assert!(partition_vec.parent_chain(0).last() == Some(&2));
+    ///
+    /// partition_vec.union_by_external_rank(0, 1, by_degree);
+    /// partition_vec.union_by_external_rank(3, 2, by_degree);
+    /// assert!(partition_vec.len_of_set(0) == 4);
+    /// assert!(partition_vec.parent_chain(2).last() == Some(&3));
+    /// ```
+    ///
+    /// [`union_prefer`]: struct.PartitionVec.html#method.union_prefer
+    pub fn union_by_external_rank<F>(&mut self, first_index: usize, second_index: usize, prefer: F)
+    where
+        F: Fn(usize, usize) -> Ordering,
+    {
+        let i = self.find(first_index);
+        let j = self.find(second_index);
 
-            // We change the last parent and link.
-            self.meta[current].set_parent(root);
-            self.meta[current].set_link(root);
+        if i == j {
+            return;
         }
 
-        self.meta[index] = Metadata::new(index);
+        match prefer(i, j) {
+            Ordering::Greater => self.union_prefer(i, j),
+            Ordering::Less => self.union_prefer(j, i),
+            Ordering::Equal => self.union(i, j),
+        }
     }
 
-    /// Returns `true` if `index` is the only element of its set.
+    /// Joins the sets of the `first_index` and the `second_index` if both are in bounds.
     ///
-    /// This will be done in `O(1)` time.
+    /// Returns `Ok(true)` if two sets were joined and `Ok(false)` if both indices were already
+    /// in the same set.
     ///
-    /// # Panics
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
     ///
-    /// If `index` is out of bounds.
+    /// # Errors
+    ///
+    /// If `first_index` or `second_index` is out of bounds an error containing that index
+    /// is returned and nothing changes, the first index is checked first.
     ///
     /// # Examples
     ///
@@ -454,29 +622,51 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![(); 4];
+    /// use partitions::partition_vec::IndexOutOfBounds;
     ///
-    /// partition_vec.union(1, 3);
+    /// let mut partition_vec = partition_vec![(); 3];
     ///
-    /// assert!(partition_vec.is_singleton(0));
-    /// assert!(!partition_vec.is_singleton(1));
-    /// assert!(partition_vec.is_singleton(2));
-    /// assert!(!partition_vec.is_singleton(3));
+    /// assert!(partition_vec.checked_union(0, 2) == Ok(true));
+    /// assert!(partition_vec.checked_union(2, 0) == Ok(false));
+    /// assert!(partition_vec.checked_union(1, 3) == Err(IndexOutOfBounds { index: 3, len: 3 }));
+    /// assert!(partition_vec.checked_union(5, 3) == Err(IndexOutOfBounds { index: 5, len: 3 }));
+    ///
+    /// assert!(partition_vec.is_singleton(1));
     /// # }
     /// ```
-    #[inline]
-    #[must_use]
-    pub fn is_singleton(&self, index: usize) -> bool {
-        self.meta[index].link() == index
+    pub fn checked_union(
+        &mut self,
+        first_index: usize,
+        second_index: usize,
+    ) -> Result<bool, IndexOutOfBounds> {
+        for &index in &[first_index, second_index] {
+            if !self.is_valid_index(index) {
+                return Err(IndexOutOfBounds {
+                    index,
+                    len: self.len(),
+                });
+            }
+        }
+
+        Ok(self.union_roots(first_index, second_index).is_some())
     }
 
-    /// Returns the amount of elements in the set that `index` belongs to.
+    /// Joins the sets of all indices in `range`.
     ///
-    /// This will be done in `O(m)` time where `m` is the size of the set that `index` belongs to.
+    /// The indices in `range` that are still in their own set are linked together directly
+    /// under the first of them without looking up any roots.
+    /// The sets of the other indices are then joined to this set with `union`, skipping
+    /// indices that are already known to be in it.
+    /// Empty ranges and ranges of a single index do nothing.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the length of `range`
+    /// if all indices are singletons, and in `O(m + k α(n))` time otherwise where `k` is the
+    /// amount of indices in `range` that were not singletons.
     ///
     /// # Panics
     ///
-    /// If `index` is out of bounds.
+    /// If the start of `range` is above its end or if the end is above the length of the
+    /// `PartitionVec<T>`.
     ///
     /// # Examples
     ///
@@ -485,35 +675,78 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![true; 3];
+    /// let mut partition_vec = partition_vec![(); 8];
     ///
-    /// assert!(partition_vec.len_of_set(0) == 1);
-    /// assert!(partition_vec.len_of_set(1) == 1);
-    /// assert!(partition_vec.len_of_set(2) == 1);
+    /// partition_vec.union_range(1..4);
+    /// assert!(partition_vec.len_of_set(1) == 3);
+    /// assert!(partition_vec.same_set(1, 3));
+    /// assert!(partition_vec.is_singleton(0));
+    /// assert!(partition_vec.is_singleton(4));
     ///
-    /// partition_vec.union(0, 2);
+    /// // Ranges can overlap existing sets.
+    /// partition_vec.union_range(3..=5);
+    /// assert!(partition_vec.len_of_set(5) == 5);
     ///
-    /// assert!(partition_vec.len_of_set(0) == 2);
-    /// assert!(partition_vec.len_of_set(1) == 1);
-    /// assert!(partition_vec.len_of_set(2) == 2);
+    /// partition_vec.union_range(6..6);
+    /// partition_vec.union_range(7..);
+    /// assert!(partition_vec.amount_of_sets() == 4);
     /// # }
     /// ```
-    #[must_use]
-    pub fn len_of_set(&self, index: usize) -> usize {
-        let mut current = self.meta[index].link();
-        let mut count = 1;
+    pub fn union_range<R>(&mut self, range: R)
+    where
+        R: ops::RangeBounds<usize>,
+    {
+        let ops::Range { start, end } = self.index_range(range);
+        if end - start < 2 {
+            return;
+        }
 
-        while current != index {
-            current = self.meta[current].link();
-            count += 1;
+        // We link all singletons into one chain with the first singleton as its root.
+        // A singleton can keep the rank it had as a root of a larger set so the other
+        // singletons become leaves with a rank of 0.
+        let mut chain: Option<(usize, usize)> = None;
+        for i in start..end {
+            if self.is_singleton(i) {
+                match chain {
+                    None => chain = Some((i, i)),
+                    Some((first, last)) => {
+                        self.meta[i].set_parent(first);
+                        self.meta[i].set_rank(0);
+                        self.meta[last].set_link(i);
+                        chain = Some((first, i));
+                    }
+                }
+            }
         }
 
-        count
+        let first = match chain {
+            Some((first, last)) => {
+                self.meta[last].set_link(first);
+                if first != last && self.meta[first].rank() == 0 {
+                    self.meta[first].set_rank(1);
+                }
+                first
+            }
+            None => start,
+        };
+
+        // Every index with `first` as its parent is already in its set,
+        // so this skips the whole chain without looking up any roots.
+        for i in start..end {
+            if i != first && self.meta[i].parent() != first {
+                self.union(first, i);
+            }
+        }
     }
 
-    /// Returns the amount of sets in the `PartitionVec<T>`.
+    /// Joins the sets of every index `i` and `i + 1` for which `pred` returns `true`
+    /// when given the values at `i` and `i + 1`, and returns the amount of sets that were joined.
     ///
-    /// This method will be executed in `O(n α(n))` where `α` is the inverse Ackermann function.
+    /// Pairs of indices that were already in the same set are not counted.
+    /// The values are visited once from the front to the back without allocating.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
     ///
     /// # Examples
     ///
@@ -522,178 +755,246 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let partition_vec = partition_vec![
-    ///     8 => 0,
-    ///     3 => 1,
-    ///     4 => 0,
-    ///     3 => 1,
-    ///     7 => 2,
-    /// ];
+    /// let mut partition_vec = partition_vec![1, 1, 2, 2, 2, 1, 3];
     ///
-    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// assert!(partition_vec.union_adjacent_if(|first, second| first == second) == 3);
+    /// assert!(partition_vec.amount_of_sets() == 4);
+    /// assert!(partition_vec.same_set(0, 1));
+    /// assert!(partition_vec.same_set(2, 4));
+    /// assert!(!partition_vec.same_set(1, 5));
+    ///
+    /// // Doing it again joins nothing new.
+    /// assert!(partition_vec.union_adjacent_if(|first, second| first == second) == 0);
     /// # }
     /// ```
-    #[must_use]
-    pub fn amount_of_sets(&self) -> usize {
-        let mut done = bit_vec![false; self.len()];
-        let mut count = 0;
-
-        for i in 0..self.len() {
-            if !done.get(self.find(i)).unwrap() {
-                done.set(self.find(i), true);
-                count += 1;
-            }
-        }
-
-        count
+    pub fn union_adjacent_if<F>(&mut self, pred: F) -> usize
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.union_adjacent_if_in(.., pred)
     }
 
-    /// Gives the representative of the set that `index` belongs to.
+    /// Joins the sets of every index `i` and `i + 1` in `range` for which `pred` returns `true`
+    /// when given the values at `i` and `i + 1`, and returns the amount of sets that were joined.
     ///
-    /// This method will be executed in `O(α(n))` time where `α` is the inverse
-    /// Ackermann function. Each index of a set
-    /// will give the same value. To see if two indexes point to values in
-    /// the same subset compare the results of `find`.
+    /// Only pairs with both indices in `range` are given to `pred`.
+    /// Pairs of indices that were already in the same set are not counted.
     ///
-    /// This method is private to keep the representative of the set an implementation
-    /// detail, this gives greater freedom to change the representative of the set.
+    /// This method will be executed in `O(m α(n))` time where `m` is the length of `range`
+    /// and `α` is the inverse Ackermann function.
     ///
     /// # Panics
     ///
-    /// If `index` is out of bounds.
-    pub(crate) fn find(&self, index: usize) -> usize {
-        // If the node is its own parent we have found the root.
-        if self.meta[index].parent() == index {
-            index
-        } else {
-            // This method is recursive so each parent on the way to the root is updated.
-            let root = self.find(self.meta[index].parent());
-
-            // We update the parent to the root for a lower tree.
-            self.meta[index].set_parent(root);
+    /// If the start of `range` is above its end or if the end is above the length of the
+    /// `PartitionVec<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec!['a', 'a', 'a', 'b', 'b', 'b'];
+    ///
+    /// assert!(partition_vec.union_adjacent_if_in(1..5, |first, second| first == second) == 2);
+    /// assert!(partition_vec.is_singleton(0));
+    /// assert!(partition_vec.same_set(1, 2));
+    /// assert!(partition_vec.same_set(3, 4));
+    /// assert!(partition_vec.is_singleton(5));
+    /// # }
+    /// ```
+    pub fn union_adjacent_if_in<R, F>(&mut self, range: R, mut pred: F) -> usize
+    where
+        R: ops::RangeBounds<usize>,
+        F: FnMut(&T, &T) -> bool,
+    {
+        let ops::Range { start, end } = self.index_range(range);
 
-            root
+        let mut joined = 0;
+        for i in start..end.saturating_sub(1) {
+            if pred(&self.data[i], &self.data[i + 1]) && self.union_roots(i, i + 1).is_some() {
+                joined += 1;
+            }
         }
+
+        joined
     }
 
-    /// Gives the representative of the set that `index` belongs to.
-    ///
-    /// This method is slightly faster than `find` but still `O(a(n))` time.
-    /// This method wont update the parents while finding the representative and should
-    /// only be used if the parents will be updated immediately afterwards.
+    /// Converts `range` to a `Range` of indices in the `PartitionVec<T>`.
     ///
     /// # Panics
     ///
-    /// If `index` is out of bounds.
-    #[inline]
-    pub(crate) fn find_final(&self, mut index: usize) -> usize {
-        while index != self.meta[index].parent() {
-            index = self.meta[index].parent();
-        }
+    /// If the start of `range` is above its end, if the end is above the length or if `range`
+    /// includes `usize::MAX`.
+    fn index_range<R>(&self, range: R) -> ops::Range<usize>
+    where
+        R: ops::RangeBounds<usize>,
+    {
+        // An index of `usize::MAX` is always out of bounds so the range can not end after it.
+        let after = |index: usize| {
+            index.checked_add(1).unwrap_or_else(|| {
+                panic!(
+                    "The index {} is out of bounds for a length of {}.",
+                    index,
+                    self.len()
+                )
+            })
+        };
+
+        let start = match range.start_bound() {
+            ops::Bound::Included(&start) => start,
+            ops::Bound::Excluded(&start) => after(start),
+            ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ops::Bound::Included(&end) => after(end),
+            ops::Bound::Excluded(&end) => end,
+            ops::Bound::Unbounded => self.len(),
+        };
 
-        index
+        assert!(
+            start <= end,
+            "The range starts at {} but ends at {}.",
+            start,
+            end
+        );
+        assert!(
+            end <= self.len(),
+            "The range ends at {} but the length is {}.",
+            end,
+            self.len(),
+        );
+
+        start..end
     }
 
-    /// Returns the number of elements the `PartitionVec<T>` can hold without reallocating.
+    /// Applies the operations in `ops` in order and returns the answers of the queries.
     ///
-    /// # Examples
+    /// Every [`Op::Union`] calls `union`, every [`Op::MakeSingleton`] calls `make_singleton`
+    /// and every [`Op::SameSet`] calls `same_set` and adds the answer to the returned `Vec`,
+    /// so the answers are in the order of the queries.
+    /// This is useful when the operations come from a stream, for example from another process.
     ///
-    /// ```
-    /// let mut partition_vec = partitions::PartitionVec::with_capacity(6);
+    /// Every operation takes the same time as the method it calls.
     ///
-    /// for i in 0 .. 6 {
-    ///     partition_vec.push(i);
-    /// }
+    /// # Panics
     ///
-    /// assert!(partition_vec.capacity() == 6);
+    /// If any of the indices is out of bounds, the operations before it are applied.
     ///
-    /// partition_vec.push(6);
+    /// # Examples
     ///
-    /// assert!(partition_vec.capacity() >= 7);
     /// ```
-    #[inline]
-    #[must_use]
-    pub fn capacity(&self) -> usize {
-        usize::min(self.data.capacity(), self.meta.capacity())
+    /// use partitions::partition_vec::Op;
+    ///
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 4]);
+    /// let ops = vec![
+    ///     Op::Union(0, 1),
+    ///     Op::SameSet(1, 0),
+    ///     Op::Union(1, 2),
+    ///     Op::MakeSingleton(1),
+    ///     Op::SameSet(0, 2),
+    ///     Op::SameSet(1, 2),
+    /// ];
+    ///
+    /// assert!(partition_vec.process_ops(ops) == [true, true, false]);
+    /// assert!(partition_vec.len_of_set(0) == 2);
+    /// ```
+    ///
+    /// [`Op::Union`]: enum.Op.html#variant.Union
+    /// [`Op::MakeSingleton`]: enum.Op.html#variant.MakeSingleton
+    /// [`Op::SameSet`]: enum.Op.html#variant.SameSet
+    pub fn process_ops<I>(&mut self, ops: I) -> Vec<bool>
+    where
+        I: IntoIterator<Item = Op>,
+    {
+        let mut answers = Vec::new();
+        for op in ops {
+            match op {
+                Op::Union(first_index, second_index) => self.union(first_index, second_index),
+                Op::SameSet(first_index, second_index) => {
+                    answers.push(self.same_set(first_index, second_index));
+                }
+                Op::MakeSingleton(index) => self.make_singleton(index),
+            }
+        }
+
+        answers
     }
 
-    /// Appends an element to the back of the `PartitionVec<T>`.
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
     ///
-    /// This element has its own disjoint set.
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
     ///
     /// # Panics
     ///
-    /// Panics if the number of elements in the `PartitionVec<T>` overflows a `usize`.
+    /// If `first_index` or `second_index` are out of bounds.
     ///
     /// # Examples
     ///
     /// ```
     /// # #[macro_use]
     /// # extern crate partitions;
-    /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     'a' => 0,
-    ///     'b' => 0,
-    ///     'c' => 1,
-    ///     'd' => 2,
-    /// ];
+    /// let mut partition_vec = partition_vec![(); 4];
     ///
-    /// partition_vec.push('e');
+    /// partition_vec.union(1, 3);
+    /// partition_vec.union(0, 1);
     ///
-    /// assert!(partition_vec.amount_of_sets() == 4);
-    /// assert!(partition_vec[4] == 'e');
+    /// assert!(partition_vec.same_set(0, 1));
+    /// assert!(!partition_vec.same_set(0, 2));
+    /// assert!(partition_vec.same_set(0, 3));
+    /// assert!(!partition_vec.same_set(1, 2));
+    /// assert!(partition_vec.same_set(1, 3));
+    /// assert!(!partition_vec.same_set(2, 3));
     /// # }
     /// ```
     #[inline]
-    pub fn push(&mut self, elem: T) {
-        let old_len = self.len();
-
-        self.data.push(elem);
-        self.meta.push(Metadata::new(old_len));
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        self.find(first_index) == self.find(second_index)
     }
 
-    /// Removes the last element returns it, or `None` if it is empty.
+    /// Returns `true` if `first_index` and `second_index` are in different sets.
     ///
-    /// This will be done in `O(m)` time where `m` is the size of the set
-    /// that `index` belongs to.
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` are out of bounds.
     ///
     /// # Examples
     ///
     /// ```
     /// # #[macro_use]
     /// # extern crate partitions;
-    /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     'a' => 0,
-    ///     'b' => 0,
-    ///     'c' => 1,
-    ///     'd' => 0,
-    /// ];
+    /// let mut partition_vec = partition_vec![(); 4];
     ///
-    /// assert!(partition_vec.pop() == Some('d'));
+    /// partition_vec.union(1, 3);
+    /// partition_vec.union(0, 1);
     ///
-    /// assert!(partition_vec.amount_of_sets() == 2);
-    /// assert!(partition_vec.len() == 3);
+    /// assert!(!partition_vec.other_sets(0, 1));
+    /// assert!(partition_vec.other_sets(0, 2));
+    /// assert!(!partition_vec.other_sets(0, 3));
+    /// assert!(partition_vec.other_sets(1, 2));
+    /// assert!(!partition_vec.other_sets(1, 3));
+    /// assert!(partition_vec.other_sets(2, 3));
     /// # }
     /// ```
-    pub fn pop(&mut self) -> Option<T> {
-        let last_index = self.data.len() - 1;
-        self.make_singleton(last_index);
-
-        self.meta.pop()?;
-        Some(self.data.pop().unwrap())
+    #[inline]
+    #[must_use]
+    pub fn other_sets(&self, first_index: usize, second_index: usize) -> bool {
+        self.find(first_index) != self.find(second_index)
     }
 
-    /// Inserts an element at `index` within the `PartitionVec<T>`, shifting all
-    /// elements after it to the right.
-    ///
-    /// This will take `O(n)` time.
-    ///
-    /// # Panics
+    /// Will remove `index` from its set while leaving the other members in it.
     ///
-    /// Panics if `index` is out of bounds.
+    /// After this `index` will be the only element of its set.
+    /// This won't change the `PartitionVec<T>` if `index` is already the only element.
+    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
     ///
     /// # Examples
     ///
@@ -703,91 +1004,113 @@ impl<T> PartitionVec<T> {
     /// #
     /// # fn main() {
     /// let mut partition_vec = partition_vec![
-    ///     0 => 0,
-    ///     1 => 1,
-    ///     2 => 0,
-    ///     3 => 2,
+    ///     () => 'a',
+    ///     () => 'a',
+    ///     () => 'a',
+    ///     () => 'b',
     /// ];
     ///
-    /// partition_vec.insert(2, -1);
+    /// // 0, 1, and 2 share a set.
+    /// assert!(partition_vec.len_of_set(0) == 3);
+    /// assert!(partition_vec.len_of_set(1) == 3);
+    /// assert!(partition_vec.len_of_set(2) == 3);
+    /// assert!(partition_vec.len_of_set(3) == 1);
     ///
-    /// assert!(partition_vec[2] == -1);
-    /// assert!(partition_vec.amount_of_sets() == 4);
+    /// partition_vec.make_singleton(2);
+    ///
+    /// // Now 2 has its own set and 1, and 2 still share a set.
+    /// assert!(partition_vec.len_of_set(0) == 2);
+    /// assert!(partition_vec.len_of_set(1) == 2);
+    /// assert!(partition_vec.len_of_set(2) == 1);
+    /// assert!(partition_vec.len_of_set(3) == 1);
     /// # }
     /// ```
-    pub fn insert(&mut self, index: usize, elem: T) {
-        // We update the parents and links above the new value.
-        for i in 0..self.meta.len() {
-            let parent = self.meta[i].parent();
-            if parent >= index {
-                self.meta[i].set_parent(parent + 1);
-            }
-
-            let link = self.meta[i].link();
-            if link >= index {
-                self.meta[i].set_link(link + 1);
-            }
-        }
-
-        self.data.insert(index, elem);
-        self.meta.insert(index, Metadata::new(index));
+    pub fn make_singleton(&mut self, index: usize) {
+        make_singleton_in(&self.meta, index);
     }
 
-    /// Removes and returns the element at position index within the `PartitionVec<T>`,
-    /// shifting all elements after it to the left.
+    /// Splits every set with more than `max` elements in sets of at most `max` elements.
     ///
-    /// This will take `O(n + m)` time where `m` is the size of the set that `index` belongs to.
+    /// The members of an oversized set are taken in the order of its set iterator and
+    /// every `max` consecutive members get a new set, so a set of `m` elements is split in
+    /// `m / max` sets rounded up.
+    /// Sets with at most `max` elements are not changed.
+    /// Returns the amount of sets that were created.
+    ///
+    /// This method will be executed in `O(n)` time.
     ///
     /// # Panics
     ///
-    /// Panics if `index` is out of bounds.
+    /// If `max` is zero.
     ///
     /// # Examples
     ///
     /// ```
-    /// # #[macro_use]
-    /// # extern crate partitions;
-    /// #
-    /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     0 => 0,
-    ///     1 => 1,
-    ///     2 => 0,
-    ///     3 => 2,
-    /// ];
-    ///
-    /// assert!(partition_vec.remove(2) == 2);
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 7]);
+    /// for i in 1..5 {
+    ///     partition_vec.union(0, i);
+    /// }
+    /// partition_vec.union(5, 6);
     ///
-    /// assert!(partition_vec[2] == 3);
-    /// assert!(partition_vec.amount_of_sets() == 3);
-    /// # }
+    /// assert!(partition_vec.cap_set_sizes(2) == 2);
+    /// assert!(partition_vec.amount_of_sets() == 4);
+    /// assert!(partition_vec.len_of_set(5) == 2);
+    /// for i in 0..5 {
+    ///     assert!(partition_vec.len_of_set(i) <= 2);
+    /// }
     /// ```
-    pub fn remove(&mut self, index: usize) -> T {
-        self.make_singleton(index);
+    pub fn cap_set_sizes(&mut self, max: usize) -> usize {
+        assert!(max > 0, "The maximum size of a set must be positive.");
 
-        self.meta.remove(index);
+        let mut done = bit_vec![false; self.len()];
+        let mut members = Vec::new();
+        let mut created = 0;
 
-        // We lower all values that point above the index.
-        for i in 0..self.meta.len() {
-            let parent = self.meta[i].parent();
-            if parent > index {
-                self.meta[i].set_parent(parent - 1);
+        for index in 0..self.len() {
+            if done[index] {
+                continue;
+            }
+
+            members.clear();
+            let mut current = index;
+            loop {
+                done.set(current, true);
+                members.push(current);
+
+                current = self.meta[current].link();
+                if current == index {
+                    break;
+                }
+            }
+
+            if members.len() <= max {
+                continue;
             }
 
-            let link = self.meta[i].link();
-            if link > index {
-                self.meta[i].set_link(link - 1);
+            for chunk in members.chunks(max) {
+                let root = chunk[0];
+                for (position, &member) in chunk.iter().enumerate() {
+                    self.meta[member].set_parent(root);
+                    self.meta[member].set_link(chunk[(position + 1) % chunk.len()]);
+                    self.meta[member].set_rank(0);
+                }
+                if chunk.len() > 1 {
+                    self.meta[root].set_rank(1);
+                }
             }
+            created += members.len().div_ceil(max) - 1;
         }
 
-        self.data.remove(index)
+        created
     }
 
-    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// This will be done in `O(1)` time.
     ///
     /// # Panics
     ///
-    /// Panics if the number of elements in de `PartitionVec<T>` overflows a `usize`.
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -796,49 +1119,29 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut first = partition_vec![
-    ///     'a' => 0,
-    ///     'b' => 1,
-    ///     'c' => 1,
-    /// ];
-    /// let mut second = partition_vec![
-    ///     'a' => 0,
-    ///     'b' => 0,
-    ///     'c' => 1,
-    /// ];
+    /// let mut partition_vec = partition_vec![(); 4];
     ///
-    /// first.append(&mut second);
-    ///
-    /// assert!(first.len() == 6);
-    /// assert!(second.len() == 0);
+    /// partition_vec.union(1, 3);
     ///
-    /// assert!(first.amount_of_sets() == 4);
-    /// assert!(second.amount_of_sets() == 0);
+    /// assert!(partition_vec.is_singleton(0));
+    /// assert!(!partition_vec.is_singleton(1));
+    /// assert!(partition_vec.is_singleton(2));
+    /// assert!(!partition_vec.is_singleton(3));
     /// # }
     /// ```
-    pub fn append(&mut self, other: &mut Self) {
-        let old_len = self.len();
-        self.data.append(&mut other.data);
-        self.meta.extend(other.meta.drain(..).map(|meta| {
-            let old_parent = meta.parent();
-            meta.set_parent(old_parent + old_len);
-            let old_link = meta.link();
-            meta.set_link(old_link + old_len);
-
-            meta
-        }));
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: usize) -> bool {
+        self.meta[index].link() == index
     }
 
-    /// Reserves capacity for at least `additional` more elements to be
-    /// inserted in the given `PartitionVec<T>`.
-    /// The collection may reserve more space to avoid frequent reallocation's.
-    /// After calling `reserve`, capacity will be greater than
-    /// or equal to `self.len() + additional`.
-    /// Does nothing if capacity is already sufficient.
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set that `index` belongs to.
     ///
     /// # Panics
     ///
-    /// Panics if the new capacity overflows a `usize`.
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -847,30 +1150,46 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![1];
-    /// partition_vec.reserve(10);
-    /// assert!(partition_vec.capacity() >= 11);
+    /// let mut partition_vec = partition_vec![true; 3];
+    ///
+    /// assert!(partition_vec.len_of_set(0) == 1);
+    /// assert!(partition_vec.len_of_set(1) == 1);
+    /// assert!(partition_vec.len_of_set(2) == 1);
+    ///
+    /// partition_vec.union(0, 2);
+    ///
+    /// assert!(partition_vec.len_of_set(0) == 2);
+    /// assert!(partition_vec.len_of_set(1) == 1);
+    /// assert!(partition_vec.len_of_set(2) == 2);
     /// # }
     /// ```
-    #[inline]
-    pub fn reserve(&mut self, additional: usize) {
-        self.data.reserve(additional);
-        self.meta.reserve(additional);
+    #[must_use]
+    pub fn len_of_set(&self, index: usize) -> usize {
+        let mut current = self.meta[index].link();
+        let mut count = 1;
+
+        while current != index {
+            current = self.meta[current].link();
+            count += 1;
+        }
+
+        count
     }
 
-    /// Reserves the minimum capacity for exactly  `additional` more elements to be
-    /// inserted in the given `PartitionVec<T>`.
-    /// After calling `reserve_exact`, capacity will be greater than or
-    /// equal to `self.len() + additional`.
-    /// Does nothing if the capacity is already sufficient.
+    /// Returns the index of the `n`th member of the set that `index` belongs to,
+    /// or `None` if the set has `n` or fewer members.
     ///
-    /// Note that the allocator may give the collection more space than it requests.
-    /// Therefore capacity can not be relied upon to be precisely minimal.
-    /// Prefer `reserve` if future insertions are expected.
+    /// The members are counted in the order of the ring that links them, starting with
+    /// `index` itself as the `0`th member.
+    /// Together with [`len_of_set`] this can be used to pick a uniformly random member
+    /// of a set.
+    ///
+    /// This method will be executed in `O(min(n, m))` time where `m` is the size of the set of
+    /// `index`.
     ///
     /// # Panics
     ///
-    /// Panics if the new capacity overflows a `usize`.
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -879,50 +1198,86 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![1];
-    /// partition_vec.reserve_exact(10);
-    /// assert!(partition_vec.capacity() >= 11);
+    /// let partition_vec = partition_vec![
+    ///     () => 'a',
+    ///     () => 'b',
+    ///     () => 'a',
+    /// ];
+    ///
+    /// assert!(partition_vec.nth_in_set(0, 0) == Some(0));
+    /// assert!(partition_vec.nth_in_set(0, 1) == Some(2));
+    /// assert!(partition_vec.nth_in_set(0, 2) == None);
+    /// assert!(partition_vec.nth_in_set(1, 1) == None);
     /// # }
     /// ```
-    #[inline]
-    pub fn reserve_exact(&mut self, additional: usize) {
-        self.data.reserve_exact(additional);
-        self.meta.reserve_exact(additional);
+    ///
+    /// [`len_of_set`]: struct.PartitionVec.html#method.len_of_set
+    #[must_use]
+    pub fn nth_in_set(&self, index: usize, n: usize) -> Option<usize> {
+        let mut current = index;
+
+        for _ in 0..n {
+            current = self.meta[current].link();
+            if current == index {
+                return None;
+            }
+        }
+
+        Some(current)
     }
 
-    /// Shrinks the capacity of the `PartitionVec<T>` as much as possible.
-    ///
-    /// It will drop down as close as possible to the length but the allocator
-    /// may still inform the `PartitionVec<T>` that there is space for a few more
-    /// elements.
+    /// Returns `true` if the set that `index` belongs to contains an element equal to `value`.
     ///
-    /// # Examples
+    /// The `value` may be any borrowed form of `T`.
+    /// This will be done in `O(m)` time where `m` is the size of the set that `index` belongs to
+    /// and stops as soon as an equal element is found.
     ///
-    /// ```
-    /// let mut partition_vec = partitions::PartitionVec::with_capacity(10);
+    /// # Panics
     ///
-    /// partition_vec.extend([1, 2, 3].iter().cloned());
+    /// If `index` is out of bounds.
     ///
-    /// assert!(partition_vec.capacity() == 10);
+    /// # Examples
     ///
-    /// partition_vec.shrink_to_fit();
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     String::from("a") => 0,
+    ///     String::from("b") => 1,
+    ///     String::from("c") => 0,
+    /// ];
     ///
-    /// assert!(partition_vec.capacity() >= 3);
+    /// assert!(partition_vec.set_contains(0, "c"));
+    /// assert!(partition_vec.set_contains(2, "a"));
+    /// // This value is only present in a different set.
+    /// assert!(!partition_vec.set_contains(0, "b"));
+    /// // This value is not present at all.
+    /// assert!(!partition_vec.set_contains(1, "d"));
+    /// # }
     /// ```
-    #[inline]
-    pub fn shrink_to_fit(&mut self) {
-        self.data.shrink_to_fit();
-        self.meta.shrink_to_fit();
+    #[must_use]
+    pub fn set_contains<Q>(&self, index: usize, value: &Q) -> bool
+    where
+        T: std::borrow::Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.position_in_set(index, value).is_some()
     }
 
-    /// Shortens the `PartitionVec<T>`, keeping the first `new_len` elements and
-    /// dropping the rest.
+    /// Returns `true` if `member` belongs to the set of `set_index`.
     ///
-    /// If `new_len` is greater than or equal to the collections current length,
-    /// this has no effect.
+    /// This is the same as [`same_set`] but reads better when asking whether an index is a
+    /// member of a given set.
+    /// Use [`set_contains`] to look for a value instead of an index.
     ///
-    /// Note that this method has no effect on the allocated capacity of the
-    /// collection.
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `set_index` or `member` are out of bounds.
     ///
     /// # Examples
     ///
@@ -931,81 +1286,37 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![
+    /// let partition_vec = partition_vec![
     ///     'a' => 0,
     ///     'b' => 1,
     ///     'c' => 0,
-    ///     'd' => 1,
-    ///     'e' => 2,
     /// ];
     ///
-    /// partition_vec.truncate(3);
-    /// assert!(partition_vec.len() == 3);
-    /// assert!(partition_vec.capacity() == 5);
-    /// assert!(partition_vec.len_of_set(0) == 2);
-    /// assert!(partition_vec.len_of_set(1) == 1);
-    /// assert!(partition_vec.len_of_set(2) == 2);
+    /// assert!(partition_vec.set_contains_index(0, 2));
+    /// assert!(partition_vec.set_contains_index(1, 1));
+    /// assert!(!partition_vec.set_contains_index(0, 1));
     /// # }
     /// ```
-    pub fn truncate(&mut self, new_len: usize) {
-        if new_len >= self.len() {
-            return;
-        }
-
-        for i in 0..new_len {
-            let parent = self.meta[i].parent();
-            let mut current = self.meta[i].link();
-            if parent >= new_len {
-                // We make `i` the new root.
-                self.meta[i].set_parent(i);
-                self.meta[i].set_rank(1);
-
-                let mut previous = i;
-                // The last index we saw before we went out of the new bounds.
-                let mut index_before_oob = if current >= new_len {
-                    Some(previous)
-                } else {
-                    None
-                };
-
-                while current != i {
-                    if current >= new_len {
-                        // If the current is above the new length we update this value if needed.
-                        if index_before_oob.is_none() {
-                            index_before_oob = Some(previous);
-                        }
-                    } else if let Some(index) = index_before_oob {
-                        // If we are back in bounds for the first time we update the link.
-                        self.meta[index].set_link(current);
-                        index_before_oob = None;
-                    }
-
-                    self.meta[current].set_parent(i);
-
-                    previous = current;
-                    current = self.meta[current].link();
-                }
-
-                if let Some(index) = index_before_oob {
-                    self.meta[index].set_link(i);
-                }
-            } else if current >= new_len {
-                while current >= new_len {
-                    current = self.meta[current].link();
-                }
-                self.meta[i].set_link(current);
-            }
-        }
-
-        self.data.truncate(new_len);
-        self.meta.truncate(new_len);
+    ///
+    /// [`same_set`]: struct.PartitionVec.html#method.same_set
+    /// [`set_contains`]: struct.PartitionVec.html#method.set_contains
+    #[inline]
+    #[must_use]
+    pub fn set_contains_index(&self, set_index: usize, member: usize) -> bool {
+        self.same_set(set_index, member)
     }
 
-    /// Resizes the `PartitionVec<T>` in-place so that `len` is equal to `new_len`.
+    /// Returns the index of an element equal to `value` in the set that `index` belongs to,
+    /// or `None` if there is no such element.
     ///
-    /// If `new_len` is greater than `len`, the collection is extended by the
-    /// difference, with each additional slot filled with `value`.
-    /// If `new_len` is less than `len`, the collection is simply truncated.
+    /// The `value` may be any borrowed form of `T`.
+    /// If the set contains multiple equal elements it is not specified which index is returned.
+    /// This will be done in `O(m)` time where `m` is the size of the set that `index` belongs to
+    /// and stops as soon as an equal element is found.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -1014,40 +1325,38 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![4, 9];
-    /// partition_vec.resize(4, 0);
-    /// assert!(partition_vec.as_slice() == &[4, 9, 0, 0]);
-    ///
-    /// let mut partition_vec = partition_vec![
-    ///     4 => 0,
-    ///     1 => 1,
-    ///     3 => 5,
-    ///     1 => 1,
-    ///     1 => 3,
+    /// let partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 1,
     /// ];
-    /// partition_vec.resize(2, 0);
-    /// assert!(partition_vec.as_slice() == &[4, 1]);
+    ///
+    /// assert!(partition_vec.position_in_set(0, &'c') == Some(2));
+    /// assert!(partition_vec.position_in_set(3, &'b') == Some(1));
+    /// assert!(partition_vec.position_in_set(2, &'d') == None);
+    /// assert!(partition_vec.position_in_set(2, &'e') == None);
     /// # }
     /// ```
-    #[inline]
-    pub fn resize(&mut self, new_len: usize, value: T)
+    #[must_use]
+    pub fn position_in_set<Q>(&self, index: usize, value: &Q) -> Option<usize>
     where
-        T: Clone,
+        T: std::borrow::Borrow<Q>,
+        Q: PartialEq + ?Sized,
     {
-        let len = self.len();
-        match Ord::cmp(&new_len, &len) {
-            Ordering::Less => self.truncate(new_len),
-            Ordering::Equal => {}
-            Ordering::Greater => {
-                self.data.append(&mut vec![value; new_len - len]);
-                self.meta.extend((len..new_len).map(Metadata::new));
-            }
-        }
+        self.set(index)
+            .find(|(_, element)| (*element).borrow() == value)
+            .map(|(index, _)| index)
     }
 
-    /// Clears the `PartitionVec<T>`, removing all values.
+    /// Returns an iterator over the elements of the set that `index` belongs to
+    /// for which `f` returns `true`.
     ///
-    /// Note that this method has no effect on the allocated capacity of the collection.
+    /// The iterator returned yields pairs `(i, &value)` in the same order as [`set`].
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -1056,60 +1365,81 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![2, 3, 4];
-    /// assert!(!partition_vec.is_empty());
-    /// partition_vec.clear();
-    /// assert!(partition_vec.is_empty());
+    /// let partition_vec = partition_vec![
+    ///     1 => 0,
+    ///     2 => 0,
+    ///     3 => 1,
+    ///     4 => 0,
+    /// ];
+    ///
+    /// let mut even: Vec<_> = partition_vec.set_filtered(0, |value| value % 2 == 0).collect();
+    /// even.sort_unstable();
+    /// assert!(even == [(1, &2), (3, &4)]);
     /// # }
     /// ```
-    #[inline]
-    pub fn clear(&mut self) {
-        self.data.clear();
-        self.meta.clear();
+    ///
+    /// [`set`]: struct.PartitionVec.html#method.set
+    pub fn set_filtered<'a, F>(
+        &'a self,
+        index: usize,
+        mut f: F,
+    ) -> impl Iterator<Item = (usize, &'a T)> + 'a
+    where
+        F: FnMut(&T) -> bool + 'a,
+    {
+        self.set(index).filter(move |&(_, value)| f(value))
     }
 
-    /// Returns `true` if the `partition_vec` contains no elements.
+    /// Returns the amount of elements in the set that `index` belongs to
+    /// for which `pred` returns `true`.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
     /// ```
-    /// let mut partition_vec = partitions::PartitionVec::new();
-    /// assert!(partition_vec.is_empty());
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     1 => 0,
+    ///     2 => 0,
+    ///     3 => 1,
+    ///     4 => 0,
+    ///     6 => 1,
+    /// ];
     ///
-    /// partition_vec.push(1);
-    /// assert!(!partition_vec.is_empty());
+    /// assert!(partition_vec.count_matching(0, |value| value % 2 == 0) == 2);
+    /// assert!(partition_vec.count_matching(2, |value| value % 2 == 0) == 1);
+    /// assert!(partition_vec.count_matching(2, |&value| value > 6) == 0);
+    /// # }
     /// ```
-    #[inline]
     #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+    pub fn count_matching<F>(&self, index: usize, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.set(index).filter(|&(_, value)| pred(value)).count()
     }
 
-    /// Converts the `PartitionVec<T>` into `Box<[T]>`.
+    /// Returns the indices on the path from `index` to the representative of its set.
     ///
-    /// Note that this will drop any excess capacity.
-    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
-    ///
-    /// # Examples
+    /// The path starts with `index` and ends with the representative, every other index
+    /// is the parent of the index before it.
+    /// No paths are compressed, so this shows the current shape of the tree but most
+    /// other methods like `same_set` will shorten the paths they visit.
+    /// This is meant for debugging since the representatives are an implementation detail.
     ///
-    /// ```
-    /// let mut partition_vec = partitions::PartitionVec::with_capacity(10);
-    /// partition_vec.extend([1, 2, 3].iter().cloned());
+    /// This will take `O(d)` time where `d` is the length of the path.
     ///
-    /// assert!(partition_vec.capacity() == 10);
-    /// let slice = partition_vec.into_boxed_slice();
-    /// assert!(slice.into_vec().capacity() == 3);
-    /// ```
-    #[inline]
-    #[must_use]
-    pub fn into_boxed_slice(self) -> Box<[T]> {
-        self.data.into_boxed_slice()
-    }
-
-    /// Extracts a slice containing the entire `PartitionVec<T>`.
+    /// # Panics
     ///
-    /// Equivalent to `&partition_vec[..]`.
-    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    /// If `index` is out of bounds.
     ///
     /// # Examples
     ///
@@ -1118,48 +1448,113 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// use std::io::{self, Write};
-    /// let buffer = partition_vec![1, 2, 3, 4, 5];
-    /// io::sink().write(buffer.as_slice()).unwrap();
+    /// let mut partition_vec = partition_vec![(); 4];
+    /// assert!(partition_vec.parent_chain(0) == [0]);
+    ///
+    /// partition_vec.union(0, 1);
+    /// partition_vec.union(2, 3);
+    /// partition_vec.union(0, 2);
+    ///
+    /// // Two trees with a rank of 1 were joined so the path has two steps.
+    /// let chain = partition_vec.parent_chain(0);
+    /// assert!(chain.len() == 3);
+    /// assert!(chain[0] == 0);
+    ///
+    /// // Finding the set compresses the path.
+    /// assert!(partition_vec.same_set(0, 3));
+    /// assert!(partition_vec.parent_chain(0) == [0, chain[2]]);
     /// # }
     /// ```
-    #[inline]
     #[must_use]
-    pub fn as_slice(&self) -> &[T] {
-        self.data.as_slice()
+    pub fn parent_chain(&self, mut index: usize) -> Vec<usize> {
+        let mut chain = vec![index];
+        while index != self.meta[index].parent() {
+            index = self.meta[index].parent();
+            chain.push(index);
+        }
+
+        chain
     }
 
-    /// Extracts a mutable slice containing the entire `PartitionVec<T>`.
+    /// Makes every element point directly to the representative of its set.
     ///
-    /// Equivalent to `&mut partition_vec[..]`.
-    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    /// This is done automatically while using the `PartitionVec<T>`, calling this method can
+    /// make later calls faster when many indices will be used.
+    /// The sets are not changed.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
     ///
     /// # Examples
     ///
     /// ```
-    /// # #[macro_use]
-    /// # extern crate partitions;
-    /// #
-    /// # fn main() {
-    /// use std::io::{self, Read};
-    /// let mut buffer = partition_vec![0; 3];
-    /// io::repeat(0b101).read_exact(buffer.as_mut_slice()).unwrap();
-    /// # }
-    #[inline]
-    pub fn as_mut_slice(&mut self) -> &mut [T] {
-        self.data.as_mut_slice()
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 4]);
+    /// partition_vec.union(0, 1);
+    /// partition_vec.union(2, 3);
+    /// partition_vec.union(0, 2);
+    ///
+    /// partition_vec.compress_all();
+    /// for i in 0..4 {
+    ///     assert!(partition_vec.parent_chain(i).len() <= 2);
+    /// }
+    /// ```
+    pub fn compress_all(&mut self) {
+        for i in 0..self.len() {
+            self.find(i);
+        }
     }
 
-    /// Returns an iterator over the elements of the set that `index` belongs to.
+    /// Compresses every path like [`compress_all`] and resets the ranks to match the
+    /// new trees.
     ///
-    /// The iterator returned yields pairs `(i, &value)` where `i` is the index of the value and
-    /// `value` is the value itself.
+    /// After the compression every tree has a depth of at most one, so the representative
+    /// of a set with multiple elements gets rank `1` and every other element gets rank `0`.
+    /// Later calls to `union` will then join sets based on the current trees instead of
+    /// ranks that were raised by earlier unions.
+    /// The sets are not changed.
     ///
-    /// The order the elements are returned in is not specified.
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// If `index` is out of bounds.
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 8]);
+    /// for i in 0..4 {
+    ///     partition_vec.union(2 * i, 2 * i + 1);
+    /// }
+    /// partition_vec.union(0, 2);
+    /// partition_vec.union(4, 6);
+    /// partition_vec.union(0, 4);
+    ///
+    /// partition_vec.reset_ranks();
+    /// assert!(partition_vec.len_of_set(0) == 8);
+    /// for i in 0..8 {
+    ///     assert!(partition_vec.parent_chain(i).len() <= 2);
+    /// }
+    /// partition_vec.assert_invariants();
+    /// ```
+    ///
+    /// [`compress_all`]: struct.PartitionVec.html#method.compress_all
+    pub fn reset_ranks(&mut self) {
+        self.compress_all();
+
+        for (index, meta) in self.meta.iter().enumerate() {
+            if meta.parent() == index && meta.link() != index {
+                meta.set_rank(1);
+            } else {
+                meta.set_rank(0);
+            }
+        }
+    }
+
+    /// Returns the length of the set of every index.
+    ///
+    /// The value at index `i` of the returned `Vec` is equal to `self.len_of_set(i)`,
+    /// so indices in the same set have the same value.
+    /// This is faster than calling `len_of_set` for every index.
+    ///
+    /// This method will be executed in `O(n α(n))` where `α` is the inverse Ackermann function.
     ///
     /// # Examples
     ///
@@ -1169,53 +1564,37 @@ impl<T> PartitionVec<T> {
     /// #
     /// # fn main() {
     /// let partition_vec = partition_vec![
-    ///     'a' => "first set",
-    ///     'b' => "first set",
-    ///     'c' => "second set",
-    ///     'd' => "second set",
+    ///     8 => 0,
+    ///     3 => 1,
+    ///     4 => 0,
+    ///     3 => 1,
+    ///     7 => 2,
+    ///     5 => 0,
     /// ];
     ///
-    /// let mut done = [0, 0, 0, 0];
-    /// for (index, value) in partition_vec.set(0) {
-    ///     assert!(*value == 'a' || *value == 'b');
-    ///     done[index] += 1;
-    /// }
-    /// for (index, value) in partition_vec.set(1) {
-    ///     assert!(*value == 'a' || *value == 'b');
-    ///     done[index] += 1;
-    /// }
-    /// for (index, value) in partition_vec.set(2) {
-    ///     assert!(*value == 'c' || *value == 'd');
-    ///     done[index] += 1;
-    /// }
-    /// // We visited the first set twice and the second set once.
-    /// assert!(done == [2, 2, 1, 1]);
+    /// let lengths = partition_vec.lengths();
+    /// assert!(lengths == [3, 2, 3, 2, 1, 3]);
+    ///
+    /// // Every set contributes its length once for each of its members.
+    /// let total: usize = lengths.iter().sum();
+    /// assert!(total == 3 * 3 + 2 * 2 + 1 * 1);
     /// # }
     /// ```
-    #[inline]
     #[must_use]
-    pub fn set(&self, index: usize) -> Set<T> {
-        let root = self.find_final(index);
+    pub fn lengths(&self) -> Vec<usize> {
+        let roots: Vec<usize> = (0..self.len()).map(|i| self.find(i)).collect();
 
-        self.meta[root].set_rank(1);
-
-        Set {
-            partition_vec: self,
-            current: Some(root),
-            root,
+        let mut counts = vec![0; self.len()];
+        for &root in &roots {
+            counts[root] += 1;
         }
+
+        roots.into_iter().map(|root| counts[root]).collect()
     }
 
-    /// Returns an iterator over the elements of the set that `index` belongs to.
-    ///
-    /// The iterator returned yields pairs `(i, &mut value)` where `i` is the index of the value and
-    /// `value` is the value itself.
-    ///
-    /// The order the elements are returned in is not specified.
-    ///
-    /// # Panics
+    /// Returns the amount of sets in the `PartitionVec<T>`.
     ///
-    /// If `index` is out of bounds.
+    /// This method will be executed in `O(n α(n))` where `α` is the inverse Ackermann function.
     ///
     /// # Examples
     ///
@@ -1224,42 +1603,47 @@ impl<T> PartitionVec<T> {
     /// # extern crate partitions;
     /// #
     /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     0 => 'a',
-    ///     0 => 'b',
-    ///     0 => 'b',
-    ///     0 => 'c',
+    /// let partition_vec = partition_vec![
+    ///     8 => 0,
+    ///     3 => 1,
+    ///     4 => 0,
+    ///     3 => 1,
+    ///     7 => 2,
     /// ];
     ///
-    /// assert!(partition_vec.as_slice() == &[0, 0, 0, 0]);
-    /// for (index, value) in partition_vec.set_mut(2) {
-    ///     assert!(index == 1 || index == 2);
-    ///     *value += 1;
-    /// }
-    /// assert!(partition_vec.as_slice() == &[0, 1, 1, 0]);
+    /// assert!(partition_vec.amount_of_sets() == 3);
     /// # }
     /// ```
-    #[inline]
-    pub fn set_mut(&mut self, index: usize) -> SetMut<T> {
-        let root = self.find_final(index);
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        let mut done = bit_vec![false; self.len()];
+        let mut count = 0;
 
-        self.meta[root].set_rank(1);
+        for i in 0..self.len() {
+            let root = self.find(i);
 
-        SetMut {
-            partition_vec: self,
-            current: Some(root),
-            root,
+            if !done[root] {
+                done.set(root, true);
+                count += 1;
+            }
         }
+
+        count
     }
 
-    /// Returns an iterator over all sets of the `PartitionVec<T>`.
+    /// Returns the index of the representative of the set of every index in `indices`.
     ///
-    /// The iterator returned yields `Set` iterators.
-    /// These `Set` iterators yield pairs `(i, &value)` where `i` is the index of
-    /// the value and `value` is the value itself.
+    /// Two indices share a set exactly when they have the same representative,
+    /// which is also the index used by [`Root`].
+    /// The paths are compressed while answering so indices later in `indices` that share
+    /// ancestors with earlier ones reach their representative in fewer steps.
+    /// The representative of a set can change when the sets are changed.
     ///
-    /// The sets are returned in order by there first member.
-    /// The order the elements of a `Set` are returned in is not specified.
+    /// This method will be executed in `O(k α(n))` time where `k` is the length of `indices`.
+    ///
+    /// # Panics
+    ///
+    /// If one of the `indices` is out of bounds.
     ///
     /// # Examples
     ///
@@ -1269,243 +1653,4558 @@ impl<T> PartitionVec<T> {
     /// #
     /// # fn main() {
     /// let partition_vec = partition_vec![
-    ///     0 => 'a',
-    ///     0 => 'a',
-    ///     2 => 'b',
-    ///     2 => 'b',
-    ///     4 => 'c',
-    ///     4 => 'c',
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 1,
     /// ];
     ///
-    /// for set in partition_vec.all_sets() {
-    ///     let mut count = 0;
-    ///     for (index, value) in set {
-    ///         assert!(index == *value || index == *value + 1);
-    ///         count += 1;
-    ///     }
-    ///     assert!(count == 2);
-    /// }
+    /// let roots = partition_vec.representatives(&[0, 1, 2, 3]);
+    /// assert!(roots[0] == roots[2]);
+    /// assert!(roots[1] == roots[3]);
+    /// assert!(roots[0] != roots[1]);
     /// # }
     /// ```
-    #[inline]
+    ///
+    /// [`Root`]: struct.Root.html
     #[must_use]
-    pub fn all_sets(&self) -> AllSets<T> {
-        let len = self.len();
-
-        AllSets {
-            partition_vec: self,
-            done: bit_vec![false; len],
-            range: 0..len,
-        }
+    pub fn representatives(&self, indices: &[usize]) -> Vec<usize> {
+        indices.iter().map(|&index| self.find(index)).collect()
     }
 
-    /// Returns an iterator over all sets of the `PartitionVec<T>`.
+    /// Gives the representative of the set that `index` belongs to.
     ///
-    /// The iterator returned yields `SetMut` iterators.
-    /// These `SetMut` iterators yield pairs `(i, &mut value)` where `i` is the index of
-    /// the value and `value` is the value itself.
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function. Each index of a set
+    /// will give the same value. To see if two indexes point to values in
+    /// the same subset compare the results of `find`.
     ///
-    /// The sets are returned in order by there first member.
-    /// The order the elements of a `SetMut` are returned in is not specified.
+    /// This method is private to keep the representative of the set an implementation
+    /// detail, this gives greater freedom to change the representative of the set.
     ///
-    /// # Examples
+    /// # Panics
     ///
-    /// ```
-    /// # #[macro_use]
-    /// # extern crate partitions;
-    /// #
-    /// # fn main() {
-    /// let mut partition_vec = partition_vec![
-    ///     0 => 'a',
-    ///     0 => 'b',
-    ///     0 => 'a',
-    ///     0 => 'b',
-    ///     0 => 'c',
-    ///     0 => 'c',
-    /// ];
+    /// If `index` is out of bounds.
+    #[inline]
+    pub(crate) fn find(&self, index: usize) -> usize {
+        find_in(&self.meta, index)
+    }
+
+    /// Gives the representative of the set that `index` belongs to.
     ///
-    /// assert!(partition_vec.as_slice() == &[0, 0, 0, 0, 0, 0]);
+    /// This method is slightly faster than `find` but still `O(a(n))` time.
+    /// This method wont update the parents while finding the representative and should
+    /// only be used if the parents will be updated immediately afterwards.
     ///
-    /// for (set_number, set_mut) in partition_vec.all_sets_mut().enumerate() {
-    ///     for (index, value) in set_mut {
-    ///         assert!(index < 6);
-    ///         *value = set_number;
-    ///     }
-    /// }
+    /// # Panics
     ///
-    /// assert!(partition_vec.as_slice() == &[0, 1, 0, 1, 2, 2]);
-    /// # }
-    /// ```
+    /// If `index` is out of bounds.
     #[inline]
-    pub fn all_sets_mut(&mut self) -> AllSetsMut<T> {
-        let len = self.len();
+    pub(crate) fn find_final(&self, index: usize) -> usize {
+        find_final_in(&self.meta, index)
+    }
 
-        AllSetsMut {
-            partition_vec: self,
-            done: bit_vec![false; len],
-            range: 0..len,
-        }
+    /// Returns a pointer to the values together with the metadata.
+    ///
+    /// The mutable iterators use this instead of holding a mutable reference to the
+    /// `PartitionVec<T>` so the references they return are never invalidated by
+    /// reborrowing the whole `data` slice.
+    #[inline]
+    fn raw_parts(&mut self) -> (*mut T, &[Metadata]) {
+        (self.data.as_mut_ptr(), &self.meta)
     }
 
-    /// This method is used by the `partition_vec!` macro.
-    #[doc(hidden)]
+    /// Returns the values together with the metadata.
     #[inline]
-    pub fn from_elem(elem: T, len: usize) -> Self
+    pub(crate) fn parts(&self) -> (&[T], &[Metadata]) {
+        (&self.data, &self.meta)
+    }
+
+    /// Returns the values, which can be changed, together with the metadata.
+    #[inline]
+    pub(crate) fn parts_mut(&mut self) -> (&mut [T], &[Metadata]) {
+        (&mut self.data, &self.meta)
+    }
+
+    /// Returns a pointer to the buffer of the values.
+    ///
+    /// Unlike a pointer to an element obtained through a reference, this stays valid when
+    /// other elements are borrowed mutably, as long as the buffer does not reallocate.
+    #[inline]
+    pub(crate) fn data_ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    /// Splits the `PartitionVec<T>` into its values and metadata.
+    #[inline]
+    pub(crate) fn into_parts(self) -> (Vec<T>, Vec<Metadata>) {
+        (self.data, self.meta)
+    }
+
+    /// Constructs a `PartitionVec<T>` from values and metadata of the same length.
+    ///
+    /// The metadata has to describe valid sets over the indices of `data`.
+    #[inline]
+    pub(crate) fn from_parts(data: Vec<T>, meta: Vec<Metadata>) -> Self {
+        debug_assert!(data.len() == meta.len());
+
+        Self { data, meta }
+    }
+
+    /// Returns the number of elements the `PartitionVec<T>` can hold without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::with_capacity(6);
+    ///
+    /// for i in 0 .. 6 {
+    ///     partition_vec.push(i);
+    /// }
+    ///
+    /// assert!(partition_vec.capacity() == 6);
+    ///
+    /// partition_vec.push(6);
+    ///
+    /// assert!(partition_vec.capacity() >= 7);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        usize::min(self.data.capacity(), self.meta.capacity())
+    }
+
+    /// Returns the amount of heap memory used by the `PartitionVec<T>`.
+    ///
+    /// This counts the memory allocated for the values and for the metadata of the sets,
+    /// including unused capacity. Memory owned by the values themselves is not counted.
+    /// The metadata takes three `usize` values per element or two with the `compact` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionVec;
+    ///
+    /// let mut partition_vec: PartitionVec<u32> = PartitionVec::with_capacity(10);
+    /// partition_vec.extend(0 .. 4);
+    ///
+    /// let memory_usage = partition_vec.memory_usage();
+    /// assert!(memory_usage.len == 4);
+    /// assert!(memory_usage.capacity == 10);
+    /// assert!(memory_usage.data_bytes == 10 * std::mem::size_of::<u32>());
+    ///
+    /// let usizes_per_element = if cfg!(feature = "compact") { 2 } else { 3 };
+    /// assert!(memory_usage.meta_bytes == 10 * usizes_per_element * std::mem::size_of::<usize>());
+    /// assert!(memory_usage.map_bytes == 0);
+    ///
+    /// // The memory usage scales linearly with the capacity.
+    /// let larger: PartitionVec<u32> = PartitionVec::with_capacity(30);
+    /// assert!(larger.memory_usage().total_bytes() == 3 * memory_usage.total_bytes());
+    /// ```
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            len: self.len(),
+            capacity: self.capacity(),
+            data_bytes: self.data.capacity() * std::mem::size_of::<T>(),
+            meta_bytes: self.meta.capacity() * std::mem::size_of::<Metadata>(),
+            map_bytes: 0,
+        }
+    }
+
+    /// Appends an element to the back of the `PartitionVec<T>`.
+    ///
+    /// This element has its own disjoint set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements in the `PartitionVec<T>` overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    ///     'd' => 2,
+    /// ];
+    ///
+    /// partition_vec.push('e');
+    ///
+    /// assert!(partition_vec.amount_of_sets() == 4);
+    /// assert!(partition_vec[4] == 'e');
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push(&mut self, elem: T) {
+        let old_len = self.len();
+
+        self.data.push(elem);
+        self.meta.push(Metadata::new(old_len));
+    }
+
+    /// Removes the last element returns it, or `None` if it is empty.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set
+    /// that `index` belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    ///     'd' => 0,
+    /// ];
+    ///
+    /// assert!(partition_vec.pop() == Some('d'));
+    ///
+    /// assert!(partition_vec.amount_of_sets() == 2);
+    /// assert!(partition_vec.len() == 3);
+    /// # }
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        let last_index = self.len().checked_sub(1)?;
+        self.make_singleton(last_index);
+
+        self.meta.pop();
+        self.data.pop()
+    }
+
+    /// Inserts an element at `index` within the `PartitionVec<T>`, shifting all
+    /// elements after it to the right.
+    ///
+    /// This will take `O(n)` time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    /// or if the `PartitionVec<T>` would hold more values than its indices can represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 0,
+    ///     1 => 1,
+    ///     2 => 0,
+    ///     3 => 2,
+    /// ];
+    ///
+    /// partition_vec.insert(2, -1);
+    ///
+    /// assert!(partition_vec[2] == -1);
+    /// assert!(partition_vec.amount_of_sets() == 4);
+    /// # }
+    /// ```
+    pub fn insert(&mut self, index: usize, elem: T) {
+        // This makes sure that increasing the indices below can not overflow.
+        checked_len(self.len(), 1);
+
+        // We update the parents and links above the new value.
+        shift_up_in(&self.meta, index);
+
+        self.data.insert(index, elem);
+        self.meta.insert(index, Metadata::new(index));
+    }
+
+    /// Removes and returns the element at position index within the `PartitionVec<T>`,
+    /// shifting all elements after it to the left.
+    ///
+    /// This will take `O(n + m)` time where `m` is the size of the set that `index` belongs to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 0,
+    ///     1 => 1,
+    ///     2 => 0,
+    ///     3 => 2,
+    /// ];
+    ///
+    /// assert!(partition_vec.remove(2) == 2);
+    ///
+    /// assert!(partition_vec[2] == 3);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// # }
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        self.make_singleton(index);
+
+        self.meta.remove(index);
+
+        // We lower all values that point above the index.
+        shift_down_in(&self.meta, index);
+
+        self.data.remove(index)
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements in de `PartitionVec<T>` overflows a `usize`
+    /// or is more than its indices can represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut first = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 1,
+    /// ];
+    /// let mut second = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    /// ];
+    ///
+    /// first.append(&mut second);
+    ///
+    /// assert!(first.len() == 6);
+    /// assert!(second.len() == 0);
+    ///
+    /// assert!(first.amount_of_sets() == 4);
+    /// assert!(second.amount_of_sets() == 0);
+    /// # }
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        let old_len = self.len();
+        // This makes sure that offsetting the indices of `other` can not overflow.
+        checked_len(old_len, other.len());
+
+        self.data.append(&mut other.data);
+        self.meta.extend(other.meta.drain(..).inspect(|meta| {
+            let old_parent = meta.parent();
+            meta.set_parent(old_parent + old_len);
+            let old_link = meta.link();
+            meta.set_link(old_link + old_len);
+        }));
+    }
+
+    /// Returns a `PartitionVec<T>` with the elements of `self` followed by the elements of `other`.
+    ///
+    /// No set of the result contains elements of both `self` and `other`,
+    /// this is [`append`] for when both `PartitionVec`s are owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements in de `PartitionVec<T>` overflows a `usize`
+    /// or is more than its indices can represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let first = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    /// ];
+    /// let second = partition_vec![
+    ///     'c' => 0,
+    ///     'd' => 1,
+    /// ];
+    ///
+    /// let both = first.concat(second);
+    ///
+    /// assert!(both.as_slice() == ['a', 'b', 'c', 'd']);
+    /// assert!(both.amount_of_sets() == 3);
+    /// assert!(both.same_set(0, 1));
+    /// assert!(!both.same_set(1, 2));
+    /// # }
+    /// ```
+    ///
+    /// [`append`]: struct.PartitionVec.html#method.append
+    #[must_use]
+    pub fn concat(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+
+        self
+    }
+
+    /// Moves all the elements of `other` into `self`
+    /// and joins the sets of all elements with the same key.
+    ///
+    /// The sets of both `PartitionVec`s are kept and two elements whose values give the same
+    /// `key` end up in the same set, also when they come from different `PartitionVec`s.
+    /// This is useful to combine partitions that were computed separately.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements in de `PartitionVec<T>` overflows a `usize`
+    /// or is more than its indices can represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// // The edges of the first shard connect 1 and 2.
+    /// let first = partition_vec![
+    ///     (0, 'a') => 0,
+    ///     (1, 'b') => 1,
+    ///     (2, 'c') => 1,
+    /// ];
+    /// // The edges of the second shard connect 2 and 3.
+    /// let second = partition_vec![
+    ///     (2, 'd') => 0,
+    ///     (3, 'e') => 0,
+    ///     (4, 'f') => 1,
+    /// ];
+    ///
+    /// let mut merged = first;
+    /// merged.merge_by_key(second, |&(node, _)| node);
+    ///
+    /// assert!(merged.len() == 6);
+    /// assert!(merged.len_of_set(1) == 4);
+    /// assert!(merged.same_set(1, 4));
+    /// assert!(merged.is_singleton(0));
+    /// assert!(merged.is_singleton(5));
+    /// # }
+    /// ```
+    pub fn merge_by_key<K, F>(&mut self, mut other: Self, key: F)
+    where
+        K: Eq + std::hash::Hash,
+        F: Fn(&T) -> K,
+    {
+        self.append(&mut other);
+        self.union_by_key(|_, value| key(value));
+    }
+
+    /// Joins the sets of all elements for which `key` gives the same key.
+    ///
+    /// The function `key` is given the index and the value of every element.
+    /// The sets that already exist are kept, so every new set is the union of the old sets of
+    /// the elements with a key.
+    /// This is useful to group elements by one of their fields.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut rows = partitions::PartitionVec::from(vec![
+    ///     ("apple", "fruit"),
+    ///     ("carrot", "vegetable"),
+    ///     ("pear", "fruit"),
+    ///     ("leek", "vegetable"),
+    ///     ("salt", "spice"),
+    /// ]);
+    /// rows.union(4, 0);
+    ///
+    /// rows.union_by_key(|_, &(_, kind)| kind);
+    ///
+    /// assert!(rows.amount_of_sets() == 2);
+    /// assert!(rows.same_set(0, 2));
+    /// assert!(rows.len_of_set(2) == 3);
+    /// assert!(rows.same_set(1, 3));
+    /// ```
+    pub fn union_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: Eq + std::hash::Hash,
+        F: FnMut(usize, &T) -> K,
+    {
+        // We map a `key` to the first index with that key.
+        let mut map = std::collections::HashMap::with_capacity(self.len());
+        for i in 0..self.len() {
+            let first = *map.entry(key(i, &self.data[i])).or_insert(i);
+            if first != i {
+                self.union(first, i);
+            }
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be
+    /// inserted in the given `PartitionVec<T>`.
+    /// The collection may reserve more space to avoid frequent reallocation's.
+    /// After calling `reserve`, capacity will be greater than
+    /// or equal to `self.len() + additional`.
+    /// Does nothing if capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![1];
+    /// partition_vec.reserve(10);
+    /// assert!(partition_vec.capacity() >= 11);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.meta.reserve(additional);
+
+        // We give both vectors the largest capacity either of them has so the capacities do not
+        // drift apart, any extra space in only one of them could never be used.
+        // Zero sized values always have a capacity of `usize::MAX`, which is left out.
+        let data_capacity = if std::mem::size_of::<T>() == 0 {
+            0
+        } else {
+            self.data.capacity()
+        };
+        let capacity = usize::max(data_capacity, self.meta.capacity());
+        let len = self.len();
+        self.data.reserve_exact(capacity - len);
+        self.meta.reserve_exact(capacity - len);
+    }
+
+    /// Reserves capacity for at least `additional` more elements like [`reserve`] and
+    /// returns the new capacity.
+    ///
+    /// This is useful to keep track of how much memory was actually allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::<u32>::new();
+    ///
+    /// let capacity = partition_vec.reserve_and_report(10);
+    /// assert!(capacity >= 10);
+    /// assert!(capacity == partition_vec.capacity());
+    /// ```
+    ///
+    /// [`reserve`]: struct.PartitionVec.html#method.reserve
+    pub fn reserve_and_report(&mut self, additional: usize) -> usize {
+        self.reserve(additional);
+
+        self.capacity()
+    }
+
+    /// Reserves the minimum capacity for exactly  `additional` more elements to be
+    /// inserted in the given `PartitionVec<T>`.
+    /// After calling `reserve_exact`, capacity will be greater than or
+    /// equal to `self.len() + additional`.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// Note that the allocator may give the collection more space than it requests.
+    /// Therefore capacity can not be relied upon to be precisely minimal.
+    /// Prefer `reserve` if future insertions are expected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![1];
+    /// partition_vec.reserve_exact(10);
+    /// assert!(partition_vec.capacity() >= 11);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.data.reserve_exact(additional);
+        self.meta.reserve_exact(additional);
+    }
+
+    /// Shrinks the capacity of the `PartitionVec<T>` as much as possible.
+    ///
+    /// It will drop down as close as possible to the length but the allocator
+    /// may still inform the `PartitionVec<T>` that there is space for a few more
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::with_capacity(10);
+    ///
+    /// partition_vec.extend([1, 2, 3].iter().cloned());
+    ///
+    /// assert!(partition_vec.capacity() == 10);
+    ///
+    /// partition_vec.shrink_to_fit();
+    ///
+    /// assert!(partition_vec.capacity() >= 3);
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.meta.shrink_to_fit();
+    }
+
+    /// Shortens the `PartitionVec<T>`, keeping the first `new_len` elements and
+    /// dropping the rest.
+    ///
+    /// If `new_len` is greater than or equal to the collections current length,
+    /// this has no effect.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the
+    /// collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 1,
+    ///     'e' => 2,
+    /// ];
+    ///
+    /// partition_vec.truncate(3);
+    /// assert!(partition_vec.len() == 3);
+    /// assert!(partition_vec.capacity() == 5);
+    /// assert!(partition_vec.len_of_set(0) == 2);
+    /// assert!(partition_vec.len_of_set(1) == 1);
+    /// assert!(partition_vec.len_of_set(2) == 2);
+    /// # }
+    /// ```
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len() {
+            return;
+        }
+
+        truncate_in(&self.meta, new_len);
+
+        self.data.truncate(new_len);
+        self.meta.truncate(new_len);
+    }
+
+    /// Resizes the `PartitionVec<T>` in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the collection is extended by the
+    /// difference, with each additional slot filled with `value`.
+    /// If `new_len` is less than `len`, the collection is simply truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![4, 9];
+    /// partition_vec.resize(4, 0);
+    /// assert!(partition_vec.as_slice() == &[4, 9, 0, 0]);
+    ///
+    /// let mut partition_vec = partition_vec![
+    ///     4 => 0,
+    ///     1 => 1,
+    ///     3 => 5,
+    ///     1 => 1,
+    ///     1 => 3,
+    /// ];
+    /// partition_vec.resize(2, 0);
+    /// assert!(partition_vec.as_slice() == &[4, 1]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        let len = self.len();
+        match Ord::cmp(&new_len, &len) {
+            Ordering::Less => self.truncate(new_len),
+            Ordering::Equal => {}
+            Ordering::Greater => {
+                self.data.append(&mut vec![value; new_len - len]);
+                self.meta.extend((len..new_len).map(Metadata::new));
+            }
+        }
+    }
+
+    /// Resizes the `PartitionVec<T>` in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the collection is extended by the
+    /// difference, with each additional slot filled with the result of calling `f`
+    /// and given its own set.
+    /// If `new_len` is less than `len`, the collection is simply truncated.
+    ///
+    /// Unlike [`resize`] this does not require `T: Clone`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     vec![1] => 0,
+    ///     vec![2] => 0,
+    /// ];
+    ///
+    /// partition_vec.resize_with(4, Vec::new);
+    /// assert!(partition_vec.as_slice() == [vec![1], vec![2], vec![], vec![]]);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    ///
+    /// let mut next = 0;
+    /// partition_vec.resize_with(1, || {
+    ///     next += 1;
+    ///     vec![next]
+    /// });
+    /// assert!(partition_vec.as_slice() == [vec![1]]);
+    /// assert!(next == 0);
+    /// # }
+    /// ```
+    ///
+    /// [`resize`]: struct.PartitionVec.html#method.resize
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        let len = self.len();
+        match Ord::cmp(&new_len, &len) {
+            Ordering::Less => self.truncate(new_len),
+            Ordering::Equal => {}
+            Ordering::Greater => {
+                self.data.resize_with(new_len, f);
+                self.meta.extend((len..new_len).map(Metadata::new));
+            }
+        }
+    }
+
+    /// Clears the `PartitionVec<T>`, removing all values.
+    ///
+    /// Note that this method has no effect on the allocated capacity of the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![2, 3, 4];
+    /// assert!(!partition_vec.is_empty());
+    /// partition_vec.clear();
+    /// assert!(partition_vec.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.meta.clear();
+    }
+
+    /// Returns `true` if the `partition_vec` contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::new();
+    /// assert!(partition_vec.is_empty());
+    ///
+    /// partition_vec.push(1);
+    /// assert!(!partition_vec.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if `index` is in bounds, which means it is below the length.
+    ///
+    /// The methods that panic when an index is out of bounds can be called with every
+    /// index for which this returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let partition_vec = partitions::PartitionVec::from(vec!['a', 'b', 'c']);
+    ///
+    /// assert!(partition_vec.is_valid_index(0));
+    /// assert!(partition_vec.is_valid_index(partition_vec.len() - 1));
+    /// assert!(!partition_vec.is_valid_index(partition_vec.len()));
+    /// assert!(!partitions::PartitionVec::<()>::new().is_valid_index(0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_valid_index(&self, index: usize) -> bool {
+        index < self.len()
+    }
+
+    /// Converts the `PartitionVec<T>` into `Box<[T]>`.
+    ///
+    /// Note that this will drop any excess capacity.
+    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::with_capacity(10);
+    /// partition_vec.extend([1, 2, 3].iter().cloned());
+    ///
+    /// assert!(partition_vec.capacity() == 10);
+    /// let slice = partition_vec.into_boxed_slice();
+    /// assert!(slice.into_vec().capacity() == 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.data.into_boxed_slice()
+    }
+
+    /// Converts the `PartitionVec<T>` into a `Vec<T>` with the same values in the same order.
+    ///
+    /// Unlike `into_boxed_slice` this keeps the capacity of the values.
+    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::with_capacity(10);
+    /// partition_vec.extend([1, 2, 3].iter().cloned());
+    /// partition_vec.union(0, 2);
+    ///
+    /// let vec = partition_vec.into_vec();
+    /// assert!(vec == [1, 2, 3]);
+    /// assert!(vec.capacity() == 10);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Converts the `PartitionVec<T>` into a `PartitionVec<U>` by calling `f` on every value
+    /// in order, while keeping the sets.
+    ///
+    /// The metadata is moved over unchanged, so every index keeps its set.
+    ///
+    /// This will take `O(n)` time, not counting the time spend in `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     1 => 0,
+    ///     2 => 1,
+    ///     3 => 0,
+    /// ];
+    ///
+    /// let mapped = partition_vec.map(|value| value.to_string());
+    /// assert!(mapped.as_slice() == ["1", "2", "3"]);
+    /// assert!(mapped.same_set(0, 2));
+    /// assert!(mapped.is_singleton(1));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map<U, F>(self, f: F) -> PartitionVec<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        PartitionVec {
+            data: self.data.into_iter().map(f).collect(),
+            meta: self.meta,
+        }
+    }
+
+    /// Returns a `PartitionVec<U>` with the result of calling `f` on every value in order
+    /// and the same sets as `self`.
+    ///
+    /// This will take `O(n)` time, not counting the time spend in `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     "a" => 0,
+    ///     "bc" => 0,
+    ///     "d" => 1,
+    /// ];
+    ///
+    /// let lengths = partition_vec.map_ref(|value| value.len());
+    /// assert!(lengths.as_slice() == [1, 2, 1]);
+    /// assert!(lengths.same_set(0, 1));
+    /// assert!(partition_vec.same_set(0, 1));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map_ref<U, F>(&self, f: F) -> PartitionVec<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        PartitionVec {
+            data: self.data.iter().map(f).collect(),
+            meta: self.meta.clone(),
+        }
+    }
+
+    /// Extracts a slice containing the entire `PartitionVec<T>`.
+    ///
+    /// Equivalent to `&partition_vec[..]`.
+    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use std::io::{self, Write};
+    /// let buffer = partition_vec![1, 2, 3, 4, 5];
+    /// io::sink().write(buffer.as_slice()).unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Extracts a mutable slice containing the entire `PartitionVec<T>`.
+    ///
+    /// Equivalent to `&mut partition_vec[..]`.
+    /// This will not take the sets of the `PartitionVec<T>` in to account at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use std::io::{self, Read};
+    /// let mut buffer = partition_vec![0; 3];
+    /// io::repeat(0b101).read_exact(buffer.as_mut_slice()).unwrap();
+    /// # }
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.data.as_mut_slice()
+    }
+
+    /// Returns an iterator over chunks of `size` values together with the index of the
+    /// first value of every chunk.
+    ///
+    /// This is the same as [`slice::chunks`] but the start index allows mapping a position
+    /// in a chunk back to an index that can be given to methods like [`union`].
+    /// The last chunk is shorter if `size` does not divide the length.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![0, 0, 1, 1, 1, 2, 2];
+    ///
+    /// let unions: Vec<(usize, usize)> = partition_vec
+    ///     .enumerate_chunks(3)
+    ///     .map(|(start, chunk)| (start, start + chunk.len() - 1))
+    ///     .collect();
+    /// assert!(unions == [(0, 2), (3, 5), (6, 6)]);
+    ///
+    /// for (first, last) in unions {
+    ///     partition_vec.union(first, last);
+    /// }
+    /// assert!(partition_vec.same_set(3, 5));
+    /// assert!(partition_vec.amount_of_sets() == 5);
+    /// # }
+    /// ```
+    ///
+    /// [`slice::chunks`]: https://doc.rust-lang.org/std/primitive.slice.html#method.chunks
+    /// [`union`]: struct.PartitionVec.html#method.union
+    pub fn enumerate_chunks(&self, size: usize) -> impl Iterator<Item = (usize, &[T])> {
+        self.data
+            .chunks(size)
+            .enumerate()
+            .map(move |(chunk, values)| (chunk * size, values))
+    }
+
+    /// Returns an iterator over all windows of `size` values together with the index of the
+    /// first value of every window.
+    ///
+    /// This is the same as [`slice::windows`] but the start index allows mapping a position
+    /// in a window back to an index that can be given to methods like [`union`].
+    ///
+    /// # Panics
+    ///
+    /// If `size` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![1, 5, 2, 1, 5];
+    ///
+    /// // Join the first and last element of every window with equal ends.
+    /// let unions: Vec<usize> = partition_vec
+    ///     .enumerate_windows(4)
+    ///     .filter(|&(_, window)| window[0] == window[3])
+    ///     .map(|(start, _)| start)
+    ///     .collect();
+    ///
+    /// for start in unions {
+    ///     partition_vec.union(start, start + 3);
+    /// }
+    /// assert!(partition_vec.same_set(0, 3));
+    /// assert!(partition_vec.same_set(1, 4));
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// # }
+    /// ```
+    ///
+    /// [`slice::windows`]: https://doc.rust-lang.org/std/primitive.slice.html#method.windows
+    /// [`union`]: struct.PartitionVec.html#method.union
+    pub fn enumerate_windows(&self, size: usize) -> impl Iterator<Item = (usize, &[T])> {
+        self.data.windows(size).enumerate()
+    }
+
+    /// Returns mutable references to the elements at every index in `indices` at once.
+    ///
+    /// This is the same as [`slice::get_disjoint_mut`] on the values and will not take the
+    /// sets of the `PartitionVec<T>` in to account at all.
+    ///
+    /// This will take `O(N²)` time to check that the indices are distinct.
+    ///
+    /// # Errors
+    ///
+    /// If any index is out of bounds or if an index is given more than once, nothing is
+    /// borrowed and the error from the slice is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use std::slice::GetDisjointMutError;
+    ///
+    /// let mut partition_vec = partition_vec![1, 2, 3, 4];
+    ///
+    /// if let Ok([first, last]) = partition_vec.get_disjoint_mut([0, 3]) {
+    ///     std::mem::swap(first, last);
+    /// }
+    /// assert!(partition_vec.as_slice() == &[4, 2, 3, 1]);
+    ///
+    /// assert!(partition_vec.get_disjoint_mut([1, 1]) == Err(GetDisjointMutError::OverlappingIndices));
+    /// assert!(partition_vec.get_disjoint_mut([1, 4]) == Err(GetDisjointMutError::IndexOutOfBounds));
+    /// # }
+    /// ```
+    ///
+    /// [`slice::get_disjoint_mut`]: https://doc.rust-lang.org/std/primitive.slice.html#method.get_disjoint_mut
+    #[inline]
+    pub fn get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[&mut T; N], std::slice::GetDisjointMutError> {
+        self.data.get_disjoint_mut(indices)
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &value)` where `i` is the index of the value and
+    /// `value` is the value itself.
+    ///
+    /// The order the elements are returned in is not specified.
+    ///
+    /// While iterating, the members of the set are made to point directly to the
+    /// representative of the set. The rank of the set is left untouched, this only
+    /// makes later calls faster.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => "first set",
+    ///     'b' => "first set",
+    ///     'c' => "second set",
+    ///     'd' => "second set",
+    /// ];
+    ///
+    /// let mut done = [0, 0, 0, 0];
+    /// for (index, value) in partition_vec.set(0) {
+    ///     assert!(*value == 'a' || *value == 'b');
+    ///     done[index] += 1;
+    /// }
+    /// for (index, value) in partition_vec.set(1) {
+    ///     assert!(*value == 'a' || *value == 'b');
+    ///     done[index] += 1;
+    /// }
+    /// for (index, value) in partition_vec.set(2) {
+    ///     assert!(*value == 'c' || *value == 'd');
+    ///     done[index] += 1;
+    /// }
+    /// // We visited the first set twice and the second set once.
+    /// assert!(done == [2, 2, 1, 1]);
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn set(&self, index: usize) -> Set<'_, T> {
+        let root = self.find_final(index);
+
+        Set::new(&self.data, &self.meta, root)
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to
+    /// in ascending order of their indices.
+    ///
+    /// The iterator returned yields pairs `(i, &value)` where `i` is the index of the value and
+    /// `value` is the value itself.
+    ///
+    /// Unlike the `set` method this collects and sorts the members of the set before
+    /// returning them, so this will take `O(m log m)` time where `m` is the size of the set
+    /// and it will allocate a `Vec` of `m` elements.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec!['a', 'b', 'c', 'd', 'e'];
+    /// partition_vec.union(4, 1);
+    /// partition_vec.union(3, 4);
+    /// partition_vec.union(0, 3);
+    ///
+    /// let indices: Vec<usize> = partition_vec.set_sorted(3).map(|(index, _)| index).collect();
+    /// assert!(indices == [0, 1, 3, 4]);
+    ///
+    /// for window in indices.windows(2) {
+    ///     assert!(window[0] < window[1]);
+    /// }
+    ///
+    /// let values: Vec<char> = partition_vec.set_sorted(1).map(|(_, &value)| value).collect();
+    /// assert!(values == ['a', 'b', 'd', 'e']);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn set_sorted(&self, index: usize) -> std::vec::IntoIter<(usize, &T)> {
+        let mut members: Vec<_> = self.set(index).collect();
+        members.sort_unstable_by_key(|&(index, _)| index);
+
+        members.into_iter()
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &mut value)` where `i` is the index of the value and
+    /// `value` is the value itself.
+    ///
+    /// The order the elements are returned in is not specified.
+    ///
+    /// While iterating, the members of the set are made to point directly to the
+    /// representative of the set. The rank of the set is left untouched, this only
+    /// makes later calls faster.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 'a',
+    ///     0 => 'b',
+    ///     0 => 'b',
+    ///     0 => 'c',
+    /// ];
+    ///
+    /// assert!(partition_vec.as_slice() == &[0, 0, 0, 0]);
+    /// for (index, value) in partition_vec.set_mut(2) {
+    ///     assert!(index == 1 || index == 2);
+    ///     *value += 1;
+    /// }
+    /// assert!(partition_vec.as_slice() == &[0, 1, 1, 0]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_mut(&mut self, index: usize) -> SetMut<'_, T> {
+        let root = self.find_final(index);
+
+        SetMut::new(&mut self.data, &self.meta, root)
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to or `None`
+    /// if `index` is out of bounds.
+    ///
+    /// This is the same as [`set`] but does not panic, which is useful when the index
+    /// comes from user input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec!['a', 'b', 'c']);
+    /// partition_vec.union(0, 2);
+    ///
+    /// assert!(partition_vec.get_set(2).map(Iterator::count) == Some(2));
+    /// assert!(partition_vec.get_set(3).is_none());
+    /// ```
+    ///
+    /// [`set`]: struct.PartitionVec.html#method.set
+    #[inline]
+    #[must_use]
+    pub fn get_set(&self, index: usize) -> Option<Set<'_, T>> {
+        if self.is_valid_index(index) {
+            Some(self.set(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to with mutable
+    /// access to the values or `None` if `index` is out of bounds.
+    ///
+    /// This is the same as [`set_mut`] but does not panic, which is useful when the index
+    /// comes from user input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![0, 0, 0]);
+    /// partition_vec.union(0, 2);
+    ///
+    /// if let Some(set) = partition_vec.get_set_mut(0) {
+    ///     for (_, value) in set {
+    ///         *value += 1;
+    ///     }
+    /// }
+    /// assert!(partition_vec.get_set_mut(5).is_none());
+    /// assert!(partition_vec.as_slice() == [1, 0, 1]);
+    /// ```
+    ///
+    /// [`set_mut`]: struct.PartitionVec.html#method.set_mut
+    #[inline]
+    pub fn get_set_mut(&mut self, index: usize) -> Option<SetMut<'_, T>> {
+        if self.is_valid_index(index) {
+            Some(self.set_mut(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable references to the values of all elements in the set of `index`.
+    ///
+    /// The references are in the same order as they are returned by `set_mut`.
+    /// Unlike the iterator returned by `set_mut` all references are available at once,
+    /// so they can for example be given to `rayon` to mutate the values in parallel.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the size of the set.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     1 => 'a',
+    ///     2 => 'b',
+    ///     3 => 'a',
+    ///     4 => 'a',
+    /// ];
+    ///
+    /// let mut values = partition_vec.set_values_mut(2);
+    /// assert!(values.len() == 3);
+    ///
+    /// // We can hold on to multiple references at the same time.
+    /// let (first, rest) = values.split_first_mut().unwrap();
+    /// for value in rest {
+    ///     **first += **value;
+    ///     **value = 0;
+    /// }
+    ///
+    /// let mut values: Vec<_> = partition_vec.iter().cloned().collect();
+    /// values.sort();
+    /// assert!(values == [0, 0, 2, 8]);
+    /// # }
+    /// ```
+    pub fn set_values_mut(&mut self, index: usize) -> Vec<&mut T> {
+        let len = self.len_of_set(index);
+        let mut values = Vec::with_capacity(len);
+        values.extend(self.set_mut(index).map(|(_, value)| value));
+
+        values
+    }
+
+    /// Returns an iterator over all sets of the `PartitionVec<T>`.
+    ///
+    /// The iterator returned yields `Set` iterators.
+    /// These `Set` iterators yield pairs `(i, &value)` where `i` is the index of
+    /// the value and `value` is the value itself.
+    ///
+    /// The sets are returned in order by there first member.
+    /// The order the elements of a `Set` are returned in is not specified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     0 => 'a',
+    ///     0 => 'a',
+    ///     2 => 'b',
+    ///     2 => 'b',
+    ///     4 => 'c',
+    ///     4 => 'c',
+    /// ];
+    ///
+    /// for set in partition_vec.all_sets() {
+    ///     let mut count = 0;
+    ///     for (index, value) in set {
+    ///         assert!(index == *value || index == *value + 1);
+    ///         count += 1;
+    ///     }
+    ///     assert!(count == 2);
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn all_sets(&self) -> AllSets<'_, T> {
+        AllSets::new(&self.data, &self.meta)
+    }
+
+    /// Returns an iterator over all sets of the `PartitionVec<T>` from the
+    /// largest to the smallest set.
+    ///
+    /// The iterator returned yields `Set` iterators just like `all_sets`.
+    /// Sets of the same size are returned in order by their first member.
+    ///
+    /// Unlike `all_sets` this collects all sets in a `Vec` before returning them.
+    /// This method will be executed in `O(n α(n) + s log s)` time where `s` is the
+    /// amount of sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 1,
+    ///     'd' => 2,
+    ///     'e' => 1,
+    ///     'f' => 2,
+    ///     'g' => 3,
+    /// ];
+    ///
+    /// let lengths: Vec<usize> = partition_vec
+    ///     .all_sets_sorted_by_len()
+    ///     .map(|set| set.count())
+    ///     .collect();
+    /// assert!(lengths == [3, 2, 1, 1]);
+    ///
+    /// let first_members: Vec<char> = partition_vec
+    ///     .all_sets_sorted_by_len()
+    ///     .map(|set| *set.map(|(_, value)| value).min().unwrap())
+    ///     .collect();
+    /// assert!(first_members == ['b', 'd', 'a', 'g']);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn all_sets_sorted_by_len(&self) -> std::vec::IntoIter<Set<'_, T>> {
+        self.all_sets_sorted_by_key(|set| std::cmp::Reverse(set.clone().count()))
+    }
+
+    /// Returns an iterator over all sets of the `PartitionVec<T>` sorted by the given key.
+    ///
+    /// The iterator returned yields `Set` iterators just like `all_sets`.
+    /// The `key` function is called once for every set.
+    /// Sets with the same key are returned in order by their first member.
+    ///
+    /// Unlike `all_sets` this collects all sets in a `Vec` before returning them.
+    /// This method will be executed in `O(n α(n) + s log s)` time where `s` is the
+    /// amount of sets, not counting the time spend in `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     5 => 'a',
+    ///     1 => 'b',
+    ///     3 => 'a',
+    ///     2 => 'c',
+    ///     4 => 'b',
+    /// ];
+    ///
+    /// // We sort the sets by the sum of their values.
+    /// let sums: Vec<i32> = partition_vec
+    ///     .all_sets_sorted_by_key(|set| set.clone().map(|(_, &value)| value).sum::<i32>())
+    ///     .map(|set| set.map(|(_, &value)| value).sum())
+    ///     .collect();
+    /// assert!(sums == [2, 5, 8]);
+    ///
+    /// // Every element is still returned exactly once.
+    /// let mut indices: Vec<usize> = partition_vec
+    ///     .all_sets_sorted_by_key(|set| set.clone().count())
+    ///     .flatten()
+    ///     .map(|(index, _)| index)
+    ///     .collect();
+    /// indices.sort();
+    /// assert!(indices == [0, 1, 2, 3, 4]);
+    /// # }
+    /// ```
+    pub fn all_sets_sorted_by_key<K, F>(&self, mut key: F) -> std::vec::IntoIter<Set<'_, T>>
+    where
+        K: Ord,
+        F: FnMut(&Set<'_, T>) -> K,
+    {
+        let mut sets: Vec<_> = self.all_sets().map(|set| (key(&set), set)).collect();
+        // This sort is stable so sets with the same key stay in order by their first member.
+        sets.sort_by(|(first_key, _), (second_key, _)| first_key.cmp(second_key));
+
+        let sets: Vec<_> = sets.into_iter().map(|(_, set)| set).collect();
+
+        sets.into_iter()
+    }
+
+    /// Returns an iterator over all sets of the `PartitionVec<T>`.
+    ///
+    /// The iterator returned yields `SetMut` iterators.
+    /// These `SetMut` iterators yield pairs `(i, &mut value)` where `i` is the index of
+    /// the value and `value` is the value itself.
+    ///
+    /// The sets are returned in order by there first member.
+    /// The order the elements of a `SetMut` are returned in is not specified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 'a',
+    ///     0 => 'b',
+    ///     0 => 'a',
+    ///     0 => 'b',
+    ///     0 => 'c',
+    ///     0 => 'c',
+    /// ];
+    ///
+    /// assert!(partition_vec.as_slice() == &[0, 0, 0, 0, 0, 0]);
+    ///
+    /// for (set_number, set_mut) in partition_vec.all_sets_mut().enumerate() {
+    ///     for (index, value) in set_mut {
+    ///         assert!(index < 6);
+    ///         *value = set_number;
+    ///     }
+    /// }
+    ///
+    /// assert!(partition_vec.as_slice() == &[0, 1, 0, 1, 2, 2]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn all_sets_mut(&mut self) -> AllSetsMut<'_, T> {
+        AllSetsMut::new(&mut self.data, &self.meta)
+    }
+
+    /// Returns an iterator over the representative of every set.
+    ///
+    /// The iterator returned yields pairs `(root, &mut value)` where `root` is the index of
+    /// the representative, once for every set in order of these indices.
+    /// The representatives are the same as the ones used by [`Root`] indexing.
+    ///
+    /// This will take `O(n)` time to iterate over all sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use partitions::partition_vec::Root;
+    ///
+    /// let mut partition_vec = partition_vec![0; 6];
+    /// partition_vec.union(0, 3);
+    /// partition_vec.union(1, 4);
+    /// partition_vec.union(4, 5);
+    ///
+    /// // We count the members of every set at its representative.
+    /// for index in 0..partition_vec.len() {
+    ///     partition_vec[Root(index)] += 1;
+    /// }
+    ///
+    /// let mut counts: Vec<usize> = partition_vec.roots_mut().map(|(_, count)| *count).collect();
+    /// counts.sort();
+    /// assert!(counts == [1, 2, 3]);
+    ///
+    /// for (_, count) in partition_vec.roots_mut() {
+    ///     *count = 0;
+    /// }
+    /// assert!(partition_vec.iter().all(|&count| count == 0));
+    /// # }
+    /// ```
+    ///
+    /// [`Root`]: struct.Root.html
+    pub fn roots_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.data
+            .iter_mut()
+            .zip(&self.meta)
+            .enumerate()
+            .filter(|&(index, (_, meta))| meta.parent() == index)
+            .map(|(index, (value, _))| (index, value))
+    }
+
+    /// Retains only the sets for which `f` returns `true`.
+    ///
+    /// The function `f` is called once for every set with a `SetMut` iterator over that set,
+    /// this allows mutating the elements of the set before deciding whether to keep it.
+    /// All elements of the sets for which `f` returns `false` are removed.
+    /// The remaining elements keep their order and their sets but their indices are
+    /// shifted to the left to fill the gaps.
+    ///
+    /// The sets are visited in order by their first member.
+    /// This will take `O(n α(n))` time, not counting the time spend in `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     1 => 'a',
+    ///     2 => 'b',
+    ///     3 => 'a',
+    ///     4 => 'c',
+    ///     5 => 'b',
+    ///     6 => 'c',
+    /// ];
+    ///
+    /// // We keep the sets with an even sum and double their values.
+    /// partition_vec.retain_sets(|set| {
+    ///     let mut sum = 0;
+    ///     for (_, value) in set {
+    ///         sum += *value;
+    ///         *value *= 2;
+    ///     }
+    ///
+    ///     sum % 2 == 0
+    /// });
+    ///
+    /// assert!(partition_vec.as_slice() == &[2, 6, 8, 12]);
+    /// assert!(partition_vec.same_set(0, 1));
+    /// assert!(partition_vec.same_set(2, 3));
+    /// assert!(partition_vec.other_sets(1, 2));
+    /// # }
+    /// ```
+    pub fn retain_sets<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut SetMut<'_, T>) -> bool,
+    {
+        let len = self.len();
+
+        // We store for every root if its set will be kept.
+        let mut keep_root = bit_vec![false; len];
+        for mut set in self.all_sets_mut() {
+            let root = set.root;
+            keep_root.set(root, f(&mut set));
+        }
+
+        // We compute the new index of every element that is kept.
+        let mut new_indices = Vec::with_capacity(len);
+        let mut keep = bit_vec![false; len];
+        let mut new_len = 0;
+        for i in 0..len {
+            new_indices.push(new_len);
+
+            if keep_root[self.find(i)] {
+                keep.set(i, true);
+                new_len += 1;
+            }
+        }
+
+        // The removed elements are moved to the back while the kept elements are moved to
+        // their new indices. A kept element only points to elements of the same set so these
+        // are kept as well.
+        let mut current = 0;
+        for i in 0..len {
+            if keep[i] {
+                let parent = new_indices[self.meta[i].parent()];
+                let link = new_indices[self.meta[i].link()];
+
+                self.data.swap(current, i);
+                self.meta.swap(current, i);
+                self.meta[current].set_parent(parent);
+                self.meta[current].set_link(link);
+
+                current += 1;
+            }
+        }
+
+        self.data.truncate(new_len);
+        self.meta.truncate(new_len);
+    }
+
+    /// Removes all elements of the set that `index` belongs to and returns an iterator
+    /// over their values in order of their indices.
+    ///
+    /// The remaining elements keep their order and their sets but their indices are
+    /// shifted to the left to fill the gaps.
+    /// The elements are removed immediately, even if the returned iterator is not used.
+    ///
+    /// This will take `O(n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    ///     'e' => 1,
+    /// ];
+    ///
+    /// let drained: Vec<char> = partition_vec.drain_set(2).collect();
+    /// assert!(drained == ['a', 'c']);
+    ///
+    /// assert!(partition_vec.as_slice() == &['b', 'd', 'e']);
+    /// assert!(partition_vec.same_set(0, 2));
+    /// assert!(partition_vec.is_singleton(1));
+    /// # }
+    /// ```
+    pub fn drain_set(&mut self, index: usize) -> std::vec::IntoIter<T> {
+        let len = self.len();
+
+        let mut removed = bit_vec![false; len];
+        for (i, _) in self.set(index) {
+            removed.set(i, true);
+        }
+
+        // We compute the new index of every element that is kept.
+        let mut new_indices = Vec::with_capacity(len);
+        let mut new_len = 0;
+        for i in 0..len {
+            new_indices.push(new_len);
+
+            if !removed[i] {
+                new_len += 1;
+            }
+        }
+
+        // A kept element only points to elements of the same set so these are kept as well.
+        let mut current = 0;
+        for i in 0..len {
+            if !removed[i] {
+                let parent = new_indices[self.meta[i].parent()];
+                let link = new_indices[self.meta[i].link()];
+
+                self.meta.swap(current, i);
+                self.meta[current].set_parent(parent);
+                self.meta[current].set_link(link);
+
+                current += 1;
+            }
+        }
+        self.meta.truncate(new_len);
+
+        let mut i = 0;
+        let drained: Vec<T> = self
+            .data
+            .extract_if(.., |_| {
+                i += 1;
+                removed[i - 1]
+            })
+            .collect();
+
+        drained.into_iter()
+    }
+
+    /// Converts the `PartitionVec<T>` into an iterator over its sets that yields the values of
+    /// every set in a `Vec<T>`.
+    ///
+    /// The values are moved so they do not have to be cloned.
+    /// The sets are returned in order by their first member like with [`all_sets`] and the
+    /// values of a set are in the order of their indices.
+    ///
+    /// The values are divided over the sets when this method is called so it will be executed
+    /// in `O(n α(n))` time where `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     String::from("a") => 0,
+    ///     String::from("b") => 1,
+    ///     String::from("c") => 0,
+    ///     String::from("d") => 2,
+    ///     String::from("e") => 1,
+    /// ];
+    ///
+    /// let sets: Vec<Vec<String>> = partition_vec.into_sets().collect();
+    /// assert!(sets == [vec!["a", "c"], vec!["b", "e"], vec!["d"]]);
+    /// # }
+    /// ```
+    ///
+    /// [`all_sets`]: struct.PartitionVec.html#method.all_sets
+    #[must_use]
+    pub fn into_sets(self) -> IntoSets<T> {
+        let len = self.len();
+        let mut labels = vec![!0; len];
+        let mut sets: Vec<Vec<T>> = Vec::new();
+
+        for (index, value) in self.data.into_iter().enumerate() {
+            let root = find_in(&self.meta, index);
+            if labels[root] == !0 {
+                labels[root] = sets.len();
+                sets.push(Vec::new());
+            }
+
+            sets[labels[root]].push(value);
+        }
+
+        IntoSets {
+            sets: sets.into_iter(),
+        }
+    }
+
+    /// Removes consecutive repeated elements according to the `PartialEq` implementation.
+    ///
+    /// Like `Vec::dedup` only the first element of every run of equal values is kept.
+    /// The removed elements leave their sets while the kept elements stay in the same sets
+    /// as before, even if equal values were in different sets.
+    /// The kept elements are shifted to the front, an element that is kept moves from
+    /// index `i` to index `i - r` where `r` is the amount of removed elements before `i`.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     1 => 'a',
+    ///     1 => 'b',
+    ///     2 => 'b',
+    ///     2 => 'a',
+    ///     3 => 'a',
+    /// ];
+    ///
+    /// partition_vec.dedup();
+    ///
+    /// assert!(partition_vec.as_slice() == [1, 2, 3]);
+    /// assert!(partition_vec.same_set(0, 2));
+    /// assert!(partition_vec.is_singleton(1));
+    /// # }
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|first, second| first == second);
+    }
+
+    /// Removes all but the first of consecutive elements that resolve to the same key.
+    ///
+    /// This works like [`dedup`] so the removed elements leave their sets and the kept
+    /// elements are shifted to the front.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![10, 11, 20, 21, 30]);
+    /// partition_vec.union(1, 4);
+    /// partition_vec.union(2, 3);
+    ///
+    /// partition_vec.dedup_by_key(|value| *value / 10);
+    ///
+    /// assert!(partition_vec.as_slice() == [10, 20, 30]);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// ```
+    ///
+    /// [`dedup`]: struct.PartitionVec.html#method.dedup
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|first, second| key(first) == key(second));
+    }
+
+    /// Removes all but the first of consecutive elements that satisfy the given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements and decides if the first
+    /// element is removed.
+    /// Like `Vec::dedup_by` the second element is the last element that was kept so the
+    /// elements are passed in the opposite order from their order in the `PartitionVec<T>`.
+    /// This works like [`dedup`] so the removed elements leave their sets and the kept
+    /// elements are shifted to the front.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec!["a", "A", "b", "B", "B"]);
+    /// partition_vec.union(1, 2);
+    /// partition_vec.union(0, 4);
+    ///
+    /// partition_vec.dedup_by(|first, second| first.eq_ignore_ascii_case(second));
+    ///
+    /// assert!(partition_vec.as_slice() == ["a", "b"]);
+    /// assert!(!partition_vec.same_set(0, 1));
+    /// ```
+    ///
+    /// [`dedup`]: struct.PartitionVec.html#method.dedup
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.len();
+        let mut removed = bit_vec![false; len];
+        let mut any_removed = false;
+
+        let mut last_kept = 0;
+        for i in 1..len {
+            let (kept, rest) = self.data.split_at_mut(i);
+            if same_bucket(&mut rest[0], &mut kept[last_kept]) {
+                removed.set(i, true);
+                any_removed = true;
+            } else {
+                last_kept = i;
+            }
+        }
+
+        if any_removed {
+            self.remove_marked(&removed);
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`, like `Vec::retain_mut`.
+    ///
+    /// The function `f` is called once for every element in order and may change the value
+    /// before deciding whether to keep it.
+    /// The removed elements leave their sets while the kept elements keep their order and
+    /// their sets but their indices are shifted to the left to fill the gaps.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     1 => 'a',
+    ///     2 => 'b',
+    ///     3 => 'a',
+    ///     4 => 'a',
+    ///     5 => 'b',
+    /// ];
+    ///
+    /// // We keep the odd values and square them.
+    /// partition_vec.retain_mut(|value| {
+    ///     let keep = *value % 2 == 1;
+    ///     *value *= *value;
+    ///
+    ///     keep
+    /// });
+    ///
+    /// assert!(partition_vec.as_slice() == [1, 9, 25]);
+    /// assert!(partition_vec.same_set(0, 1));
+    /// assert!(partition_vec.is_singleton(2));
+    /// # }
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut removed = bit_vec![false; self.len()];
+        let mut any_removed = false;
+
+        for (i, value) in self.data.iter_mut().enumerate() {
+            if !f(value) {
+                removed.set(i, true);
+                any_removed = true;
+            }
+        }
+
+        if any_removed {
+            self.remove_marked(&removed);
+        }
+    }
+
+    /// Removes the elements whose bit is set in `removed` and keeps the sets of the other elements.
+    ///
+    /// The sets are rebuilt so every kept set has its first kept member as representative
+    /// and all other members as direct children.
+    fn remove_marked(&mut self, removed: &bit_vec::BitVec) {
+        let len = self.len();
+
+        // For every old root we store the new index of its first and last kept member.
+        let mut first = vec![!0; len];
+        let mut last = vec![!0; len];
+        let mut meta: Vec<Metadata> = Vec::with_capacity(len);
+        for i in 0..len {
+            if removed[i] {
+                continue;
+            }
+
+            let root = self.find_final(i);
+            let new_index = meta.len();
+            let new_meta = Metadata::new(new_index);
+            if first[root] == !0 {
+                first[root] = new_index;
+            } else {
+                new_meta.set_parent(first[root]);
+                meta[last[root]].set_link(new_index);
+            }
+            last[root] = new_index;
+            meta.push(new_meta);
+        }
+
+        for (&first, &last) in first.iter().zip(&last) {
+            if first != last {
+                meta[last].set_link(first);
+                meta[first].set_rank(1);
+            }
+        }
+        self.meta = meta;
+
+        let mut i = 0;
+        self.data.retain(|_| {
+            i += 1;
+            !removed[i - 1]
+        });
+    }
+
+    /// Reorders the elements so that the members of every set are stored next to each other.
+    ///
+    /// This can speed up iterating over the sets once the members of many sets have been
+    /// scattered by calls to `union`.
+    /// The sets are placed in order of their first appearance in the `PartitionVec<T>`.
+    ///
+    /// Every index into the `PartitionVec<T>` is invalidated by this method.
+    /// The returned permutation maps the old indices to the new indices,
+    /// the element at index `i` is moved to index `permutation[i]`.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let original = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    ///     'e' => 1,
+    /// ];
+    /// let mut partition_vec = original.clone();
+    ///
+    /// let permutation = partition_vec.reorder_by_set();
+    ///
+    /// for (old_index, &new_index) in permutation.iter().enumerate() {
+    ///     assert!(partition_vec[new_index] == original[old_index]);
+    /// }
+    /// assert!(partition_vec.same_set(permutation[1], permutation[4]));
+    ///
+    /// // The members of every set are now contiguous.
+    /// for set in partition_vec.all_sets() {
+    ///     let mut indices: Vec<usize> = set.map(|(index, _)| index).collect();
+    ///     indices.sort();
+    ///     assert!(indices.windows(2).all(|pair| pair[0] + 1 == pair[1]));
+    /// }
+    /// # }
+    /// ```
+    pub fn reorder_by_set(&mut self) -> Vec<usize> {
+        let mut permutation = vec![0; self.len()];
+        let mut new_index = 0;
+        for set in self.all_sets() {
+            for (old_index, _) in set {
+                permutation[old_index] = new_index;
+                new_index += 1;
+            }
+        }
+
+        // We move every element to its new index one cycle of the permutation at a time.
+        let mut remaining = permutation.clone();
+        for i in 0..remaining.len() {
+            while remaining[i] != i {
+                let target = remaining[i];
+                self.data.swap(i, target);
+                self.meta.swap(i, target);
+                remaining.swap(i, target);
+            }
+        }
+
+        for meta in &self.meta {
+            meta.set_parent(permutation[meta.parent()]);
+            meta.set_link(permutation[meta.link()]);
+        }
+
+        permutation
+    }
+
+    /// Makes the sets of `self` the same as the sets of `source` without changing any values.
+    ///
+    /// Afterwards two indices share a set in `self` if and only if they share a set in `source`.
+    /// This is useful to keep multiple `PartitionVec`s over the same indices in sync.
+    /// The allocation of `self` is reused.
+    ///
+    /// This method will be executed in `O(n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `self` and `source` do not have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let names = partition_vec![
+    ///     "a" => 0,
+    ///     "b" => 1,
+    ///     "c" => 0,
+    /// ];
+    /// let mut weights = partition_vec![1.5, 2.0, 0.5];
+    ///
+    /// weights.copy_partition_from(&names);
+    ///
+    /// assert!(weights.as_slice() == &[1.5, 2.0, 0.5]);
+    /// assert!(weights.same_set(0, 2));
+    /// assert!(weights.is_singleton(1));
+    /// # }
+    /// ```
+    pub fn copy_partition_from<U>(&mut self, source: &PartitionVec<U>) {
+        assert!(
+            self.len() == source.len(),
+            "The partitions have different lengths {} and {}.",
+            self.len(),
+            source.len(),
+        );
+
+        // The metadata only refers to indices so it does not depend on the values.
+        self.meta.clone_from(&source.meta);
+    }
+
+    /// Attaches the given labels to the sets of the `PartitionVec<T>`.
+    ///
+    /// Every pair `(index, label)` gives `label` to the set containing `index`,
+    /// when a set is given multiple labels the last one is kept.
+    /// The returned [`SetLabels`] can be used to look up the label of the set of any index.
+    ///
+    /// The labels are stored by the root of each set.
+    /// Lookups and iteration only compress paths which never changes a root,
+    /// but methods that change the sets such as `union`, `make_singleton` or `remove`
+    /// can give a set a new root and invalidate its label.
+    /// Sets can be joined while keeping their labels with [`SetLabels::union`].
+    ///
+    /// This method will be executed in `O(m α(n))` time where `m` is the amount of labels and
+    /// `α` is the inverse Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If any of the indices is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    /// ];
+    ///
+    /// let labels = partition_vec.relabel(vec![(2, "first"), (1, "second")]);
+    ///
+    /// // Iterating compresses the paths in the sets but keeps the labels.
+    /// assert!(partition_vec.all_sets().count() == 3);
+    ///
+    /// assert!(labels.get(&partition_vec, 0) == Some(&"first"));
+    /// assert!(labels.get(&partition_vec, 1) == Some(&"second"));
+    /// assert!(labels.get(&partition_vec, 2) == Some(&"first"));
+    /// assert!(labels.get(&partition_vec, 3) == None);
+    /// # }
+    /// ```
+    ///
+    /// [`SetLabels`]: struct.SetLabels.html
+    /// [`SetLabels::union`]: struct.SetLabels.html#method.union
+    pub fn relabel<L, I>(&self, labels: I) -> SetLabels<L>
+    where
+        I: IntoIterator<Item = (usize, L)>,
+    {
+        let mut set_labels = SetLabels {
+            labels: std::collections::HashMap::new(),
+        };
+        for (index, label) in labels {
+            set_labels.insert(self, index, label);
+        }
+
+        set_labels
+    }
+
+    /// Returns `true` if `self` and `other` have the same values and the same internal
+    /// representation of their sets.
+    ///
+    /// The `==` operator only checks that both have the same values and the same sets,
+    /// while this also checks that every element has the same parent, rank and link.
+    /// So this implies `==` but two equal partitions can be built differently, for example
+    /// by joining the same indices in another order.
+    /// Methods that only read the sets can still compress paths, which changes the parents
+    /// and can make two representations that were identical differ.
+    ///
+    /// This method will be executed in `O(n)` time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut first = partitions::PartitionVec::from(vec!['a', 'b', 'c']);
+    /// first.union(0, 1);
+    /// first.union(1, 2);
+    /// let mut second = partitions::PartitionVec::from(vec!['a', 'b', 'c']);
+    /// second.union(2, 1);
+    /// second.union(1, 0);
+    ///
+    /// assert!(first == second);
+    /// assert!(!first.eq_exact(&second));
+    /// assert!(first.eq_exact(&first.clone()));
+    /// ```
+    #[must_use]
+    pub fn eq_exact(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.data == other.data
+            && self.meta.len() == other.meta.len()
+            && self.meta.iter().zip(&other.meta).all(|(first, second)| {
+                first.parent() == second.parent()
+                    && first.link() == second.link()
+                    && first.rank() == second.rank()
+            })
+    }
+
+    /// Returns a hash of the sets, ignoring the values.
+    ///
+    /// Every element is labeled with the number of its set in order of first appearance,
+    /// so two `PartitionVec`s with the same length and the same sets give the same hash
+    /// whatever their values are or how the sets were built.
+    /// Different sets give different hashes except for rare collisions.
+    /// This is useful as a cheap key when memoizing on the sets alone.
+    ///
+    /// The hash is computed with `DefaultHasher` which is not guaranteed to give the same
+    /// result between Rust releases, so it should not be stored.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let first = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    /// ];
+    /// let second = partition_vec![
+    ///     1.5 => 7,
+    ///     2.5 => 3,
+    ///     3.5 => 7,
+    /// ];
+    ///
+    /// assert!(first.structure_hash() == second.structure_hash());
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn structure_hash(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.len().hash(&mut hasher);
+
+        let mut labels = vec![usize::MAX; self.len()];
+        let mut next_label = 0;
+        for i in 0..self.len() {
+            let root = self.find(i);
+            if labels[root] == usize::MAX {
+                labels[root] = next_label;
+                next_label += 1;
+            }
+            labels[root].hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Returns `true` if every set of `self` is contained in a single set of `coarser`.
+    ///
+    /// This means that any two indices that share a set in `self` also share a set in `coarser`.
+    /// Every partition refines itself and a partition with only singletons refines
+    /// every other partition of the same length.
+    /// The values are not compared.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `self` and `coarser` do not have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let fine = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    /// ];
+    /// let coarse = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 1,
+    /// ];
+    /// let other = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    ///     'd' => 2,
+    /// ];
+    ///
+    /// assert!(fine.refines(&coarse));
+    /// assert!(!coarse.refines(&fine));
+    /// assert!(fine.refines(&fine));
+    ///
+    /// assert!(!fine.refines(&other));
+    /// assert!(!other.refines(&fine));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn refines<U>(&self, coarser: &PartitionVec<U>) -> bool {
+        assert!(
+            self.len() == coarser.len(),
+            "The partitions have different lengths {} and {}.",
+            self.len(),
+            coarser.len(),
+        );
+
+        // We map every root of `self` to the root of `coarser` its set has to be contained in.
+        let mut map = std::collections::HashMap::with_capacity(self.len());
+
+        (0..self.len()).all(|i| {
+            let coarser_root = coarser.find(i);
+
+            *map.entry(self.find(i)).or_insert(coarser_root) == coarser_root
+        })
+    }
+
+    /// Compares the sets of `self` with the sets of `other` and returns how they changed.
+    ///
+    /// The indices of `self` and `other` are split in pieces where two indices share a piece
+    /// if they share a set in both partitions.
+    /// A set of `other` that contains pieces of multiple sets of `self` is reported as merged,
+    /// a set of `self` whose pieces are in multiple sets of `other` is reported as split
+    /// and a set that is the same in both is reported as unchanged.
+    /// A set can be both split and merged if its pieces are joined with pieces of other sets.
+    /// The values are not compared.
+    ///
+    /// Every set and piece is sorted and the sets and pieces are ordered by their first index.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `self` and `other` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use partitions::partition_vec::{MergeEvent, SplitEvent};
+    ///
+    /// let before = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    ///     'e' => 3,
+    ///     'f' => 3,
+    /// ];
+    /// let mut after = before.clone();
+    /// after.union(1, 3);
+    /// after.make_singleton(5);
+    ///
+    /// let diff = before.diff(&after);
+    /// assert!(diff.merged == [MergeEvent { set: vec![1, 3], parts: vec![vec![1], vec![3]] }]);
+    /// assert!(diff.split == [SplitEvent { set: vec![4, 5], parts: vec![vec![4], vec![5]] }]);
+    /// assert!(diff.unchanged == [vec![0, 2]]);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn diff<U>(&self, other: &PartitionVec<U>) -> PartitionDiff {
+        use std::collections::HashMap;
+
+        assert!(
+            self.len() == other.len(),
+            "The partitions have different lengths {} and {}.",
+            self.len(),
+            other.len(),
+        );
+
+        // We label the sets of both partitions and their pieces in order of their first index.
+        let mut self_labels = HashMap::new();
+        let mut other_labels = HashMap::new();
+        let mut piece_labels = HashMap::new();
+        let mut self_sets: Vec<Vec<usize>> = Vec::new();
+        let mut other_sets: Vec<Vec<usize>> = Vec::new();
+        let mut pieces: Vec<Vec<usize>> = Vec::new();
+        // The pieces of every set.
+        let mut self_pieces: Vec<Vec<usize>> = Vec::new();
+        let mut other_pieces: Vec<Vec<usize>> = Vec::new();
+
+        for i in 0..self.len() {
+            let self_label = label_of_root(&mut self_labels, self.find(i));
+            let other_label = label_of_root(&mut other_labels, other.find(i));
+            if self_label == self_sets.len() {
+                self_sets.push(Vec::new());
+                self_pieces.push(Vec::new());
+            }
+            if other_label == other_sets.len() {
+                other_sets.push(Vec::new());
+                other_pieces.push(Vec::new());
+            }
+
+            let new_piece = piece_labels.len();
+            let piece = *piece_labels
+                .entry((self_label, other_label))
+                .or_insert(new_piece);
+            if piece == pieces.len() {
+                pieces.push(Vec::new());
+                self_pieces[self_label].push(piece);
+                other_pieces[other_label].push(piece);
+            }
+
+            self_sets[self_label].push(i);
+            other_sets[other_label].push(i);
+            pieces[piece].push(i);
+        }
+
+        let parts = |piece_labels: &[usize]| -> Vec<Vec<usize>> {
+            piece_labels
+                .iter()
+                .map(|&piece| pieces[piece].clone())
+                .collect()
+        };
+
+        let mut diff = PartitionDiff::default();
+        for (set, piece_labels) in other_sets.iter().zip(&other_pieces) {
+            if piece_labels.len() > 1 {
+                diff.merged.push(MergeEvent {
+                    set: set.clone(),
+                    parts: parts(piece_labels),
+                });
+            }
+        }
+        for (set, piece_labels) in self_sets.into_iter().zip(&self_pieces) {
+            if piece_labels.len() > 1 {
+                diff.split.push(SplitEvent {
+                    set,
+                    parts: parts(piece_labels),
+                });
+            } else if other_sets[other_labels[&other.find(set[0])]].len() == set.len() {
+                diff.unchanged.push(set);
+            }
+        }
+
+        diff
+    }
+
+    /// Encodes the `PartitionVec<T>` as bytes which can be decoded with [`from_bytes`].
+    ///
+    /// The function `encode` is called for every value in order and appends the bytes of
+    /// that value to the given `Vec<u8>`.
+    /// The sets are stored as a label for every index instead of the parents and links,
+    /// so the bytes only depend on the sets and the values and can be decoded on any target
+    /// with or without the `compact` feature.
+    ///
+    /// The bytes start with the magic bytes `PVEC` and a version byte which is currently `1`.
+    /// The header is followed by the length, the label of every index and finally the
+    /// length and bytes of every value.
+    /// All integers are stored as little endian `u64` values and the sets are labeled
+    /// in order of their first index.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function, not counting the time spend in `encode`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// use partitions::PartitionVec;
+    ///
+    /// let partition_vec = partition_vec![
+    ///     7u16 => 'a',
+    ///     3 => 'b',
+    ///     5 => 'a',
+    /// ];
+    ///
+    /// let bytes = partition_vec.to_bytes(|value, bytes| bytes.extend(&value.to_le_bytes()));
+    /// assert!(bytes[..5] == *b"PVEC\x01");
+    ///
+    /// let decoded = PartitionVec::from_bytes(&bytes, |bytes| {
+    ///     Some(u16::from_le_bytes([*bytes.first()?, *bytes.get(1)?]))
+    /// });
+    /// assert!(decoded == Ok(partition_vec));
+    /// # }
+    /// ```
+    ///
+    /// [`from_bytes`]: struct.PartitionVec.html#method.from_bytes
+    #[must_use]
+    pub fn to_bytes<F>(&self, mut encode: F) -> Vec<u8>
+    where
+        F: FnMut(&T, &mut Vec<u8>),
+    {
+        let mut bytes = Vec::with_capacity(BYTES_HEADER.len() + 8 * (2 * self.len() + 1));
+        bytes.extend(&BYTES_HEADER);
+        bytes.extend(&(self.len() as u64).to_le_bytes());
+
+        let mut labels = std::collections::HashMap::new();
+        for i in 0..self.len() {
+            let label = label_of_root(&mut labels, self.find(i));
+            bytes.extend(&(label as u64).to_le_bytes());
+        }
+
+        let mut value_bytes = Vec::new();
+        for value in &self.data {
+            value_bytes.clear();
+            encode(value, &mut value_bytes);
+
+            bytes.extend(&(value_bytes.len() as u64).to_le_bytes());
+            bytes.extend(&value_bytes);
+        }
+
+        bytes
+    }
+
+    /// Decodes a `PartitionVec<T>` from bytes created by [`to_bytes`].
+    ///
+    /// The function `decode` is called for every value in order with exactly the bytes that
+    /// were appended for that value and returns `None` if they are not a valid value.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function, not counting the time spend in `decode`.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes do not start with the header of a supported version, if they end
+    /// too early or contain bytes after the last value, if the labels are not created by
+    /// `to_bytes`, if the length does not fit in the `PartitionVec<T>` or if `decode`
+    /// returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionVec;
+    /// use partitions::partition_vec::DecodeError;
+    ///
+    /// let partition_vec: PartitionVec<u8> = (0..4).collect();
+    /// let mut bytes = partition_vec.to_bytes(|&value, bytes| bytes.push(value));
+    /// let decode = |bytes: &[u8]| bytes.first().copied();
+    ///
+    /// assert!(PartitionVec::from_bytes(&bytes[..bytes.len() - 1], decode) == Err(DecodeError::UnexpectedEnd));
+    ///
+    /// bytes[4] += 1;
+    /// assert!(PartitionVec::from_bytes(&bytes, decode) == Err(DecodeError::UnsupportedVersion(2)));
+    /// ```
+    ///
+    /// [`to_bytes`]: struct.PartitionVec.html#method.to_bytes
+    pub fn from_bytes<F>(mut bytes: &[u8], mut decode: F) -> Result<Self, DecodeError>
+    where
+        F: FnMut(&[u8]) -> Option<T>,
+    {
+        let header = take_bytes(&mut bytes, BYTES_HEADER.len())?;
+        if header[..4] != BYTES_HEADER[..4] {
+            return Err(DecodeError::InvalidHeader);
+        }
+        if header[4] != BYTES_HEADER[4] {
+            return Err(DecodeError::UnsupportedVersion(header[4]));
+        }
+
+        let len = take_len(&mut bytes)?;
+        // Every index needs a label and a length so we check this before allocating.
+        // The `MAX_LEN` of the `compact` feature is far above the amount of bytes in a slice
+        // divided by 16 so this also makes sure the length fits.
+        if len > bytes.len() / 16 {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        // We store the first index of every label.
+        let mut firsts: Vec<usize> = Vec::new();
+        let mut parents = Vec::with_capacity(len);
+        for index in 0..len {
+            let label = take_len(&mut bytes)?;
+            match Ord::cmp(&label, &firsts.len()) {
+                Ordering::Less => parents.push(firsts[label]),
+                Ordering::Equal => {
+                    firsts.push(index);
+                    parents.push(index);
+                }
+                Ordering::Greater => return Err(DecodeError::InvalidLabel { index, label }),
+            }
+        }
+
+        let mut data = Vec::with_capacity(len);
+        for index in 0..len {
+            let value_len = take_len(&mut bytes)?;
+            let value_bytes = take_bytes(&mut bytes, value_len)?;
+            data.push(decode(value_bytes).ok_or(DecodeError::InvalidValue { index })?);
+        }
+
+        if !bytes.is_empty() {
+            return Err(DecodeError::TrailingBytes(bytes.len()));
+        }
+
+        let mut partition_vec = Self::from(data);
+        for (index, parent) in parents.into_iter().enumerate() {
+            partition_vec.union(parent, index);
+        }
+
+        Ok(partition_vec)
+    }
+
+    /// Converts the `PartitionVec<T>` into a [`FrozenPartition<T>`] that can no longer change
+    /// its sets.
+    ///
+    /// Every index is stored together with the representative of its set so finding the
+    /// representative only takes a single lookup.
+    /// Unlike the `PartitionVec<T>` the `FrozenPartition<T>` does not update anything
+    /// when it is queried so it can be shared between threads if `T` is `Sync`.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 1,
+    /// ];
+    ///
+    /// let frozen = partition_vec.freeze();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| assert!(frozen.same_set(0, 2)));
+    ///     scope.spawn(|| assert!(!frozen.same_set(0, 1)));
+    /// });
+    ///
+    /// assert!(frozen.representative(1) == frozen.representative(3));
+    /// assert!(frozen[3] == 'd');
+    /// # }
+    /// ```
+    ///
+    /// [`FrozenPartition<T>`]: struct.FrozenPartition.html
+    #[must_use]
+    pub fn freeze(self) -> FrozenPartition<T> {
+        let roots = (0..self.len()).map(|i| self.find(i)).collect();
+
+        FrozenPartition {
+            data: self.data,
+            roots,
+        }
+    }
+
+    /// Converts the `PartitionVec<T>` into a [`SharedPartitionVec<T>`] which can be cloned
+    /// without copying the values and metadata.
+    ///
+    /// See the documentation of [`SharedPartitionVec<T>`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let shared = partition_vec!['a', 'b', 'c'].share();
+    /// let mut copy = shared.clone();
+    ///
+    /// copy.make_mut().union(0, 2);
+    ///
+    /// assert!(copy.same_set(0, 2));
+    /// assert!(!shared.same_set(0, 2));
+    /// # }
+    /// ```
+    ///
+    /// [`SharedPartitionVec<T>`]: struct.SharedPartitionVec.html
+    #[must_use]
+    pub fn share(self) -> SharedPartitionVec<T> {
+        SharedPartitionVec {
+            inner: std::sync::Arc::new(self),
+        }
+    }
+
+    /// Returns an iterator over all elements of the `PartitionVec<T>` together with a label
+    /// for their set.
+    ///
+    /// The iterator returned yields triples `(i, label, &value)` where `i` is the index of
+    /// the value, `label` identifies the set of the value and `value` is the value itself.
+    ///
+    /// The elements are returned in order of their index.
+    /// The labels are given out in order of first appearance, the set of the first element
+    /// has label `0`, the next set that is encountered has label `1` and so on.
+    /// These are the same labels that are used by the `Debug` implementation.
+    /// Two elements have the same label if and only if they are in the same set.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => "first set",
+    ///     'b' => "second set",
+    ///     'c' => "first set",
+    ///     'd' => "third set",
+    ///     'e' => "second set",
+    /// ];
+    ///
+    /// let triples: Vec<_> = partition_vec.iter_with_sets().collect();
+    /// assert!(triples == [
+    ///     (0, 0, &'a'),
+    ///     (1, 1, &'b'),
+    ///     (2, 0, &'c'),
+    ///     (3, 2, &'d'),
+    ///     (4, 1, &'e'),
+    /// ]);
+    ///
+    /// for &(first_index, first_label, _) in &triples {
+    ///     for &(second_index, second_label, _) in &triples {
+    ///         assert!(
+    ///             (first_label == second_label) ==
+    ///             partition_vec.same_set(first_index, second_index)
+    ///         );
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn iter_with_sets(&self) -> IterWithSets<'_, T> {
+        let len = self.len();
+
+        IterWithSets {
+            partition_vec: self,
+            labels: std::collections::HashMap::new(),
+            range: 0..len,
+        }
+    }
+
+    /// Returns an iterator over all elements of the `PartitionVec<T>` together with a label
+    /// for their set that allows mutating the elements.
+    ///
+    /// The iterator returned yields triples `(i, label, &mut value)` where `i` is the index of
+    /// the value, `label` identifies the set of the value and `value` is the value itself.
+    ///
+    /// The elements and labels are returned in the same order as `iter_with_sets`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 'a',
+    ///     0 => 'b',
+    ///     0 => 'a',
+    ///     0 => 'c',
+    ///     0 => 'b',
+    /// ];
+    ///
+    /// for (_, label, value) in partition_vec.iter_with_sets_mut() {
+    ///     *value = label;
+    /// }
+    ///
+    /// assert!(partition_vec.as_slice() == &[0, 1, 0, 2, 1]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter_with_sets_mut(&mut self) -> IterWithSetsMut<'_, T> {
+        let len = self.len();
+        let (data, meta) = self.raw_parts();
+
+        IterWithSetsMut {
+            data,
+            meta,
+            labels: std::collections::HashMap::new(),
+            range: 0..len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Checks that the internal structure of the `PartitionVec<T>` is consistent.
+    ///
+    /// The following invariants are checked:
+    ///
+    /// - There is metadata for every value.
+    /// - Every parent and link is in bounds.
+    /// - The rank of every element is below the rank of its parent and below the maximum rank.
+    ///   This means that following the parents from any element always reaches a root.
+    /// - The links form disjoint cycles, no two elements link to the same element.
+    /// - All elements on a cycle of links have the same root and every root has a single cycle,
+    ///   these cycles are the sets.
+    ///
+    /// These hold for any `PartitionVec<T>` and are only useful when debugging this crate
+    /// or code built on top of it.
+    /// The first violation that is found is returned,
+    /// see [`assert_invariants`] for a version that panics.
+    ///
+    /// This method will be executed in `O(n)` time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the violated invariant together with the index where it was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![(); 5];
+    /// partition_vec.union(0, 3);
+    /// partition_vec.union(1, 3);
+    /// partition_vec.make_singleton(3);
+    ///
+    /// assert!(partition_vec.validate().is_ok());
+    /// # }
+    /// ```
+    ///
+    /// [`assert_invariants`]: struct.PartitionVec.html#method.assert_invariants
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let len = self.data.len();
+        if self.meta.len() != len {
+            return Err(InvariantViolation::LengthMismatch {
+                data_len: len,
+                meta_len: self.meta.len(),
+            });
+        }
+
+        for (index, meta) in self.meta.iter().enumerate() {
+            let (parent, link, rank) = (meta.parent(), meta.link(), meta.rank());
+            if parent >= len {
+                return Err(InvariantViolation::ParentOutOfBounds { index, parent });
+            }
+            if link >= len {
+                return Err(InvariantViolation::LinkOutOfBounds { index, link });
+            }
+            if rank > MAX_RANK || (parent != index && rank >= self.meta[parent].rank()) {
+                return Err(InvariantViolation::InvalidRank { index, rank });
+            }
+        }
+
+        // Because the ranks increase every parent chain ends in a root.
+        // We store the roots we found so every parent is only followed once.
+        let mut roots = vec![!0; len];
+        let mut path = Vec::new();
+        for index in 0..len {
+            let mut current = index;
+            while roots[current] == !0 && self.meta[current].parent() != current {
+                path.push(current);
+                current = self.meta[current].parent();
+            }
+            let root = if roots[current] == !0 {
+                current
+            } else {
+                roots[current]
+            };
+
+            roots[current] = root;
+            for element in path.drain(..) {
+                roots[element] = root;
+            }
+        }
+
+        let mut linked = bit_vec![false; len];
+        for (index, meta) in self.meta.iter().enumerate() {
+            let link = meta.link();
+            if linked[link] {
+                return Err(InvariantViolation::DuplicateLink { index, link });
+            }
+            linked.set(link, true);
+        }
+
+        // Every element has a single predecessor so the links form disjoint cycles.
+        let mut done = bit_vec![false; len];
+        let mut root_done = bit_vec![false; len];
+        for index in 0..len {
+            if done[index] {
+                continue;
+            }
+
+            let root = roots[index];
+            if root_done[root] {
+                return Err(InvariantViolation::SplitSet { index, root });
+            }
+            root_done.set(root, true);
+
+            let mut current = index;
+            loop {
+                if roots[current] != root {
+                    return Err(InvariantViolation::RootMismatch {
+                        index: current,
+                        root: roots[current],
+                        expected: root,
+                    });
+                }
+                done.set(current, true);
+
+                current = self.meta[current].link();
+                if current == index {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Panics if the internal structure of the `PartitionVec<T>` is not consistent.
+    ///
+    /// See [`validate`] for the invariants that are checked.
+    ///
+    /// This method will be executed in `O(n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If one of the invariants does not hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![(); 5];
+    /// partition_vec.union(0, 3);
+    /// partition_vec.truncate(2);
+    ///
+    /// partition_vec.assert_invariants();
+    /// # }
+    /// ```
+    ///
+    /// [`validate`]: struct.PartitionVec.html#method.validate
+    pub fn assert_invariants(&self) {
+        if let Err(violation) = self.validate() {
+            panic!("Invalid PartitionVec: {}", violation);
+        }
+    }
+
+    /// Panics if the values and the metadata of the `PartitionVec<T>` have different lengths.
+    ///
+    /// Every value has exactly one metadata entry so every method has to change both lengths
+    /// together.
+    /// This is the cheapest of the invariants checked by [`assert_invariants`] and can be used
+    /// in tests after every operation.
+    ///
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If the lengths of the values and the metadata differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::with_capacity(4);
+    /// partition_vec.push('a');
+    /// partition_vec.insert(0, 'b');
+    /// partition_vec.union(0, 1);
+    ///
+    /// partition_vec.assert_meta_data_len_match();
+    /// ```
+    ///
+    /// [`assert_invariants`]: struct.PartitionVec.html#method.assert_invariants
+    pub fn assert_meta_data_len_match(&self) {
+        assert!(
+            self.data.len() == self.meta.len(),
+            "The PartitionVec has {} values but {} metadata entries.",
+            self.data.len(),
+            self.meta.len(),
+        );
+    }
+
+    /// Checks that the internal structure of the `PartitionVec<T>` is consistent
+    /// and describes the first problem that is found.
+    ///
+    /// This is the same as [`validate`] but with an error message instead of
+    /// an [`InvariantViolation`].
+    /// Because the rank is stored in a few bits of the other values when the `compact` feature
+    /// is enabled this also checks the bit packing of the metadata.
+    ///
+    /// This method will be executed in `O(n)` time.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the violated invariant and the index where it was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![(); 100];
+    /// for i in 0 .. 99 {
+    ///     partition_vec.union(i, (i * 37) % 100);
+    /// }
+    /// partition_vec.remove(50);
+    ///
+    /// assert!(partition_vec.check_invariants() == Ok(()));
+    /// # }
+    /// ```
+    ///
+    /// [`validate`]: struct.PartitionVec.html#method.validate
+    /// [`InvariantViolation`]: enum.InvariantViolation.html
+    pub fn check_invariants(&self) -> Result<(), String> {
+        self.validate().map_err(|violation| violation.to_string())
+    }
+
+    /// This method is used by the `partition_vec!` macro.
+    #[doc(hidden)]
+    #[inline]
+    pub fn from_elem(elem: T, len: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            data: vec![elem; len],
+            meta: (0..len).map(Metadata::new).collect(),
+        }
+    }
+
+    pub(crate) unsafe fn set_len(&mut self, len: usize) {
+        self.data.set_len(len);
+        self.meta.set_len(len);
+    }
+
+    pub(crate) unsafe fn insert_over_lazy_removed(&mut self, index: usize, value: T) -> usize {
+        let marked_value = self.meta[index].marked_value();
+
+        std::ptr::write(&raw mut self.data[index], value);
+        self.meta[index] = Metadata::new(index);
+
+        marked_value
+    }
+
+    pub(crate) unsafe fn lazy_remove(&mut self, index: usize, marked_value: usize) -> T {
+        self.make_singleton(index);
+
+        let value = std::ptr::read(&raw const self.data[index]);
+        self.meta[index].set_marked_value(marked_value);
+
+        value
+    }
+
+    pub(crate) fn clear_lazy_removed(&mut self) {
+        for i in 0..self.len() {
+            if !self.meta[i].is_marked() {
+                unsafe {
+                    drop(std::ptr::read(&raw const self.data[i]));
+                }
+            }
+        }
+
+        unsafe {
+            self.set_len(0);
+        }
+    }
+
+    /// Clones `source` into `self` while keeping lazily removed values removed.
+    ///
+    /// The values of `self` are dropped as with `clear_lazy_removed` and the allocations are reused.
+    pub(crate) fn clone_lazy_removed_from(&mut self, source: &Self)
+    where
+        T: Clone,
+    {
+        self.clear_lazy_removed();
+        self.reserve(source.len());
+
+        for i in 0..source.len() {
+            if source.meta[i].is_marked() {
+                unsafe {
+                    self.push_lazy_removed();
+                    self.meta[i].set_marked_value(source.meta[i].marked_value());
+                }
+            } else {
+                self.data.push(source.data[i].clone());
+                self.meta.push(source.meta[i].clone());
+            }
+        }
+    }
+
+    pub(crate) fn is_lazy_removed(&self, index: usize) -> bool {
+        self.meta[index].is_marked()
+    }
+
+    pub(crate) unsafe fn push_lazy_removed(&mut self) {
+        let index = self.len();
+
+        self.reserve(1);
+        self.set_len(index + 1);
+
+        self.meta[index] = Metadata::new(0);
+        self.meta[index].set_marked_value(!0);
+    }
+}
+
+/// The magic bytes and the version byte at the start of the bytes of [`to_bytes`].
+///
+/// [`to_bytes`]: struct.PartitionVec.html#method.to_bytes
+const BYTES_HEADER: [u8; 5] = *b"PVEC\x01";
+
+/// Removes the first `len` bytes of `bytes` and returns them.
+fn take_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if bytes.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+
+    Ok(taken)
+}
+
+/// Removes a little endian `u64` from the start of `bytes` and returns it as a `usize`.
+fn take_len(bytes: &mut &[u8]) -> Result<usize, DecodeError> {
+    let mut buffer = [0; 8];
+    buffer.copy_from_slice(take_bytes(bytes, 8)?);
+    let value = u64::from_le_bytes(buffer);
+
+    std::convert::TryFrom::try_from(value).map_err(|_| DecodeError::TooLong(usize::MAX))
+}
+
+/// Returns the length after adding `additional` values to a `PartitionVec<T>` of length `len`.
+///
+/// # Panics
+///
+/// Panics if the new length overflows or if some of the new indices could not be stored.
+// Without the `compact` feature `MAX_LEN` is `usize::MAX` and the comparison is always true.
+#[allow(clippy::absurd_extreme_comparisons)]
+fn checked_len(len: usize, additional: usize) -> usize {
+    match len.checked_add(additional) {
+        Some(new_len) if new_len <= MAX_LEN => new_len,
+        _ => panic!("A PartitionVec can only hold {} values.", MAX_LEN),
+    }
+}
+
+impl PartitionVec<()> {
+    /// Creates a `PartitionVec<()>` with an element for every cell of a `width` by `height`
+    /// grid where horizontally or vertically adjacent cells are joined if `connected` is true.
+    ///
+    /// The cells are stored row by row so the cell at `(x, y)` has index `y * width + x`.
+    /// The function `connected` is called once with the indices of every pair of adjacent
+    /// cells, the first index is always the smaller one.
+    /// Use a [`PartitionGrid`] to store values in the cells or to also join diagonal cells.
+    ///
+    /// This will take `O(n α(n))` time, not counting the time spend in `connected`.
+    ///
+    /// # Panics
+    ///
+    /// If the amount of cells overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::PartitionVec;
+    ///
+    /// let image = [
+    ///     1, 1, 0, 0,
+    ///     0, 1, 0, 1,
+    ///     1, 0, 0, 1,
+    /// ];
+    ///
+    /// let blobs = PartitionVec::from_grid(4, 3, |first, second| image[first] == image[second]);
+    ///
+    /// assert!(blobs.len() == 12);
+    /// assert!(blobs.amount_of_sets() == 5);
+    /// assert!(blobs.same_set(0, 5));
+    /// assert!(blobs.same_set(2, 10));
+    /// assert!(blobs.other_sets(5, 8));
+    /// ```
+    ///
+    /// [`PartitionGrid`]: ../partition_grid/struct.PartitionGrid.html
+    #[must_use]
+    pub fn from_grid<F>(width: usize, height: usize, connected: F) -> Self
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let len = width
+            .checked_mul(height)
+            .expect("The amount of cells overflows a usize.");
+        let mut partition_vec = Self::from_elem((), len);
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+
+                if x + 1 < width && connected(index, index + 1) {
+                    partition_vec.union(index, index + 1);
+                }
+                if y + 1 < height && connected(index, index + width) {
+                    partition_vec.union(index, index + width);
+                }
+            }
+        }
+
+        partition_vec
+    }
+
+    /// Constructs a new `PartitionVec<()>` of length `len` that joins the indices of
+    /// `all_unions` except for the unions in `removed`.
+    ///
+    /// This can be used to retract earlier unions because a single union can not be undone.
+    /// The unions are not ordered so `(a, b)` in `removed` also removes `(b, a)` and every
+    /// pair in `removed` removes at most one union from `all_unions`.
+    /// To answer queries on a timeline where unions are added and removed use an
+    /// [`OfflineConnectivity`] instead.
+    ///
+    /// This function will be executed in `O(n + k α(n))` time where `k` is the amount of unions.
+    ///
+    /// # Panics
+    ///
+    /// If an index in `all_unions` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let unions = [(0, 1), (1, 2), (2, 3)];
+    ///
+    /// let rebuilt = partitions::PartitionVec::rebuild_without(5, &[(2, 1)], &unions);
+    /// assert!(rebuilt.same_set(0, 1));
+    /// assert!(!rebuilt.same_set(1, 2));
+    /// assert!(rebuilt.same_set(2, 3));
+    /// assert!(rebuilt.is_singleton(4));
+    /// ```
+    ///
+    /// [`OfflineConnectivity`]: ../offline_connectivity/struct.OfflineConnectivity.html
+    #[must_use]
+    pub fn rebuild_without(
+        len: usize,
+        removed: &[(usize, usize)],
+        all_unions: &[(usize, usize)],
+    ) -> Self {
+        let key = |(first, second): (usize, usize)| (first.min(second), first.max(second));
+
+        let mut remaining = std::collections::HashMap::new();
+        for &union in removed {
+            *remaining.entry(key(union)).or_insert(0) += 1;
+        }
+
+        let mut partition_vec = PartitionVec::from(vec![(); len]);
+        for &union in all_unions {
+            if let Some(count) = remaining.get_mut(&key(union)) {
+                if *count > 0 {
+                    *count -= 1;
+                    continue;
+                }
+            }
+
+            partition_vec.union(union.0, union.1);
+        }
+
+        partition_vec
+    }
+}
+
+impl<T> UnionFind for PartitionVec<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    fn union(&mut self, first_index: usize, second_index: usize) -> bool {
+        self.union_roots(first_index, second_index).is_some()
+    }
+
+    #[inline]
+    fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        PartitionVec::same_set(self, first_index, second_index)
+    }
+
+    #[inline]
+    fn make_singleton(&mut self, index: usize) {
+        PartitionVec::make_singleton(self, index);
+    }
+
+    #[inline]
+    fn amount_of_sets(&self) -> usize {
+        PartitionVec::amount_of_sets(self)
+    }
+}
+
+impl<T> Default for PartitionVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for PartitionVec<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            meta: self.meta.clone(),
+        }
+    }
+
+    /// Overwrites `self` with a copy of `source` while reusing the allocations of `self`.
+    ///
+    /// Existing values are overwritten using `T::clone_from`,
+    /// so no reallocation happens when `self` already has enough capacity.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let template = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    /// ];
+    /// let mut scratch = partitions::PartitionVec::with_capacity(5);
+    /// let capacity = scratch.capacity();
+    ///
+    /// for _ in 0 .. 3 {
+    ///     scratch.clone_from(&template);
+    ///     scratch.union(1, 2);
+    ///
+    ///     assert!(scratch.capacity() == capacity);
+    ///     assert!(scratch.amount_of_sets() == 1);
+    /// }
+    /// assert!(template.amount_of_sets() == 2);
+    /// # }
+    /// ```
+    fn clone_from(&mut self, source: &Self) {
+        self.data.clone_from(&source.data);
+        self.meta.clone_from(&source.meta);
+    }
+}
+
+impl<T> std::fmt::Debug for PartitionVec<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut builder = formatter.debug_list();
+
+        for (_, label, value) in self.iter_with_sets() {
+            builder.entry(&format_args!("{value:?} => {label}"));
+        }
+
+        builder.finish()
+    }
+}
+
+impl<T> PartialEq for PartitionVec<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        // The same parents always give the same sets so only the values have to be compared.
+        let same_parents = std::ptr::eq(self, other)
+            || self
+                .meta
+                .iter()
+                .zip(&other.meta)
+                .all(|(first, second)| first.parent() == second.parent());
+        if same_parents {
+            return self.data == other.data;
+        }
+
+        // We map the roots of self to the roots of other and the other way around.
+        // Both maps are needed because the mapping has to be a bijection,
+        // otherwise a partition would be equal to any of its refinements.
+        let mut map = std::collections::HashMap::with_capacity(self.len());
+        let mut inverse = std::collections::HashMap::with_capacity(self.len());
+
+        for i in 0..self.len() {
+            if self.data[i] != other.data[i] {
+                return false;
+            }
+
+            let self_root = self.find(i);
+            let other_root = other.find(i);
+
+            // If we have seen either root we check if we have the same relation.
+            if *map.entry(self_root).or_insert(other_root) != other_root {
+                return false;
+            }
+            if *inverse.entry(other_root).or_insert(self_root) != self_root {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl<T> Eq for PartitionVec<T> where T: Eq {}
+
+impl<T> ops::BitOr for &PartitionVec<T>
+where
+    T: Clone,
+{
+    type Output = PartitionVec<T>;
+
+    /// Returns the join of two partitions.
+    ///
+    /// This is the finest partition that is coarser than both `self` and `rhs`.
+    /// Two indices are in the same set of the result if they can be connected
+    /// by a chain of indices where each step shares a set in either `self` or `rhs`.
+    /// The values are cloned from `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `self` and `rhs` do not have the same length.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let first = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    ///     'd' => 2,
+    /// ];
+    /// let second = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 1,
+    ///     'd' => 2,
+    /// ];
+    ///
+    /// let join = &first | &second;
+    /// assert!(join.same_set(0, 2));
+    /// assert!(join.is_singleton(3));
+    /// assert!(join.amount_of_sets() == 2);
+    ///
+    /// assert!(&first | &first == first);
+    /// # }
+    /// ```
+    fn bitor(self, rhs: Self) -> PartitionVec<T> {
+        assert!(
+            self.len() == rhs.len(),
+            "The partitions have different lengths {} and {}.",
+            self.len(),
+            rhs.len(),
+        );
+
+        let mut join = self.clone();
+        for i in 0..rhs.len() {
+            join.union(i, rhs.find(i));
+        }
+
+        join
+    }
+}
+
+impl<T> ops::BitAnd for &PartitionVec<T>
+where
+    T: Clone,
+{
+    type Output = PartitionVec<T>;
+
+    /// Returns the meet of two partitions.
+    ///
+    /// This is the coarsest partition that is finer than both `self` and `rhs`.
+    /// Two indices are in the same set of the result if they share a set in both `self` and `rhs`.
+    /// The values are cloned from `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `self` and `rhs` do not have the same length.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let first = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 0,
+    ///     'd' => 1,
+    /// ];
+    /// let second = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 0,
+    ///     'c' => 1,
+    ///     'd' => 1,
+    /// ];
+    ///
+    /// let meet = &first & &second;
+    /// assert!(meet.same_set(0, 1));
+    /// assert!(meet.is_singleton(2));
+    /// assert!(meet.is_singleton(3));
+    ///
+    /// assert!(&first & &first == first);
+    /// # }
+    /// ```
+    fn bitand(self, rhs: Self) -> PartitionVec<T> {
+        assert!(
+            self.len() == rhs.len(),
+            "The partitions have different lengths {} and {}.",
+            self.len(),
+            rhs.len(),
+        );
+
+        PartitionVec::from_iter_with_sets(
+            self.data
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (value.clone(), (self.find(i), rhs.find(i)))),
+        )
+    }
+}
+
+impl<T, I> ops::Index<I> for PartitionVec<T>
+where
+    I: std::slice::SliceIndex<[T]>,
+{
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &I::Output {
+        (**self).index(index)
+    }
+}
+
+impl<T, I> ops::IndexMut<I> for PartitionVec<T>
+where
+    I: std::slice::SliceIndex<[T]>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        (**self).index_mut(index)
+    }
+}
+
+impl<T> ops::Index<Root> for PartitionVec<T> {
+    type Output = T;
+
+    /// Returns the value of the representative of the set containing the given index.
+    #[inline]
+    fn index(&self, Root(index): Root) -> &T {
+        &self.data[self.find(index)]
+    }
+}
+
+impl<T> ops::IndexMut<Root> for PartitionVec<T> {
+    /// Returns the value of the representative of the set containing the given index.
+    #[inline]
+    fn index_mut(&mut self, Root(index): Root) -> &mut T {
+        let root = self.find(index);
+        &mut self.data[root]
+    }
+}
+
+impl<T> ops::Deref for PartitionVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> ops::DerefMut for PartitionVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+/// Gives the values as a slice, the sets are ignored.
+impl<T> AsRef<[T]> for PartitionVec<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+/// Gives the values as a mutable slice, the sets are ignored and will not change.
+impl<T> AsMut<[T]> for PartitionVec<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+/// Borrows the values as a slice, the sets are ignored.
+///
+/// The `Eq`, `Ord` and `Hash` implementations of `[T]` only look at the values while
+/// two `PartitionVec<T>` values are only equal if they also have the same sets.
+/// This is why `PartitionVec<T>` does not implement `Hash`.
+impl<T> Borrow<[T]> for PartitionVec<T> {
+    fn borrow(&self) -> &[T] {
+        &self.data
+    }
+}
+
+/// Borrows the values as a mutable slice, the sets are ignored and will not change.
+impl<T> BorrowMut<[T]> for PartitionVec<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+/// Moves the values out of the `PartitionVec<T>` and discards the sets.
+///
+/// This is the same as calling [`into_vec`].
+///
+/// [`into_vec`]: struct.PartitionVec.html#method.into_vec
+impl<T> From<PartitionVec<T>> for Vec<T> {
+    fn from(partition_vec: PartitionVec<T>) -> Self {
+        partition_vec.data
+    }
+}
+
+impl<T> From<Vec<T>> for PartitionVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+
+        Self {
+            data: vec,
+            meta: (0..len).map(Metadata::new).collect(),
+        }
+    }
+}
+
+impl<T> FromIterator<T> for PartitionVec<T> {
+    fn from_iter<I>(iter: I) -> Self
     where
-        T: Clone,
+        I: IntoIterator<Item = T>,
+    {
+        let data = Vec::from_iter(iter);
+        let len = data.len();
+
+        Self {
+            data,
+            meta: (0..len).map(Metadata::new).collect(),
+        }
+    }
+}
+
+impl<'a, T> FromIterator<&'a T> for PartitionVec<T>
+where
+    T: Copy + 'a,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        iter.into_iter().copied().collect()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> PartitionVec<T> {
+    /// Constructs a new `PartitionVec<T>` from a parallel iterator of pairs `(value, key)`.
+    ///
+    /// This is the parallel version of [`from_iter_with_sets`].
+    /// The values are stored in the order of the iterator and values with the same key
+    /// will be in the same set.
+    ///
+    /// Only the values and keys are collected in parallel.
+    /// The sets are joined afterwards on the current thread in the order of the iterator
+    /// because the unions all modify the same metadata, this also makes the resulting
+    /// representatives independent of how the work was split.
+    ///
+    /// This will take `O(n α(n))` time where `α` is the inverse Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate partitions;
+    /// extern crate rayon;
+    ///
+    /// use partitions::PartitionVec;
+    /// use rayon::prelude::*;
+    ///
+    /// # fn main() {
+    /// let partition_vec = PartitionVec::from_par_iter_labeled(
+    ///     (0..1000).into_par_iter().map(|value| (value, value % 3)),
+    /// );
+    ///
+    /// assert!(partition_vec.len() == 1000);
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// assert!(partition_vec.same_set(0, 999));
+    /// assert!(!partition_vec.same_set(0, 1));
+    /// assert!(partition_vec[500] == 500);
+    ///
+    /// let sequential = PartitionVec::from_iter_with_sets((0..1000).map(|value| (value, value % 3)));
+    /// assert!(partition_vec == sequential);
+    /// # }
+    /// ```
+    ///
+    /// [`from_iter_with_sets`]: struct.PartitionVec.html#method.from_iter_with_sets
+    pub fn from_par_iter_labeled<K, I>(par_iter: I) -> Self
+    where
+        T: Send,
+        K: Eq + std::hash::Hash + Send,
+        I: IntoParallelIterator<Item = (T, K)>,
+    {
+        use std::collections::hash_map;
+
+        let (data, keys): (Vec<T>, Vec<K>) = par_iter.into_par_iter().unzip();
+        let mut partition_vec = Self::from(data);
+
+        // We map a `key` to an `index` of that set.
+        let mut map = hash_map::HashMap::new();
+
+        for (index, key) in keys.into_iter().enumerate() {
+            match map.entry(key) {
+                hash_map::Entry::Occupied(occupied) => {
+                    partition_vec.union(index, *occupied.get());
+                }
+                hash_map::Entry::Vacant(vacant) => {
+                    vacant.insert(index);
+                }
+            }
+        }
+
+        partition_vec
+    }
+
+    /// Returns the indices of every set, the sets are collected in parallel.
+    ///
+    /// Each set starts with its representative and is followed by the other indices in the
+    /// same order as they are returned by the `set` method.
+    /// The sets are ordered by the index of their representative.
+    ///
+    /// The roots and links are gathered in `O(n)` time after which each set is collected
+    /// in a separate task.
+    /// No paths are compressed because the sets are only read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let partition_vec = partition_vec![
+    ///     'a' => 0,
+    ///     'b' => 1,
+    ///     'c' => 0,
+    ///     'd' => 2,
+    ///     'e' => 1,
+    /// ];
+    ///
+    /// let mut components = partition_vec.par_components();
+    /// for component in &mut components {
+    ///     component.sort();
+    /// }
+    /// components.sort();
+    ///
+    /// assert!(components == [vec![0, 2], vec![1, 4], vec![3]]);
+    ///
+    /// let mut sequential: Vec<Vec<usize>> = partition_vec
+    ///     .all_sets()
+    ///     .map(|set| {
+    ///         let mut indices: Vec<usize> = set.map(|(index, _)| index).collect();
+    ///         indices.sort();
+    ///         indices
+    ///     })
+    ///     .collect();
+    /// sequential.sort();
+    /// assert!(components == sequential);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn par_components(&self) -> Vec<Vec<usize>> {
+        // The metadata can not be shared between threads so we copy what we need.
+        let links: Vec<usize> = self.meta.iter().map(Metadata::link).collect();
+        let roots: Vec<usize> = (0..self.len())
+            .filter(|&index| self.meta[index].parent() == index)
+            .collect();
+
+        roots
+            .into_par_iter()
+            .map(|root| {
+                let mut component = vec![root];
+                let mut current = links[root];
+                while current != root {
+                    component.push(current);
+                    current = links[current];
+                }
+
+                component
+            })
+            .collect()
+    }
+
+    /// Returns a parallel iterator over all sets of the `PartitionVec<T>`.
+    ///
+    /// The iterator returned yields `ParSetMut` iterators.
+    /// These `ParSetMut` iterators yield pairs `(i, &mut value)` where `i` is the index of
+    /// the value and `value` is the value itself.
+    ///
+    /// The indices of every set are gathered before the parallel iterator is returned,
+    /// so the metadata which can not be shared between threads is not used while iterating.
+    /// Because the sets are disjoint every value is only given to one of the tasks.
+    /// The sets are returned in order by their first member.
+    /// The order the elements of a `ParSetMut` are returned in is not specified.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// extern crate rayon;
+    ///
+    /// use rayon::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![
+    ///     0 => 'a',
+    ///     0 => 'b',
+    ///     0 => 'a',
+    ///     0 => 'c',
+    /// ];
+    ///
+    /// partition_vec
+    ///     .par_all_sets_mut()
+    ///     .enumerate()
+    ///     .for_each(|(set_number, set)| {
+    ///         for (_, value) in set {
+    ///             *value = set_number;
+    ///         }
+    ///     });
+    ///
+    /// assert!(partition_vec.as_slice() == [0, 1, 0, 2]);
+    /// # }
+    /// ```
+    pub fn par_all_sets_mut(&mut self) -> rayon::vec::IntoIter<ParSetMut<'_, T>>
+    where
+        T: Send,
+    {
+        let len = self.len();
+        let mut done = bit_vec![false; len];
+        let mut sets = Vec::new();
+        for first in 0..len {
+            if done[first] {
+                continue;
+            }
+
+            let mut indices = Vec::new();
+            let mut current = first;
+            loop {
+                done.set(current, true);
+                indices.push(current);
+
+                current = self.meta[current].link();
+                if current == first {
+                    break;
+                }
+            }
+            sets.push(indices);
+        }
+
+        let data = self.data.as_mut_ptr();
+        sets.into_iter()
+            .map(|indices| ParSetMut {
+                data,
+                indices: indices.into_iter(),
+                marker: PhantomData,
+            })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+    }
+
+    /// Joins the sets of the two indices of every edge, partially in parallel.
+    ///
+    /// The edges are split in chunks and for every chunk a spanning forest is computed
+    /// in parallel, these are the edges of the chunk that are not redundant within the chunk.
+    /// Afterwards only the edges of these forests are joined into `self` one by one.
+    /// The resulting sets are the same as calling `union` for every edge,
+    /// no matter how the edges are split in chunks.
+    /// Which elements become the representatives of the sets can depend on the chunks.
+    ///
+    /// This gives a speedup when there are many redundant edges,
+    /// the last step still takes `O(m α(n))` time where `m` is the amount of edges in the forests.
+    ///
+    /// # Panics
+    ///
+    /// If any of the indices is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate partitions;
+    /// #
+    /// # fn main() {
+    /// let mut partition_vec = partition_vec![(); 1000];
+    /// let edges: Vec<(usize, usize)> = (0 .. 10_000)
+    ///     .map(|i| ((i * 7) % 500, (i * 13) % 500))
+    ///     .collect();
+    ///
+    /// let mut sequential = partition_vec.clone();
+    /// for &(first, second) in &edges {
+    ///     sequential.union(first, second);
+    /// }
+    ///
+    /// partition_vec.par_union_all(&edges);
+    ///
+    /// // Equal `PartitionVec`s have the same values and the same sets.
+    /// assert!(partition_vec == sequential);
+    /// assert!(partition_vec.is_singleton(999));
+    /// # }
+    /// ```
+    pub fn par_union_all(&mut self, edges: &[(usize, usize)]) {
+        use std::collections::hash_map;
+
+        let chunk_len = std::cmp::max(1, edges.len().div_ceil(rayon::current_num_threads()));
+
+        let forests: Vec<Vec<(usize, usize)>> = edges
+            .par_chunks(chunk_len)
+            .map(|chunk| {
+                // We give every index in the chunk a local index in a small `PartitionVec`.
+                let mut local_indices = std::collections::HashMap::new();
+                let mut local = PartitionVec::<()>::new();
+                let mut local_index =
+                    |index: usize, local: &mut PartitionVec<()>| match local_indices.entry(index) {
+                        hash_map::Entry::Occupied(occupied) => *occupied.get(),
+                        hash_map::Entry::Vacant(vacant) => {
+                            local.push(());
+                            *vacant.insert(local.len() - 1)
+                        }
+                    };
+
+                // The first edge of an index always joins two sets,
+                // so every index is part of the forest and gets checked later.
+                chunk
+                    .iter()
+                    .filter(|&&(first, second)| {
+                        let first = local_index(first, &mut local);
+                        let second = local_index(second, &mut local);
+
+                        local.union_roots(first, second).is_some()
+                    })
+                    .copied()
+                    .collect()
+            })
+            .collect();
+
+        for (first, second) in forests.into_iter().flatten() {
+            self.union(first, second);
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> FromParallelIterator<T> for PartitionVec<T>
+where
+    T: Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let par_iter = par_iter.into_par_iter();
+
+        let mut partition = if let Some(len) = par_iter.opt_len() {
+            Self::with_capacity(len)
+        } else {
+            Self::new()
+        };
+
+        partition.par_extend(par_iter);
+
+        partition
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T> FromParallelIterator<&'a T> for PartitionVec<T>
+where
+    T: Copy + Send + Sync + 'a,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = &'a T>,
     {
-        Self {
-            data: vec![elem; len],
-            meta: (0..len).map(Metadata::new).collect(),
-        }
+        Self::from_par_iter(par_iter.into_par_iter().cloned())
     }
+}
 
-    pub(crate) unsafe fn set_len(&mut self, len: usize) {
-        self.data.set_len(len);
-        self.meta.set_len(len);
+impl<T> IntoIterator for PartitionVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> std::vec::IntoIter<T> {
+        self.data.into_iter()
     }
+}
 
-    pub(crate) unsafe fn insert_over_lazy_removed(&mut self, index: usize, value: T) -> usize {
-        let marked_value = self.meta[index].marked_value();
+impl<'a, T> IntoIterator for &'a PartitionVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
 
-        std::ptr::write(&mut self.data[index], value);
-        self.meta[index] = Metadata::new(index);
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.data.iter()
+    }
+}
 
-        marked_value
+impl<'a, T> IntoIterator for &'a mut PartitionVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> std::slice::IterMut<'a, T> {
+        self.data.iter_mut()
     }
+}
 
-    pub(crate) unsafe fn lazy_remove(&mut self, index: usize, marked_value: usize) -> T {
-        self.make_singleton(index);
+#[cfg(feature = "rayon")]
+impl<T> IntoParallelIterator for PartitionVec<T>
+where
+    T: Send,
+{
+    type Item = T;
+    type Iter = rayon::vec::IntoIter<T>;
 
-        let value = std::ptr::read(&self.data[index]);
-        self.meta[index].set_marked_value(marked_value);
+    fn into_par_iter(self) -> Self::Iter {
+        self.data.into_par_iter()
+    }
+}
 
-        value
+#[cfg(feature = "rayon")]
+impl<'a, T> IntoParallelIterator for &'a PartitionVec<T>
+where
+    T: Send + Sync,
+{
+    type Item = &'a T;
+    type Iter = rayon::slice::Iter<'a, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.data.par_iter()
     }
+}
 
-    pub(crate) fn clear_lazy_removed(&mut self) {
-        for i in 0..self.len() {
-            if !self.meta[i].is_marked() {
-                unsafe {
-                    drop(std::ptr::read(&self.data[i]));
-                }
-            }
-        }
+#[cfg(feature = "rayon")]
+impl<'a, T> IntoParallelIterator for &'a mut PartitionVec<T>
+where
+    T: Send + Sync,
+{
+    type Item = &'a mut T;
+    type Iter = rayon::slice::IterMut<'a, T>;
 
-        unsafe {
-            self.set_len(0);
-        }
+    fn into_par_iter(self) -> Self::Iter {
+        self.data.par_iter_mut()
     }
+}
 
-    pub(crate) unsafe fn push_lazy_removed(&mut self) {
-        let index = self.len();
+impl<T> Extend<T> for PartitionVec<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let len = self.len();
+        self.data.extend(iter);
+        let new_len = self.data.len();
 
-        self.reserve(1);
-        self.set_len(index + 1);
+        self.meta.extend((len..new_len).map(Metadata::new));
+    }
+}
 
-        self.meta[index] = Metadata::new(0);
-        self.meta[index].set_marked_value(!0);
+impl<'a, T> Extend<&'a T> for PartitionVec<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        let len = self.len();
+        self.data.extend(iter);
+        let new_len = self.data.len();
+
+        self.meta.extend((len..new_len).map(Metadata::new));
     }
 }
 
-impl<T> Default for PartitionVec<T> {
-    fn default() -> Self {
-        Self::new()
+#[cfg(feature = "rayon")]
+impl<T> ParallelExtend<T> for PartitionVec<T>
+where
+    T: Send,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let par_iter = par_iter.into_par_iter();
+
+        self.data.par_extend(par_iter);
+        self.meta
+            .par_extend((0..self.data.len()).into_par_iter().map(Metadata::new));
     }
 }
 
-impl<T> std::fmt::Debug for PartitionVec<T>
+#[cfg(feature = "rayon")]
+impl<'a, T> ParallelExtend<&'a T> for PartitionVec<T>
 where
-    T: std::fmt::Debug,
+    T: Copy + Send + Sync + 'a,
 {
-    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        // We map the roots to `usize` names.
-        let mut map = std::collections::HashMap::with_capacity(self.len());
-        let mut builder = formatter.debug_list();
-        let mut names = 0;
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = &'a T>,
+    {
+        self.par_extend(par_iter.into_par_iter().cloned());
+    }
+}
 
-        for i in 0..self.len() {
-            let root = self.find(i);
+#[cfg(feature = "rand")]
+impl<T> PartitionVec<T> {
+    /// Returns the index of a uniformly random member of the set that `index` belongs to.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate partitions;
+    /// extern crate rand;
+    ///
+    /// # fn main() {
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 5]);
+    /// partition_vec.union(1, 3);
+    /// partition_vec.union(3, 4);
+    ///
+    /// let member = partition_vec.random_member(1, &mut rand::thread_rng());
+    /// assert!(member == 1 || member == 3 || member == 4);
+    /// assert!(partition_vec.random_member(2, &mut rand::thread_rng()) == 2);
+    /// # }
+    /// ```
+    pub fn random_member<R>(&self, index: usize, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+    {
+        let n = rng.gen_range(0, self.len_of_set(index));
 
-            let name = if let Some(&name) = map.get(&root) {
-                // If we already have a name we use it.
-                name
-            } else {
-                // If we don't we make a new name.
-                let new_name = names;
-                map.insert(root, new_name);
-                names += 1;
+        // The member exists because `n` is below the size of the set.
+        self.nth_in_set(index, n).unwrap()
+    }
+}
 
-                new_name
-            };
+#[cfg(feature = "proptest")]
+impl<T> Arbitrary for PartitionVec<T>
+where
+    T: Arbitrary,
+    T::Strategy: 'static,
+{
+    type Parameters = (proptest::collection::SizeRange, T::Parameters);
+    type Strategy = BoxedStrategy<Self>;
 
-            builder.entry(&format_args!("{:?} => {}", self.data[i], name));
-        }
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        use std::collections::hash_map;
+
+        let (size_range, params) = params;
+        let params = (size_range, (params, ()));
+
+        (Vec::<(T, usize)>::arbitrary_with(params))
+            .prop_map(|vec| {
+                let mut partition_vec = Self::with_capacity(vec.len());
+
+                // We map a `set_number` to an `index` of that set.
+                let mut map = hash_map::HashMap::with_capacity(vec.len());
+
+                for (index, (value, set_number)) in vec.into_iter().enumerate() {
+                    partition_vec.push(value);
+
+                    let set_number = set_number.trailing_zeros();
+
+                    match map.entry(set_number) {
+                        hash_map::Entry::Occupied(occupied) => {
+                            partition_vec.union(index, *occupied.get());
+                        }
+                        hash_map::Entry::Vacant(vacant) => {
+                            vacant.insert(index);
+                        }
+                    }
+                }
+
+                partition_vec
+            })
+            .boxed()
+    }
+}
+
+/// An index that refers to the representative of the set containing it.
+///
+/// Indexing a [`PartitionVec<T>`] with `Root(index)` gives the value of the representative of
+/// the set that `index` belongs to, so every index of a set gives the same value.
+/// This can be used to store a value for every set, such as its total weight.
+/// Note that joining two sets makes one of the two representatives the representative of
+/// the joined set, so such values have to be combined before calling `union`.
+///
+/// This will be done in `O(α(n))` time where `α` is the inverse Ackermann function.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate partitions;
+/// #
+/// # fn main() {
+/// use partitions::partition_vec::Root;
+///
+/// // We store the total weight of every set at its representative.
+/// let mut weights = partition_vec![1, 2, 3, 4];
+///
+/// let total = weights[Root(0)] + weights[Root(2)];
+/// weights.union(0, 2);
+/// weights[Root(2)] = total;
+///
+/// assert!(weights[Root(0)] == 4);
+/// assert!(weights[Root(2)] == 4);
+/// assert!(weights[Root(1)] == 2);
+///
+/// // Normal indexing is not affected.
+/// assert!(weights[1] == 2);
+/// # }
+/// ```
+///
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Root(pub usize);
+
+/// The amount of heap memory used by a `PartitionVec<T>` or one of the partition maps.
+///
+/// This struct is created by the [`memory_usage`] method on [`PartitionVec<T>`] and the
+/// `memory_usage` methods of the partition maps.
+/// See its documentation for more.
+///
+/// [`memory_usage`]: struct.PartitionVec.html#method.memory_usage
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryUsage {
+    /// The amount of elements stored.
+    pub len: usize,
+    /// The amount of elements that can be stored without reallocating.
+    pub capacity: usize,
+    /// The amount of bytes allocated for the values.
+    pub data_bytes: usize,
+    /// The amount of bytes allocated for the metadata of the sets.
+    pub meta_bytes: usize,
+    /// The estimated amount of bytes allocated for the key lookup of a partition map.
+    /// This is always `0` for a `PartitionVec<T>`.
+    pub map_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the total amount of bytes.
+    #[inline]
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.data_bytes + self.meta_bytes + self.map_bytes
+    }
+}
+
+impl std::fmt::Display for MemoryUsage {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{} bytes for {} of {} elements ({} data, {} metadata, {} map)",
+            self.total_bytes(),
+            self.len,
+            self.capacity,
+            self.data_bytes,
+            self.meta_bytes,
+            self.map_bytes,
+        )
+    }
+}
+
+/// Labels for the sets of a `PartitionVec<T>`.
+///
+/// This struct is created by the [`relabel`] method on [`PartitionVec<T>`].
+/// See its documentation for more.
+///
+/// [`relabel`]: struct.PartitionVec.html#method.relabel
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Debug, Default)]
+pub struct SetLabels<L> {
+    /// The label for each labeled root.
+    labels: std::collections::HashMap<usize, L>,
+}
+
+impl<L> SetLabels<L> {
+    /// Returns the label of the set containing `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get<T>(&self, partition_vec: &PartitionVec<T>, index: usize) -> Option<&L> {
+        self.labels.get(&partition_vec.find(index))
+    }
 
-        builder.finish()
+    /// Gives `label` to the set containing `index` and returns the old label if there was one.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    pub fn insert<T>(
+        &mut self,
+        partition_vec: &PartitionVec<T>,
+        index: usize,
+        label: L,
+    ) -> Option<L> {
+        self.labels.insert(partition_vec.find(index), label)
     }
-}
 
-impl<T> PartialEq for PartitionVec<T>
-where
-    T: PartialEq,
-{
-    fn eq(&self, other: &Self) -> bool {
-        if self.len() != other.len() {
+    /// Joins the sets of the `first_index` and the `second_index` in `partition_vec` and
+    /// keeps the labels of both sets.
+    ///
+    /// If only one of the sets has a label the joined set gets that label.
+    /// If both sets have a label the joined set gets the label returned by `resolve`,
+    /// which is given the label of the set of `first_index` first.
+    /// Returns `true` if two different sets were joined, `resolve` is only called in that case.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition_vec = partitions::PartitionVec::from(vec![(); 4]);
+    /// let mut labels = partition_vec.relabel(vec![(0, 10_u64), (1, 20), (2, 30)]);
+    ///
+    /// assert!(labels.union(&mut partition_vec, 0, 3, |first, _| first));
+    /// assert!(labels.get(&partition_vec, 3) == Some(&10));
+    ///
+    /// assert!(labels.union(&mut partition_vec, 1, 3, u64::min));
+    /// assert!(labels.get(&partition_vec, 0) == Some(&10));
+    /// assert!(labels.get(&partition_vec, 2) == Some(&30));
+    /// assert!(labels.len() == 2);
+    /// ```
+    pub fn union<T, F>(
+        &mut self,
+        partition_vec: &mut PartitionVec<T>,
+        first_index: usize,
+        second_index: usize,
+        resolve: F,
+    ) -> bool
+    where
+        F: FnOnce(L, L) -> L,
+    {
+        let Some((first_root, second_root)) = partition_vec.union_roots(first_index, second_index)
+        else {
             return false;
+        };
+
+        let label = match (
+            self.labels.remove(&first_root),
+            self.labels.remove(&second_root),
+        ) {
+            (Some(first), Some(second)) => Some(resolve(first, second)),
+            (first, None) => first,
+            (None, second) => second,
+        };
+        if let Some(label) = label {
+            self.labels.insert(partition_vec.find(first_root), label);
         }
 
-        // We map the roots of self to the roots of other.
-        let mut map = std::collections::HashMap::with_capacity(self.len());
+        true
+    }
 
-        for i in 0..self.len() {
-            if self.data[i] != other.data[i] {
-                return false;
-            }
+    /// Returns the amount of labeled sets.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
 
-            let self_root = self.find(i);
-            let other_root = other.find(i);
+    /// Returns `true` if no set has a label.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
 
-            if let Some(&root) = map.get(&self_root) {
-                // If we have seen this root we check if we have the same map.
-                if root != other_root {
-                    return false;
-                }
-            } else {
-                // If we have not seen this root we add the relation to the map.
-                map.insert(self_root, other_root);
-            }
-        }
+/// The changes between the sets of two partitions.
+///
+/// This struct is created by the [`diff`] method on [`PartitionVec<T>`].
+/// See its documentation for more.
+///
+/// [`diff`]: struct.PartitionVec.html#method.diff
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PartitionDiff {
+    /// The sets of the second partition that contain indices of multiple sets of the first.
+    pub merged: Vec<MergeEvent>,
+    /// The sets of the first partition with indices in multiple sets of the second.
+    pub split: Vec<SplitEvent>,
+    /// The sets that are the same in both partitions.
+    pub unchanged: Vec<Vec<usize>>,
+}
 
-        true
-    }
+/// A set of the second partition of a [`PartitionDiff`] that was formed from multiple sets.
+///
+/// [`PartitionDiff`]: struct.PartitionDiff.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MergeEvent {
+    /// The indices of the set in the second partition.
+    pub set: Vec<usize>,
+    /// The indices of the set grouped by their set in the first partition.
+    pub parts: Vec<Vec<usize>>,
 }
 
-impl<T> Eq for PartitionVec<T> where T: Eq {}
+/// A set of the first partition of a [`PartitionDiff`] that was divided over multiple sets.
+///
+/// [`PartitionDiff`]: struct.PartitionDiff.html
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SplitEvent {
+    /// The indices of the set in the first partition.
+    pub set: Vec<usize>,
+    /// The indices of the set grouped by their set in the second partition.
+    pub parts: Vec<Vec<usize>>,
+}
 
-impl<T, I> ops::Index<I> for PartitionVec<T>
-where
-    I: std::slice::SliceIndex<[T]>,
-{
-    type Output = I::Output;
+/// A partition that can no longer change its sets.
+///
+/// This struct is created by the [`freeze`] method on [`PartitionVec<T>`].
+/// See its documentation for more.
+///
+/// The sets are stored as the representative of every index, so this has no
+/// interior mutability and is `Sync` if `T` is `Sync`.
+/// The values can still be accessed and changed because it dereferences to a slice.
+///
+/// [`freeze`]: struct.PartitionVec.html#method.freeze
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Debug)]
+pub struct FrozenPartition<T> {
+    /// Each index has a value.
+    data: Vec<T>,
+    /// The representative of the set of each index.
+    roots: Vec<usize>,
+}
 
+impl<T> FrozenPartition<T> {
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` are out of bounds.
     #[inline]
-    fn index(&self, index: I) -> &I::Output {
-        (**self).index(index)
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        self.roots[first_index] == self.roots[second_index]
     }
-}
 
-impl<T, I> ops::IndexMut<I> for PartitionVec<T>
-where
-    I: std::slice::SliceIndex<[T]>,
-{
+    /// Returns the index of the representative of the set that `index` belongs to.
+    ///
+    /// Two indices share a set exactly when they have the same representative.
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
     #[inline]
-    fn index_mut(&mut self, index: I) -> &mut I::Output {
-        (**self).index_mut(index)
+    #[must_use]
+    pub fn representative(&self, index: usize) -> usize {
+        self.roots[index]
+    }
+
+    /// Returns the values as a `Vec<T>`, dropping the sets.
+    #[inline]
+    #[must_use]
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
     }
 }
 
-impl<T> ops::Deref for PartitionVec<T> {
+impl<T> ops::Deref for FrozenPartition<T> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -1513,444 +6212,1386 @@ impl<T> ops::Deref for PartitionVec<T> {
     }
 }
 
-impl<T> ops::DerefMut for PartitionVec<T> {
+impl<T> ops::DerefMut for FrozenPartition<T> {
     fn deref_mut(&mut self) -> &mut [T] {
         &mut self.data
     }
 }
 
-impl<T> From<Vec<T>> for PartitionVec<T> {
-    fn from(vec: Vec<T>) -> Self {
-        let len = vec.len();
+/// A `PartitionVec<T>` that shares its values and metadata with its clones.
+///
+/// This struct is created by the [`share`] method on [`PartitionVec<T>`].
+///
+/// Cloning a `SharedPartitionVec<T>` is `O(1)` and gives a handle to the same buffers.
+/// The first call to [`make_mut`] on a handle that shares its buffers copies them,
+/// after which the handle can be changed without affecting the other handles.
+///
+/// The methods that only read never change the metadata, unlike the methods on
+/// `PartitionVec<T>` that compress paths while they look for the representative of a set.
+/// This means they can take longer on deep trees but also that the handles can be sent to
+/// and read from many threads at the same time.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate partitions;
+/// #
+/// # fn main() {
+/// let mut partition_vec = partition_vec![0; 1000];
+/// partition_vec.union_range(..500);
+/// let shared = partition_vec.share();
+///
+/// std::thread::scope(|scope| {
+///     for task in 0..4 {
+///         let mut local = shared.clone();
+///         scope.spawn(move || {
+///             assert!(local.same_set(0, 499));
+///
+///             // Only this task sees the change.
+///             local.make_mut().union(0, 500 + task);
+///             assert!(local.len_of_set(0) == 501);
+///         });
+///     }
+/// });
+///
+/// assert!(shared.len_of_set(0) == 500);
+/// # }
+/// ```
+///
+/// [`share`]: struct.PartitionVec.html#method.share
+/// [`make_mut`]: struct.SharedPartitionVec.html#method.make_mut
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+pub struct SharedPartitionVec<T> {
+    inner: std::sync::Arc<PartitionVec<T>>,
+}
 
-        Self {
-            data: vec,
-            meta: (0..len).map(Metadata::new).collect(),
-        }
+// The metadata is only changed through `make_mut` which gives a mutable reference that
+// can only exist while no other handle shares the `PartitionVec<T>`.
+// All other methods only read the metadata so the handles can be shared between threads.
+unsafe impl<T> Send for SharedPartitionVec<T> where T: Send + Sync {}
+unsafe impl<T> Sync for SharedPartitionVec<T> where T: Send + Sync {}
+
+impl<T> SharedPartitionVec<T> {
+    /// Returns a mutable reference to the `PartitionVec<T>`, copying the values and metadata
+    /// first if they are shared with another handle.
+    pub fn make_mut(&mut self) -> &mut PartitionVec<T>
+    where
+        T: Clone,
+    {
+        std::sync::Arc::make_mut(&mut self.inner)
     }
-}
 
-impl<T> FromIterator<T> for PartitionVec<T> {
-    fn from_iter<I>(iter: I) -> Self
+    /// Converts the handle into a `PartitionVec<T>`, copying the values and metadata
+    /// if they are shared with another handle.
+    #[must_use]
+    pub fn into_inner(self) -> PartitionVec<T>
     where
-        I: IntoIterator<Item = T>,
+        T: Clone,
     {
-        let data = Vec::from_iter(iter);
-        let len = data.len();
+        std::sync::Arc::try_unwrap(self.inner).unwrap_or_else(|inner| (*inner).clone())
+    }
+
+    /// Returns `true` if both handles share the same values and metadata.
+    #[inline]
+    #[must_use]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&this.inner, &other.inner)
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// No paths are compressed, so this takes time proportional to the height of the trees of
+    /// both indices.
+    /// That height is `O(log(n))` when the sets were joined by rank, like with `union`.
+    /// [`union_prefer`] and [`union_by_external_rank`] can build higher trees, but the ranks
+    /// increase along every path so no tree is more levels high than a `usize` has bits.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` are out of bounds.
+    ///
+    /// [`union_prefer`]: struct.PartitionVec.html#method.union_prefer
+    /// [`union_by_external_rank`]: struct.PartitionVec.html#method.union_by_external_rank
+    #[inline]
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        self.inner.find_final(first_index) == self.inner.find_final(second_index)
+    }
+
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: usize) -> bool {
+        self.inner.is_singleton(index)
+    }
+
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn len_of_set(&self, index: usize) -> usize {
+        self.inner.len_of_set(index)
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[must_use]
+    pub fn set(&self, index: usize) -> Set<'_, T> {
+        let root = self.inner.find_final(index);
+
+        Set::read_only(&self.inner.data, &self.inner.meta, root)
+    }
+}
 
+impl<T> Clone for SharedPartitionVec<T> {
+    fn clone(&self) -> Self {
         Self {
-            data,
-            meta: (0..len).map(Metadata::new).collect(),
+            inner: self.inner.clone(),
         }
     }
 }
 
-impl<'a, T> FromIterator<&'a T> for PartitionVec<T>
+impl<T> std::fmt::Debug for SharedPartitionVec<T>
 where
-    T: Copy + 'a,
+    T: std::fmt::Debug,
 {
-    fn from_iter<I>(iter: I) -> Self
-    where
-        I: IntoIterator<Item = &'a T>,
-    {
-        Self::from_iter(iter.into_iter().copied())
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter
+            .debug_list()
+            .entries(self.inner.data.iter())
+            .finish()
     }
 }
 
-#[cfg(feature = "rayon")]
-impl<T> FromParallelIterator<T> for PartitionVec<T>
-where
-    T: Send,
-{
-    fn from_par_iter<I>(par_iter: I) -> Self
-    where
-        I: IntoParallelIterator<Item = T>,
-    {
-        let par_iter = par_iter.into_par_iter();
+impl<T> ops::Deref for SharedPartitionVec<T> {
+    type Target = [T];
 
-        let mut partition = if let Some(len) = par_iter.opt_len() {
-            Self::with_capacity(len)
-        } else {
-            Self::new()
-        };
+    fn deref(&self) -> &[T] {
+        &self.inner.data
+    }
+}
 
-        partition.par_extend(par_iter);
+/// The error returned when an index is not below the length of a `PartitionVec<T>`.
+///
+/// This is returned by the [`checked_union`] method on [`PartitionVec<T>`].
+///
+/// [`checked_union`]: struct.PartitionVec.html#method.checked_union
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IndexOutOfBounds {
+    /// The index that is out of bounds.
+    pub index: usize,
+    /// The length of the `PartitionVec<T>`.
+    pub len: usize,
+}
 
-        partition
+impl std::fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "the index {} is out of bounds for a length of {}",
+            self.index, self.len,
+        )
     }
 }
 
-#[cfg(feature = "rayon")]
-impl<'a, T> FromParallelIterator<&'a T> for PartitionVec<T>
-where
-    T: Copy + Send + Sync + 'a,
-{
-    fn from_par_iter<I>(par_iter: I) -> Self
-    where
-        I: IntoParallelIterator<Item = &'a T>,
-    {
-        Self::from_par_iter(par_iter.into_par_iter().cloned())
+impl std::error::Error for IndexOutOfBounds {}
+
+/// An invariant of a `PartitionVec<T>` that does not hold.
+///
+/// This is returned by the [`validate`] method on [`PartitionVec<T>`].
+/// See its documentation for more.
+///
+/// [`validate`]: struct.PartitionVec.html#method.validate
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InvariantViolation {
+    /// The amount of values and the amount of metadata differ.
+    LengthMismatch { data_len: usize, meta_len: usize },
+    /// The parent of `index` is out of bounds.
+    ParentOutOfBounds { index: usize, parent: usize },
+    /// The link of `index` is out of bounds.
+    LinkOutOfBounds { index: usize, link: usize },
+    /// The rank of `index` is too large or not below the rank of its parent.
+    InvalidRank { index: usize, rank: usize },
+    /// Both `index` and another element link to `link`.
+    DuplicateLink { index: usize, link: usize },
+    /// The element `index` links to an element with another root.
+    RootMismatch {
+        index: usize,
+        root: usize,
+        expected: usize,
+    },
+    /// The elements with the root `root` form multiple cycles, the one containing `index` is not
+    /// the first.
+    SplitSet { index: usize, root: usize },
+}
+
+impl std::fmt::Display for InvariantViolation {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            InvariantViolation::LengthMismatch { data_len, meta_len } => write!(
+                formatter,
+                "there are {data_len} values but {meta_len} metadata entries"
+            ),
+            InvariantViolation::ParentOutOfBounds { index, parent } => {
+                write!(
+                    formatter,
+                    "the parent {parent} of index {index} is out of bounds"
+                )
+            }
+            InvariantViolation::LinkOutOfBounds { index, link } => {
+                write!(
+                    formatter,
+                    "the link {link} of index {index} is out of bounds"
+                )
+            }
+            InvariantViolation::InvalidRank { index, rank } => {
+                write!(formatter, "the rank {rank} of index {index} is invalid")
+            }
+            InvariantViolation::DuplicateLink { index, link } => {
+                write!(
+                    formatter,
+                    "index {index} links to {link} which already has a predecessor"
+                )
+            }
+            InvariantViolation::RootMismatch {
+                index,
+                root,
+                expected,
+            } => write!(
+                formatter,
+                "index {index} has the root {root} but is in the set with root {expected}"
+            ),
+            InvariantViolation::SplitSet { index, root } => write!(
+                formatter,
+                "index {index} has the root {root} which already has another cycle of links"
+            ),
+        }
     }
 }
 
-impl<T> IntoIterator for PartitionVec<T> {
-    type Item = T;
-    type IntoIter = std::vec::IntoIter<T>;
+impl std::error::Error for InvariantViolation {}
 
-    fn into_iter(self) -> std::vec::IntoIter<T> {
-        self.data.into_iter()
-    }
+/// An operation on the sets of a `PartitionVec<T>`.
+///
+/// A list of these is applied by the [`process_ops`] method on [`PartitionVec<T>`].
+///
+/// [`process_ops`]: struct.PartitionVec.html#method.process_ops
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Joins the sets of both indices.
+    Union(usize, usize),
+    /// Asks whether both indices are in the same set.
+    SameSet(usize, usize),
+    /// Gives the index its own set.
+    MakeSingleton(usize),
 }
 
-impl<'a, T> IntoIterator for &'a PartitionVec<T> {
-    type Item = &'a T;
-    type IntoIter = std::slice::Iter<'a, T>;
+/// The error returned when bytes can not be decoded as a `PartitionVec<T>`.
+///
+/// This is returned by the [`from_bytes`] method on [`PartitionVec<T>`].
+///
+/// [`from_bytes`]: struct.PartitionVec.html#method.from_bytes
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The bytes do not start with the magic bytes.
+    InvalidHeader,
+    /// The version byte is not supported by this version of the crate.
+    UnsupportedVersion(u8),
+    /// The bytes end before the last value.
+    UnexpectedEnd,
+    /// There are bytes left after the last value.
+    TrailingBytes(usize),
+    /// The length or a value length is too large for this target.
+    TooLong(usize),
+    /// The label of `index` is not at most one more than the labels before it.
+    InvalidLabel { index: usize, label: usize },
+    /// The value at `index` could not be decoded.
+    InvalidValue { index: usize },
+}
 
-    fn into_iter(self) -> std::slice::Iter<'a, T> {
-        self.data.iter()
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DecodeError::InvalidHeader => write!(formatter, "the bytes do not start with PVEC"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(formatter, "the version {version} is not supported")
+            }
+            DecodeError::UnexpectedEnd => write!(formatter, "the bytes end too early"),
+            DecodeError::TrailingBytes(len) => {
+                write!(formatter, "there are {len} bytes after the last value")
+            }
+            DecodeError::TooLong(len) => write!(formatter, "the length {len} is too large"),
+            DecodeError::InvalidLabel { index, label } => {
+                write!(formatter, "the label {label} of index {index} is invalid")
+            }
+            DecodeError::InvalidValue { index } => {
+                write!(formatter, "the value at index {index} is invalid")
+            }
+        }
     }
 }
 
-impl<'a, T> IntoIterator for &'a mut PartitionVec<T> {
-    type Item = &'a mut T;
-    type IntoIter = std::slice::IterMut<'a, T>;
+impl std::error::Error for DecodeError {}
 
-    fn into_iter(self) -> std::slice::IterMut<'a, T> {
-        self.data.iter_mut()
-    }
+/// An iterator over a set in a `PartitionVec<T>` that allows mutating elements.
+///
+/// This struct is created by the [`set_mut`] method on [`PartitionVec<T>`].
+/// See its documentation for more.
+///
+/// [`set_mut`]: struct.PartitionVec.html#method.set_mut
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+///
+/// The values are accessed through a pointer instead of a mutable reference to the
+/// `PartitionVec<T>`. Creating a mutable reference to the whole slice of values for every
+/// call to `next` would invalidate the references that were returned before.
+/// The `PartitionVec<T>` is mutably borrowed for `'a` so only the `SetMut`s that are created
+/// together by [`all_sets_mut`] access it, and each of them only accesses its own set.
+///
+/// A `SetMut` is not `Send`.
+/// The sets of an [`all_sets_mut`] iterator compress paths in the same metadata, so moving
+/// one of them to another thread would race with the others.
+///
+/// ```compile_fail,E0277
+/// fn assert_send<S: Send>(_: S) {}
+///
+/// let mut partition_vec = partitions::PartitionVec::from(vec![1, 2, 3]);
+/// assert_send(partition_vec.set_mut(0));
+/// ```
+///
+/// [`all_sets_mut`]: struct.PartitionVec.html#method.all_sets_mut
+#[derive(Debug)]
+pub struct SetMut<'a, T: 'a> {
+    data: *mut T,
+    meta: &'a [Metadata],
+    current: Option<usize>,
+    root: usize,
+    marker: PhantomData<&'a mut T>,
 }
 
-#[cfg(feature = "rayon")]
-impl<T> IntoParallelIterator for PartitionVec<T>
-where
-    T: Send,
-{
-    type Item = T;
-    type Iter = rayon::vec::IntoIter<T>;
+impl<'a, T> SetMut<'a, T> {
+    /// Creates an iterator over the set of `root` in `data` that allows mutating the values.
+    pub(crate) fn new(data: &'a mut [T], meta: &'a [Metadata], root: usize) -> Self {
+        Self {
+            data: data.as_mut_ptr(),
+            meta,
+            current: Some(root),
+            root,
+            marker: PhantomData,
+        }
+    }
 
-    fn into_par_iter(self) -> Self::Iter {
-        self.data.into_par_iter()
+    /// Returns the index of the representative of the set.
+    ///
+    /// This is the same as [`Set::root`] for the same set.
+    ///
+    /// [`Set::root`]: struct.Set.html#method.root
+    #[inline]
+    #[must_use]
+    pub fn root(&self) -> usize {
+        self.root
     }
 }
 
-#[cfg(feature = "rayon")]
-impl<'a, T> IntoParallelIterator for &'a PartitionVec<T>
-where
-    T: Send + Sync,
-{
-    type Item = &'a T;
-    type Iter = rayon::slice::Iter<'a, T>;
+impl<'a, T> Iterator for SetMut<'a, T> {
+    type Item = (usize, &'a mut T);
 
-    fn into_par_iter(self) -> Self::Iter {
-        self.data.par_iter()
-    }
-}
+    fn next(&mut self) -> Option<(usize, &'a mut T)> {
+        let current = self.current?;
 
-#[cfg(feature = "rayon")]
-impl<'a, T> IntoParallelIterator for &'a mut PartitionVec<T>
-where
-    T: Send + Sync,
-{
-    type Item = &'a mut T;
-    type Iter = rayon::slice::IterMut<'a, T>;
+        // We compress the path of every element we visit.
+        // This only lowers the height of the tree so the rank of the root stays
+        // a valid upper bound and does not need to be changed.
+        self.meta[current].set_parent(self.root);
 
-    fn into_par_iter(self) -> Self::Iter {
-        self.data.par_iter_mut()
+        let next = self.meta[current].link();
+
+        // We started at the root.
+        self.current = if next == self.root { None } else { Some(next) };
+
+        // The index is in bounds because the metadata has the same length as the values.
+        // This iterator visits every index of its set once and no other iterator visits this
+        // set so this is the only reference to the value.
+        unsafe { Some((current, &mut *self.data.add(current))) }
     }
 }
 
-impl<T> Extend<T> for PartitionVec<T> {
-    fn extend<I>(&mut self, iter: I)
-    where
-        I: IntoIterator<Item = T>,
-    {
-        let len = self.len();
-        self.data.extend(iter);
-        let new_len = self.data.len();
+impl<T> FusedIterator for SetMut<'_, T> {}
 
-        self.meta.extend((len..new_len).map(Metadata::new));
-    }
+/// An iterator over all sets in a `PartitionVec<T>`.
+///
+/// This struct is created by the [`all_sets`] method on [`PartitionVec<T>`].
+/// See its documentation for more information.
+///
+/// [`all_sets`]: struct.PartitionVec.html#method.all_sets
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[derive(Debug)]
+pub struct AllSets<'a, T: 'a> {
+    data: &'a [T],
+    meta: &'a [Metadata],
+    done: bit_vec::BitVec,
+    range: ops::Range<usize>,
 }
 
-impl<'a, T> Extend<&'a T> for PartitionVec<T>
-where
-    T: Copy + 'a,
-{
-    fn extend<I>(&mut self, iter: I)
-    where
-        I: IntoIterator<Item = &'a T>,
-    {
-        let len = self.len();
-        self.data.extend(iter);
-        let new_len = self.data.len();
+impl<'a, T> AllSets<'a, T> {
+    /// Creates an iterator over all sets in `meta` that yields the values in `data`.
+    pub(crate) fn new(data: &'a [T], meta: &'a [Metadata]) -> Self {
+        Self {
+            data,
+            meta,
+            done: bit_vec![false; meta.len()],
+            range: 0..meta.len(),
+        }
+    }
+}
 
-        self.meta.extend((len..new_len).map(Metadata::new));
+impl<T> Clone for AllSets<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            meta: self.meta,
+            done: self.done.clone(),
+            range: self.range.clone(),
+        }
     }
 }
 
-#[cfg(feature = "rayon")]
-impl<T> ParallelExtend<T> for PartitionVec<T>
-where
-    T: Send,
-{
-    fn par_extend<I>(&mut self, par_iter: I)
-    where
-        I: IntoParallelIterator<Item = T>,
-    {
-        let par_iter = par_iter.into_par_iter();
+impl<'a, T> Iterator for AllSets<'a, T> {
+    type Item = Set<'a, T>;
 
-        self.data.par_extend(par_iter);
-        self.meta
-            .par_extend((0..self.data.len()).into_par_iter().map(Metadata::new));
+    fn next(&mut self) -> Option<Set<'a, T>> {
+        // We keep going until we find a set we have not returned yet.
+        loop {
+            let index = self.range.next()?;
+            let root = find_final_in(self.meta, index);
+
+            // If we have not returned this set yet.
+            if !self.done.get(root).unwrap() {
+                self.done.set(root, true);
+
+                return Some(Set::new(self.data, self.meta, root));
+            }
+        }
     }
 }
 
-#[cfg(feature = "rayon")]
-impl<'a, T> ParallelExtend<&'a T> for PartitionVec<T>
-where
-    T: Copy + Send + Sync + 'a,
-{
-    fn par_extend<I>(&mut self, par_iter: I)
-    where
-        I: IntoParallelIterator<Item = &'a T>,
-    {
-        self.par_extend(par_iter.into_par_iter().cloned())
+impl<'a, T> DoubleEndedIterator for AllSets<'a, T> {
+    fn next_back(&mut self) -> Option<Set<'a, T>> {
+        // We keep going until we find a set we have not returned yet.
+        loop {
+            let index = self.range.next_back()?;
+            let root = find_final_in(self.meta, index);
+
+            // If we have not returned this set yet.
+            if !self.done.get(root).unwrap() {
+                self.done.set(root, true);
+
+                return Some(Set::new(self.data, self.meta, root));
+            }
+        }
     }
 }
 
-#[cfg(feature = "proptest")]
-impl<T> Arbitrary for PartitionVec<T>
-where
-    T: Arbitrary,
-    T::Strategy: 'static,
-{
-    type Parameters = (proptest::collection::SizeRange, T::Parameters);
-    type Strategy = BoxedStrategy<Self>;
+impl<T> FusedIterator for AllSets<'_, T> {}
 
-    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
-        use std::collections::hash_map;
+/// An iterator over all sets in a `PartitionVec<T>` that allows mutating elements.
+///
+/// This struct is created by the [`all_sets`] method on [`PartitionVec<T>`].
+/// See its documentation for more information.
+///
+/// An `AllSetsMut` is not `Send` because the [`SetMut`]s it returned before can still be
+/// compressing paths in the same metadata on the original thread.
+///
+/// ```compile_fail,E0277
+/// fn assert_send<S: Send>(_: S) {}
+///
+/// let mut partition_vec = partitions::PartitionVec::from(vec![1, 2, 3]);
+/// assert_send(partition_vec.all_sets_mut());
+/// ```
+///
+/// [`all_sets`]: struct.PartitionVec.html#method.all_sets
+/// [`PartitionVec<T>`]: struct.PartitionVec.html
+/// [`SetMut`]: struct.SetMut.html
+#[derive(Debug)]
+pub struct AllSetsMut<'a, T: 'a> {
+    data: *mut T,
+    meta: &'a [Metadata],
+    done: bit_vec::BitVec,
+    range: ops::Range<usize>,
+    marker: PhantomData<&'a mut T>,
+}
 
-        let (size_range, params) = params;
-        let params = (size_range, (params, ()));
+impl<'a, T> AllSetsMut<'a, T> {
+    /// Creates an iterator over all sets in `meta` that allows mutating the values in `data`.
+    pub(crate) fn new(data: &'a mut [T], meta: &'a [Metadata]) -> Self {
+        Self {
+            data: data.as_mut_ptr(),
+            meta,
+            done: bit_vec![false; meta.len()],
+            range: 0..meta.len(),
+            marker: PhantomData,
+        }
+    }
+}
 
-        (Vec::<(T, usize)>::arbitrary_with(params))
-            .prop_map(|vec| {
-                let mut partition_vec = Self::with_capacity(vec.len());
+impl<'a, T> Iterator for AllSetsMut<'a, T> {
+    type Item = SetMut<'a, T>;
 
-                // We map a `set_number` to an `index` of that set.
-                let mut map = hash_map::HashMap::with_capacity(vec.len());
+    fn next(&mut self) -> Option<SetMut<'a, T>> {
+        // We keep going until we find a set we have not returned yet.
+        loop {
+            let index = self.range.next()?;
+            let root = find_final_in(self.meta, index);
 
-                for (index, (value, set_number)) in vec.into_iter().enumerate() {
-                    partition_vec.push(value);
+            // If we have not returned this set yet.
+            if !self.done.get(root).unwrap() {
+                self.done.set(root, true);
 
-                    let set_number = set_number.trailing_zeros();
+                // We will not return this set again so the sets never share a value.
+                return Some(SetMut {
+                    data: self.data,
+                    meta: self.meta,
+                    current: Some(root),
+                    root,
+                    marker: PhantomData,
+                });
+            }
+        }
+    }
+}
 
-                    match map.entry(set_number) {
-                        hash_map::Entry::Occupied(occupied) => {
-                            partition_vec.union(index, *occupied.get());
-                        }
-                        hash_map::Entry::Vacant(vacant) => {
-                            vacant.insert(index);
-                        }
-                    }
-                }
+impl<'a, T> DoubleEndedIterator for AllSetsMut<'a, T> {
+    fn next_back(&mut self) -> Option<SetMut<'a, T>> {
+        // We keep going until we find a set we have not returned yet.
+        loop {
+            let index = self.range.next_back()?;
+            let root = find_final_in(self.meta, index);
 
-                partition_vec
-            })
-            .boxed()
+            // If we have not returned this set yet.
+            if !self.done.get(root).unwrap() {
+                self.done.set(root, true);
+
+                // We will not return this set again so the sets never share a value.
+                return Some(SetMut {
+                    data: self.data,
+                    meta: self.meta,
+                    current: Some(root),
+                    root,
+                    marker: PhantomData,
+                });
+            }
+        }
     }
 }
 
-/// An iterator over a set in a `PartitionVec<T>`.
+impl<T> FusedIterator for AllSetsMut<'_, T> {}
+
+/// An iterator that moves the values of a `PartitionVec<T>` out per set.
 ///
-/// This struct is created by the [`set`] method on [`PartitionVec<T>`].
-/// See its documentation for more.
+/// This struct is created by the [`into_sets`] method on [`PartitionVec<T>`].
+/// See its documentation for more information.
 ///
-/// [`set`]: struct.PartitionVec.html#method.set
+/// [`into_sets`]: struct.PartitionVec.html#method.into_sets
 /// [`PartitionVec<T>`]: struct.PartitionVec.html
 #[derive(Clone, Debug)]
-pub struct Set<'a, T: 'a> {
-    partition_vec: &'a PartitionVec<T>,
-    current: Option<usize>,
-    root: usize,
+pub struct IntoSets<T> {
+    sets: std::vec::IntoIter<Vec<T>>,
 }
 
-impl<'a, T> Iterator for Set<'a, T> {
-    type Item = (usize, &'a T);
+impl<T> Iterator for IntoSets<T> {
+    type Item = Vec<T>;
 
-    fn next(&mut self) -> Option<(usize, &'a T)> {
-        let current = self.current?;
-
-        self.partition_vec.meta[current].set_parent(self.root);
-
-        let next = self.partition_vec.meta[current].link();
+    #[inline]
+    fn next(&mut self) -> Option<Vec<T>> {
+        self.sets.next()
+    }
 
-        // We started at the root.
-        self.current = if next == self.root { None } else { Some(next) };
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sets.size_hint()
+    }
+}
 
-        Some((current, &self.partition_vec.data[current]))
+impl<T> DoubleEndedIterator for IntoSets<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Vec<T>> {
+        self.sets.next_back()
     }
 }
 
-impl<'a, T> FusedIterator for Set<'a, T> {}
+impl<T> ExactSizeIterator for IntoSets<T> {}
 
-/// An iterator over a set in a `PartitionVec<T>` that allows mutating elements.
+impl<T> FusedIterator for IntoSets<T> {}
+
+/// An iterator over a set in a `PartitionVec<T>` that allows mutating elements and can be
+/// sent to another thread.
 ///
-/// This struct is created by the [`set_mut`] method on [`PartitionVec<T>`].
-/// See its documentation for more.
+/// This struct is created by the [`par_all_sets_mut`] method on [`PartitionVec<T>`].
+/// See its documentation for more information.
 ///
-/// [`set_mut`]: struct.PartitionVec.html#method.set_mut
+/// [`par_all_sets_mut`]: struct.PartitionVec.html#method.par_all_sets_mut
 /// [`PartitionVec<T>`]: struct.PartitionVec.html
+#[cfg(feature = "rayon")]
 #[derive(Debug)]
-pub struct SetMut<'a, T: 'a> {
-    partition_vec: &'a mut PartitionVec<T>,
-    current: Option<usize>,
-    root: usize,
+pub struct ParSetMut<'a, T: 'a> {
+    data: *mut T,
+    indices: std::vec::IntoIter<usize>,
+    marker: PhantomData<&'a mut T>,
 }
 
-impl<'a, T> Iterator for SetMut<'a, T> {
-    type Item = (usize, &'a mut T);
+// The indices of different `ParSetMut` values are disjoint and every index is only
+// returned once, so this is an exclusive borrow of the values at these indices just
+// like a `&mut [T]` is an exclusive borrow that can be sent when `T` can be sent.
+#[cfg(feature = "rayon")]
+unsafe impl<T> Send for ParSetMut<'_, T> where T: Send {}
 
-    fn next(&mut self) -> Option<(usize, &'a mut T)> {
-        let current = self.current?;
+// A shared `ParSetMut` can not be used to get any reference to the values.
+#[cfg(feature = "rayon")]
+unsafe impl<T> Sync for ParSetMut<'_, T> where T: Sync {}
 
-        self.partition_vec.meta[current].set_parent(self.root);
+#[cfg(feature = "rayon")]
+impl<'a, T> Iterator for ParSetMut<'a, T> {
+    type Item = (usize, &'a mut T);
 
-        let next = self.partition_vec.meta[current].link();
+    fn next(&mut self) -> Option<(usize, &'a mut T)> {
+        let index = self.indices.next()?;
 
-        // We started at the root.
-        self.current = if next == self.root { None } else { Some(next) };
+        // The index is in bounds and is not returned again by any `ParSetMut`
+        // created by the same call, so the returned reference is unique.
+        Some((index, unsafe { &mut *self.data.add(index) }))
+    }
 
-        // This iterator wont give a reference to this value again so it is safe to extend
-        // the lifetime of the mutable reference.
-        unsafe { Some((current, extend_mut(&mut self.partition_vec.data[current]))) }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
     }
 }
 
-impl<'a, T> FusedIterator for SetMut<'a, T> {}
+#[cfg(feature = "rayon")]
+impl<T> ExactSizeIterator for ParSetMut<'_, T> {}
 
-/// An iterator over all sets in a `PartitionVec<T>`.
+#[cfg(feature = "rayon")]
+impl<T> FusedIterator for ParSetMut<'_, T> {}
+
+/// Gives the label for the set with the given `root`.
 ///
-/// This struct is created by the [`all_sets`] method on [`PartitionVec<T>`].
+/// If the `root` has not been seen before it gets the next unused label.
+fn label_of_root(labels: &mut std::collections::HashMap<usize, usize>, root: usize) -> usize {
+    let new_label = labels.len();
+
+    *labels.entry(root).or_insert(new_label)
+}
+
+/// An iterator over all elements in a `PartitionVec<T>` together with a label for their set.
+///
+/// This struct is created by the [`iter_with_sets`] method on [`PartitionVec<T>`].
 /// See its documentation for more information.
 ///
-/// [`all_sets`]: struct.PartitionVec.html#method.all_sets
+/// [`iter_with_sets`]: struct.PartitionVec.html#method.iter_with_sets
 /// [`PartitionVec<T>`]: struct.PartitionVec.html
-#[derive(Clone, Debug)]
-pub struct AllSets<'a, T: 'a> {
+#[derive(Debug)]
+pub struct IterWithSets<'a, T: 'a> {
     partition_vec: &'a PartitionVec<T>,
-    done: bit_vec::BitVec,
+    labels: std::collections::HashMap<usize, usize>,
     range: ops::Range<usize>,
 }
 
-impl<'a, T> Iterator for AllSets<'a, T> {
-    type Item = Set<'a, T>;
-
-    fn next(&mut self) -> Option<Set<'a, T>> {
-        // We keep going until we find a set we have not returned yet.
-        loop {
-            let index = self.range.next()?;
-            let root = self.partition_vec.find_final(index);
-
-            // If we have not returned this set yet.
-            if !self.done.get(root).unwrap() {
-                self.done.set(root, true);
-
-                return Some(Set {
-                    partition_vec: self.partition_vec,
-                    current: Some(root),
-                    root,
-                });
-            }
+impl<T> Clone for IterWithSets<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            partition_vec: self.partition_vec,
+            labels: self.labels.clone(),
+            range: self.range.clone(),
         }
     }
 }
 
-impl<'a, T> DoubleEndedIterator for AllSets<'a, T> {
-    fn next_back(&mut self) -> Option<Set<'a, T>> {
-        // We keep going until we find a set we have not returned yet.
-        loop {
-            let index = self.range.next_back()?;
-            let root = self.partition_vec.find_final(index);
+impl<'a, T> Iterator for IterWithSets<'a, T> {
+    type Item = (usize, usize, &'a T);
 
-            // If we have not returned this set yet.
-            if !self.done.get(root).unwrap() {
-                self.done.set(root, true);
+    fn next(&mut self) -> Option<(usize, usize, &'a T)> {
+        let index = self.range.next()?;
+        let label = label_of_root(&mut self.labels, self.partition_vec.find(index));
 
-                return Some(Set {
-                    partition_vec: self.partition_vec,
-                    current: Some(root),
-                    root,
-                });
-            }
-        }
+        Some((index, label, &self.partition_vec.data[index]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
     }
 }
 
-impl<'a, T> FusedIterator for AllSets<'a, T> {}
+impl<T> ExactSizeIterator for IterWithSets<'_, T> {}
 
-/// An iterator over all sets in a `PartitionVec<T>` that allows mutating elements.
+impl<T> FusedIterator for IterWithSets<'_, T> {}
+
+/// An iterator over all elements in a `PartitionVec<T>` together with a label for their set
+/// that allows mutating elements.
 ///
-/// This struct is created by the [`all_sets`] method on [`PartitionVec<T>`].
+/// This struct is created by the [`iter_with_sets_mut`] method on [`PartitionVec<T>`].
 /// See its documentation for more information.
 ///
-/// [`all_sets`]: struct.PartitionVec.html#method.all_sets
+/// [`iter_with_sets_mut`]: struct.PartitionVec.html#method.iter_with_sets_mut
 /// [`PartitionVec<T>`]: struct.PartitionVec.html
 #[derive(Debug)]
-pub struct AllSetsMut<'a, T: 'a> {
-    partition_vec: &'a mut PartitionVec<T>,
-    done: bit_vec::BitVec,
+pub struct IterWithSetsMut<'a, T: 'a> {
+    data: *mut T,
+    meta: &'a [Metadata],
+    labels: std::collections::HashMap<usize, usize>,
     range: ops::Range<usize>,
+    marker: PhantomData<&'a mut T>,
 }
 
-impl<'a, T> Iterator for AllSetsMut<'a, T> {
-    type Item = SetMut<'a, T>;
+// This iterator has unique access to the `PartitionVec<T>` it was created from.
+unsafe impl<T> Send for IterWithSetsMut<'_, T> where T: Send {}
 
-    fn next(&mut self) -> Option<SetMut<'a, T>> {
-        // We keep going until we find a set we have not returned yet.
-        loop {
-            let index = self.range.next()?;
-            let root = self.partition_vec.find_final(index);
+impl<'a, T> Iterator for IterWithSetsMut<'a, T> {
+    type Item = (usize, usize, &'a mut T);
 
-            // If we have not returned this set yet.
-            if !self.done.get(root).unwrap() {
-                self.done.set(root, true);
+    fn next(&mut self) -> Option<(usize, usize, &'a mut T)> {
+        let index = self.range.next()?;
+        let label = label_of_root(&mut self.labels, find_in(self.meta, index));
 
-                // This is safe because we will not return this set again.
-                unsafe {
-                    return Some(SetMut {
-                        partition_vec: extend_mut(self).partition_vec,
-                        current: Some(root),
-                        root,
-                    });
+        // The range only contains indices that are in bounds
+        // and every index is only returned once.
+        unsafe { Some((index, label, &mut *self.data.add(index))) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterWithSetsMut<'_, T> {}
+
+impl<T> FusedIterator for IterWithSetsMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        checked_len, InvariantViolation, Metadata, PartitionVec, SharedPartitionVec, MAX_LEN,
+        MAX_RANK,
+    };
+    use std::borrow::{Borrow, BorrowMut};
+    use test_helpers::{apply, Operation};
+
+    #[test]
+    fn representatives_match_find() {
+        let partition_vec: PartitionVec<()> = PartitionVec::from(vec![(); 64]);
+        for i in 1..64 {
+            partition_vec.meta[i].set_parent(i / 2 - i / 8);
+        }
+        let meta = partition_vec.meta.clone();
+        let indices: Vec<usize> = (0..64).rev().chain(0..64).collect();
+
+        let individual = PartitionVec {
+            data: partition_vec.data.clone(),
+            meta,
+        };
+        let expected: Vec<usize> = indices.iter().map(|&i| individual.find(i)).collect();
+
+        assert_eq!(partition_vec.representatives(&indices), expected);
+        for i in 0..64 {
+            assert_eq!(partition_vec.meta[i].parent(), 0);
+        }
+    }
+
+    #[test]
+    fn checked_len_near_the_limit() {
+        assert!(checked_len(MAX_LEN - 2, 2) == MAX_LEN);
+        assert!(checked_len(0, MAX_LEN) == MAX_LEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "A PartitionVec can only hold")]
+    fn checked_len_detects_overflow() {
+        checked_len(MAX_LEN, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "A PartitionVec can only hold")]
+    fn checked_len_detects_wrapping() {
+        checked_len(!0, !0);
+    }
+
+    /// Builds a `PartitionVec` with the five sets `{i | i % 5 == r}` for `r` in `0..5`.
+    fn five_sets() -> PartitionVec<usize> {
+        let mut partition_vec: PartitionVec<usize> = (0..50).collect();
+        let operations: Vec<Operation> = (5..50).map(|i| Operation::Union(i - 5, i)).collect();
+        apply(&mut partition_vec, &operations);
+
+        partition_vec
+    }
+
+    /// Checks that the value at every index is `expected(index)`.
+    fn assert_values(partition_vec: &PartitionVec<usize>, expected: impl Fn(usize) -> usize) {
+        for (index, &value) in partition_vec.iter().enumerate() {
+            assert!(value == expected(index));
+        }
+    }
+
+    #[test]
+    fn set_mut_references_stay_valid() {
+        let mut partition_vec = five_sets();
+
+        let references: Vec<(usize, &mut usize)> = partition_vec.set_mut(7).collect();
+        assert!(references.len() == 10);
+        for (index, value) in references {
+            *value += 100 * index;
+        }
+
+        assert_values(&partition_vec, |index| {
+            if index % 5 == 2 {
+                index * 101
+            } else {
+                index
+            }
+        });
+    }
+
+    #[test]
+    fn all_sets_mut_interleaved() {
+        let mut partition_vec = five_sets();
+
+        let mut sets = Vec::new();
+        {
+            let mut all_sets = partition_vec.all_sets_mut();
+            while let Some(set) = all_sets.next() {
+                sets.push(set);
+                sets.extend(all_sets.next_back());
+            }
+        }
+        assert!(sets.len() == 5);
+
+        // We take values of all sets in turns while holding on to every reference.
+        let mut references = Vec::new();
+        while !sets.is_empty() {
+            sets.retain_mut(|set| match set.next() {
+                Some(reference) => {
+                    references.push(reference);
+                    true
                 }
+                None => false,
+            });
+        }
+        assert!(references.len() == 50);
+        for (index, value) in &mut references {
+            **value = *index % 5;
+        }
+        drop(references);
+
+        assert_values(&partition_vec, |index| index % 5);
+        assert!(partition_vec.amount_of_sets() == 5);
+    }
+
+    #[test]
+    fn iter_with_sets_mut_references_stay_valid() {
+        let mut partition_vec = five_sets();
+
+        let references: Vec<(usize, usize, &mut usize)> =
+            partition_vec.iter_with_sets_mut().collect();
+        for (_, label, value) in references {
+            *value = label;
+        }
+
+        assert_values(&partition_vec, |index| index % 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "is out of bounds for a length of 3.")]
+    fn index_range_up_to_usize_max() {
+        let mut partition_vec = PartitionVec::from(vec![0, 0, 1]);
+        partition_vec.union_adjacent_if_in(1..=usize::MAX, PartialEq::eq);
+    }
+
+    #[test]
+    fn ranks_round_trip() {
+        let meta = Metadata::new(5);
+        meta.set_parent(3);
+        meta.set_link(7);
+
+        for rank in 0..8 * std::mem::size_of::<usize>() {
+            meta.set_rank(rank);
+            assert!(meta.rank() == rank);
+            assert!(meta.parent() == 3 && meta.link() == 7);
+        }
+    }
+
+    #[test]
+    fn iterating_sets_keeps_ranks() {
+        let mut partition_vec = PartitionVec::from(vec![(); 8]);
+        // Two sets of four elements whose roots have rank 2.
+        for &(first, second) in &[(0, 1), (2, 3), (1, 3), (4, 5), (6, 7), (5, 7)] {
+            partition_vec.union(first, second);
+        }
+        let root = partition_vec.find(0);
+        let ranks: Vec<usize> = partition_vec.meta.iter().map(Metadata::rank).collect();
+        assert!(partition_vec.meta[root].rank() == 2);
+
+        for _ in 0..3 {
+            assert!(partition_vec.set(0).count() == 4);
+            assert!(partition_vec.set_mut(5).count() == 4);
+            assert!(partition_vec.all_sets().count() == 2);
+            assert!(partition_vec.all_sets_mut().count() == 2);
+        }
+        let ranks_after: Vec<usize> = partition_vec.meta.iter().map(Metadata::rank).collect();
+        assert!(ranks == ranks_after);
+
+        // A union with a lower ranked tree keeps the old root and does not grow the tree.
+        partition_vec.push(());
+        partition_vec.push(());
+        partition_vec.union(8, 9);
+        partition_vec.union(8, 0);
+        assert!(partition_vec.find_final(8) == root);
+        assert!(partition_vec.meta[root].rank() == 2);
+    }
+
+    #[test]
+    fn eq_requires_the_same_sets() {
+        let singletons = partition_vec![1, 2];
+        let joined = partition_vec![1 => 'a', 2 => 'a'];
+        assert!(singletons != joined);
+        assert!(joined != singletons);
+
+        let coarse = partition_vec![
+            'a' => 0,
+            'b' => 0,
+            'c' => 0,
+            'd' => 1
+        ];
+        let fine = partition_vec![
+            'a' => 0,
+            'b' => 1,
+            'c' => 0,
+            'd' => 2
+        ];
+        assert!(coarse != fine);
+        assert!(fine != coarse);
+    }
+
+    #[test]
+    fn eq_ignores_union_order() {
+        let mut first = PartitionVec::from(vec!['a', 'b', 'c', 'd', 'e']);
+        let mut second = first.clone();
+        for &(a, b) in &[(0, 1), (1, 2), (3, 4)] {
+            first.union(a, b);
+        }
+        for &(a, b) in &[(4, 3), (2, 0), (1, 2)] {
+            second.union(a, b);
+        }
+        assert!(first == second);
+        assert!(second == first);
+
+        second[4] = 'f';
+        assert!(first != second);
+        assert!(second != first);
+    }
+
+    #[test]
+    fn labels_survive_path_compression() {
+        let mut partition_vec = PartitionVec::from(vec![(); 16]);
+        // Repeatedly joining sets of equal size builds trees with long paths.
+        let mut size = 1;
+        while size < 16 {
+            for start in (0..16).step_by(2 * size) {
+                partition_vec.union(start, start + size);
             }
+            size *= 2;
+        }
+        let labels = partition_vec.relabel(vec![(15, "all")]);
+        let root = partition_vec.find_final(0);
+        let depth = |partition_vec: &PartitionVec<()>| {
+            (0..16)
+                .map(|mut index| {
+                    let mut depth = 0;
+                    while partition_vec.meta[index].parent() != index {
+                        index = partition_vec.meta[index].parent();
+                        depth += 1;
+                    }
+                    depth
+                })
+                .max()
+        };
+        assert!(depth(&partition_vec) >= Some(2));
+
+        for index in 0..16 {
+            assert!(labels.get(&partition_vec, index) == Some(&"all"));
+        }
+        assert!(partition_vec.set(3).count() == 16);
+
+        assert!(depth(&partition_vec) == Some(1));
+        assert!(partition_vec.find_final(0) == root);
+        for index in 0..16 {
+            assert!(labels.get(&partition_vec, index) == Some(&"all"));
         }
+        assert!(labels.len() == 1);
     }
-}
 
-impl<'a, T> DoubleEndedIterator for AllSetsMut<'a, T> {
-    fn next_back(&mut self) -> Option<SetMut<'a, T>> {
-        // We keep going until we find a set we have not returned yet.
-        loop {
-            let index = self.range.next_back()?;
-            let root = self.partition_vec.find_final(index);
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn set_values_mut_in_parallel() {
+        use rayon::prelude::*;
 
-            // If we have not returned this set yet.
-            if !self.done.get(root).unwrap() {
-                self.done.set(root, true);
+        let mut partition_vec = five_sets();
+        partition_vec
+            .set_values_mut(3)
+            .into_par_iter()
+            .for_each(|value| *value *= 2);
 
-                // This is safe because we will not return this set again.
-                unsafe {
-                    return Some(SetMut {
-                        partition_vec: extend_mut(self).partition_vec,
-                        current: Some(root),
-                        root,
-                    });
-                }
+        assert_values(&partition_vec, |index| {
+            if index % 5 == 3 {
+                index * 2
+            } else {
+                index
             }
+        });
+    }
+
+    #[test]
+    fn validate_finds_violations() {
+        let valid = || {
+            let mut partition_vec = PartitionVec::from(vec![(); 4]);
+            partition_vec.union(0, 1);
+            partition_vec.union(2, 3);
+            partition_vec.union(1, 3);
+            partition_vec.assert_invariants();
+            partition_vec
+        };
+        let root = valid().find(0);
+
+        let partition_vec = valid();
+        partition_vec.meta[2].set_parent(7);
+        assert!(
+            partition_vec.validate()
+                == Err(InvariantViolation::ParentOutOfBounds {
+                    index: 2,
+                    parent: 7
+                })
+        );
+
+        let partition_vec = valid();
+        partition_vec.meta[1].set_link(4);
+        assert!(
+            partition_vec.validate()
+                == Err(InvariantViolation::LinkOutOfBounds { index: 1, link: 4 })
+        );
+
+        let partition_vec = valid();
+        partition_vec.meta[root].set_rank(0);
+        assert!(matches!(
+            partition_vec.validate(),
+            Err(InvariantViolation::InvalidRank { .. })
+        ));
+
+        let partition_vec = valid();
+        let link = partition_vec.meta[0].link();
+        partition_vec.meta[1].set_link(link);
+        assert!(matches!(
+            partition_vec.validate(),
+            Err(InvariantViolation::DuplicateLink { .. })
+        ));
+
+        // Splitting the cycle of links without changing the parents.
+        let partition_vec = valid();
+        let other = partition_vec.meta[root].link();
+        let after_other = partition_vec.meta[other].link();
+        partition_vec.meta[root].set_link(after_other);
+        partition_vec.meta[other].set_link(other);
+        assert!(matches!(
+            partition_vec.validate(),
+            Err(InvariantViolation::SplitSet { .. })
+        ));
+
+        // Joining the cycles of two sets without changing the parents.
+        let partition_vec = PartitionVec::from(vec![(); 2]);
+        partition_vec.meta[0].set_link(1);
+        partition_vec.meta[1].set_link(0);
+        assert!(
+            partition_vec.validate()
+                == Err(InvariantViolation::RootMismatch {
+                    index: 1,
+                    root: 1,
+                    expected: 0
+                })
+        );
+
+        let mut partition_vec = valid();
+        partition_vec.data.push(());
+        assert!(
+            partition_vec.validate()
+                == Err(InvariantViolation::LengthMismatch {
+                    data_len: 5,
+                    meta_len: 4
+                })
+        );
+        assert!(!partition_vec.validate().unwrap_err().to_string().is_empty());
+    }
+
+    #[test]
+    fn shared_partition_vec_copies_on_write() {
+        let partition_vec = five_sets();
+        let chain = partition_vec.parent_chain(49);
+        let shared = partition_vec.clone().share();
+        let mut copy = shared.clone();
+
+        assert!(SharedPartitionVec::ptr_eq(&shared, &copy));
+        assert!(shared.as_ptr() == copy.as_ptr());
+
+        // Reading does not copy or compress anything.
+        for i in 0..50 {
+            assert!(copy.same_set(i, 49) == partition_vec.same_set(i, 49));
+            assert!(copy.len_of_set(i) == partition_vec.len_of_set(i));
+            assert!(copy.set(i).count() == 10);
         }
+        assert!(SharedPartitionVec::ptr_eq(&shared, &copy));
+        assert!(shared.inner.parent_chain(49) == chain);
+
+        copy.make_mut().union(0, 1);
+        assert!(!SharedPartitionVec::ptr_eq(&shared, &copy));
+        assert!(shared.as_ptr() != copy.as_ptr());
+        assert!(copy.len_of_set(0) == 20);
+        assert!(shared.len_of_set(0) == 10);
+
+        // A handle that is not shared is changed in place.
+        let ptr = copy.as_ptr();
+        copy.make_mut()[3] = 100;
+        assert!(copy.as_ptr() == ptr);
+        assert!(shared[3] == 3);
+
+        assert!(shared.into_inner() == partition_vec);
+    }
+
+    #[test]
+    fn bytes_format() {
+        let mut partition_vec = PartitionVec::from(vec![7u8, 8, 9]);
+        partition_vec.union(2, 0);
+
+        // The length and the labels followed by the length and bytes of every value.
+        let mut expected = b"PVEC\x01".to_vec();
+        for integer in &[3u64, 0, 1, 0] {
+            expected.extend(&integer.to_le_bytes());
+        }
+        for &value in &[7, 8, 9] {
+            expected.extend(&1u64.to_le_bytes());
+            expected.push(value);
+        }
+
+        let bytes = partition_vec.to_bytes(|&value, bytes| bytes.push(value));
+        assert!(bytes == expected);
+
+        let decoded = PartitionVec::from_bytes(&bytes, |bytes| bytes.first().copied()).unwrap();
+        assert!(decoded == partition_vec);
+    }
+
+    #[test]
+    fn union_prefer_keeps_ranks_valid() {
+        // Always absorbing the larger set raises the rank every time.
+        let mut partition_vec = PartitionVec::from(vec![(); 300]);
+        for i in 1..300 {
+            partition_vec.union_prefer(i, 0);
+            partition_vec.assert_invariants();
+            assert!(partition_vec.find(0) == i);
+        }
+        assert!(partition_vec.len_of_set(0) == 300);
+        assert!(partition_vec.parent_chain(0).len() <= MAX_RANK + 1);
+    }
+
+    #[test]
+    fn slice_conversions() {
+        fn sum<S: AsRef<[u32]>>(values: &S) -> u32 {
+            values.as_ref().iter().sum()
+        }
+
+        fn double<S: AsMut<[u32]>>(values: &mut S) {
+            for value in values.as_mut() {
+                *value *= 2;
+            }
+        }
+
+        fn first<S: Borrow<[u32]>>(values: &S) -> u32 {
+            values.borrow()[0]
+        }
+
+        fn reverse<S: BorrowMut<[u32]>>(values: &mut S) {
+            values.borrow_mut().reverse();
+        }
+
+        let mut partition_vec = PartitionVec::from(vec![1, 2, 3]);
+        partition_vec.union(0, 1);
+
+        assert!(sum(&partition_vec) == 6);
+        double(&mut partition_vec);
+        assert!(partition_vec.as_slice() == [2, 4, 6]);
+        reverse(&mut partition_vec);
+        assert!(first(&partition_vec) == 6);
+        assert!(partition_vec.same_set(0, 1));
+        assert!(partition_vec.is_singleton(2));
+
+        let vec: Vec<u32> = partition_vec.into();
+        assert!(vec == [6, 4, 2]);
     }
-}
 
-impl<'a, T> FusedIterator for AllSetsMut<'a, T> {}
+    #[test]
+    fn with_capacity_reserves_meta() {
+        let mut partition_vec = PartitionVec::with_capacity(10);
+        assert!(partition_vec.data.capacity() >= 10);
+        assert!(partition_vec.meta.capacity() >= 10);
+
+        partition_vec.reserve(20);
+        assert!(partition_vec.data.capacity() >= 20);
+        assert!(partition_vec.meta.capacity() >= 20);
+        partition_vec.push(());
+        partition_vec.assert_meta_data_len_match();
+    }
+
+    #[test]
+    #[should_panic(expected = "The PartitionVec has 1 values but 0 metadata entries.")]
+    fn meta_data_len_mismatch() {
+        let mut partition_vec = PartitionVec::from(vec![()]);
+        partition_vec.meta.clear();
+        partition_vec.assert_meta_data_len_match();
+    }
+
+    #[test]
+    fn reset_ranks_after_compress_all() {
+        let mut partition_vec = PartitionVec::from(vec![(); 16]);
+        for step in [1, 2, 4, 8] {
+            for i in (0..16).step_by(2 * step) {
+                partition_vec.union(i, i + step);
+            }
+        }
+        partition_vec.make_singleton(15);
+        let original = partition_vec.clone();
+
+        partition_vec.compress_all();
+        partition_vec.reset_ranks();
+        partition_vec.assert_invariants();
+        for (index, meta) in partition_vec.meta.iter().enumerate() {
+            if meta.parent() == index {
+                assert!(meta.rank() == usize::from(index != 15));
+            } else {
+                assert!(meta.rank() == 0);
+            }
+        }
+        assert!(partition_vec == original);
+    }
+
+    #[test]
+    fn set_contains_index_matches_same_set() {
+        let mut partition_vec = PartitionVec::from(vec![(); 6]);
+        partition_vec.union(0, 4);
+        partition_vec.union(4, 5);
+        partition_vec.union(1, 2);
+
+        for set_index in 0..6 {
+            for member in 0..6 {
+                assert!(
+                    partition_vec.set_contains_index(set_index, member)
+                        == partition_vec.same_set(set_index, member)
+                );
+            }
+            assert!(partition_vec.set_contains_index(set_index, set_index));
+        }
+        assert!(partition_vec.set_contains_index(5, 0));
+        assert!(!partition_vec.set_contains_index(3, 2));
+    }
+
+    #[test]
+    fn reserve_keeps_capacities_equal() {
+        let mut partition_vec = PartitionVec::from(Vec::<u64>::with_capacity(5));
+        assert!(partition_vec.data.capacity() != partition_vec.meta.capacity());
+
+        for additional in [6, 0, 13, 40] {
+            let capacity = partition_vec.reserve_and_report(additional);
+
+            assert!(capacity >= partition_vec.len() + additional);
+            assert!(partition_vec.data.capacity() == capacity);
+            assert!(partition_vec.meta.capacity() == capacity);
+            partition_vec.extend(0..3);
+        }
+    }
+
+    #[test]
+    fn reserve_keeps_the_larger_capacity() {
+        let mut data = Vec::with_capacity(64);
+        data.extend(0..4);
+        let mut partition_vec = PartitionVec::from(data);
+
+        partition_vec.reserve(8);
+        assert!(partition_vec.data.capacity() >= 64);
+        assert!(partition_vec.meta.capacity() >= 64);
+        assert!(partition_vec.capacity() >= 64);
+
+        partition_vec.reserve(100);
+        assert!(partition_vec.capacity() >= 104);
+        assert!(partition_vec.data.capacity() == partition_vec.meta.capacity());
+    }
+
+    #[test]
+    fn truncate_set_alternating_across_the_cut() {
+        // The set {0, 1, 2, 3, 6, 7, 8, 9} has its root at 9 and the link ring
+        // 0 -> 6 -> 1 -> 7 -> 2 -> 8 -> 3 -> 9 -> 0 crosses the cut at 4 six times.
+        // The parent of 1 is 7 and the parent of 2 is 1, so 2 only reaches the root through
+        // a removed element. The set {4, 5} is removed entirely.
+        let parents = [9, 7, 1, 8, 4, 4, 9, 9, 9, 9];
+        let links = [6, 7, 8, 9, 5, 4, 1, 2, 3, 0];
+        let ranks = [0, 1, 0, 0, 1, 0, 0, 2, 1, 3];
+        let meta = (0..10)
+            .map(|index| {
+                let meta = Metadata::new(index);
+                meta.set_parent(parents[index]);
+                meta.set_link(links[index]);
+                meta.set_rank(ranks[index]);
+
+                meta
+            })
+            .collect();
+        let mut partition_vec = PartitionVec::from_parts(vec![(); 10], meta);
+        partition_vec.assert_invariants();
+
+        partition_vec.truncate(4);
+
+        partition_vec.assert_invariants();
+        assert!(partition_vec.len() == 4);
+        assert!(partition_vec.amount_of_sets() == 1);
+        assert!(partition_vec.len_of_set(2) == 4);
+        let mut members: Vec<usize> = partition_vec.set(3).map(|(index, ())| index).collect();
+        members.sort_unstable();
+        assert!(members == [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn labels_follow_unions() {
+        let mut partition_vec = PartitionVec::from(vec![(); 8]);
+        let mut labels = partition_vec.relabel((0..8).map(|index| (index, index as u64)));
+
+        for start in (0..8).step_by(2) {
+            assert!(labels.union(&mut partition_vec, start, start + 1, u64::max));
+        }
+        assert!(labels.union(&mut partition_vec, 0, 2, u64::max));
+        assert!(labels.union(&mut partition_vec, 6, 4, u64::max));
+        assert!(!labels.union(&mut partition_vec, 1, 3, |_, _| unreachable!()));
+        partition_vec.compress_all();
+
+        assert!(labels.len() == 2);
+        for index in 0..8 {
+            let expected = if index < 4 { 3 } else { 7 };
+            assert!(labels.get(&partition_vec, index) == Some(&expected));
+        }
+    }
+}
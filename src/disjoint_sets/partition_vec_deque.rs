@@ -0,0 +1,577 @@
+//! A [disjoint-sets/union-find] implementation of a double-ended queue partitioned in sets.
+//!
+//! See [`PartitionVecDeque<T>`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`PartitionVecDeque<T>`]: struct.PartitionVecDeque.html
+
+use {
+    crate::disjoint_sets::{
+        forest::{find_in, make_singleton_in, union_roots_in, Members},
+        metadata::Metadata,
+    },
+    std::{
+        collections::{vec_deque, HashMap, VecDeque},
+        fmt,
+        iter::{FromIterator, FusedIterator},
+        ops,
+    },
+};
+
+/// A [disjoint-sets/union-find] implementation of a `VecDeque<T>` partitioned in sets.
+///
+/// This has the same sets as a [`PartitionVec<T>`] but elements can also be added and
+/// removed at the front in `O(1)` time, or `O(m)` time to remove an element from a set of
+/// `m` elements.
+/// The metadata is kept in a ring buffer and elements refer to each other by their slot in it.
+/// Slots do not change when elements are added or removed at the front, so these operations
+/// do not have to update the metadata of every element like [`PartitionVec::insert`] and
+/// [`PartitionVec::remove`] do.
+///
+/// Methods take positions counted from the current front, like a `VecDeque<T>`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::PartitionVecDeque;
+///
+/// // A window over a stream, every event joins the set of the event before it if
+/// // they have the same value.
+/// let mut window = PartitionVecDeque::new();
+/// for value in [1, 1, 2, 2, 2, 1] {
+///     window.push_back(value);
+///     let last = window.len() - 1;
+///     if last > 0 && window[last - 1] == value {
+///         window.union(last - 1, last);
+///     }
+///     if window.len() > 4 {
+///         window.pop_front();
+///     }
+/// }
+///
+/// assert!(window.iter().eq(&[2, 2, 2, 1]));
+/// assert!(window.same_set(0, 2));
+/// assert!(window.is_singleton(3));
+///
+/// // Debugging shows a label for each set, counting up from the front.
+/// assert!(format!("{window:?}") == "[2 => 0, 2 => 0, 2 => 0, 1 => 1]");
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+/// [`PartitionVec::insert`]: ../partition_vec/struct.PartitionVec.html#method.insert
+/// [`PartitionVec::remove`]: ../partition_vec/struct.PartitionVec.html#method.remove
+#[derive(Clone)]
+pub struct PartitionVecDeque<T> {
+    /// Each position has a value.
+    data: VecDeque<T>,
+    /// The slots of the ring buffer, the element at position `i` has the metadata in slot
+    /// `(head + i) % meta.len()` and the slots that are not used have arbitrary metadata.
+    meta: Vec<Metadata>,
+    /// The slot of the element at the front.
+    head: usize,
+}
+
+impl<T> PartitionVecDeque<T> {
+    /// Constructs a new, empty `PartitionVecDeque<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let deque = partitions::PartitionVecDeque::<()>::new();
+    ///
+    /// assert!(deque.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            data: VecDeque::new(),
+            meta: Vec::new(),
+            head: 0,
+        }
+    }
+
+    /// Constructs a new, empty `PartitionVecDeque<T>` with room for `capacity` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let deque = partitions::PartitionVecDeque::<()>::with_capacity(10);
+    ///
+    /// assert!(deque.capacity() >= 10);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::with_capacity(capacity),
+            meta: (0..capacity).map(Metadata::new).collect(),
+            head: 0,
+        }
+    }
+
+    /// Returns the amount of elements.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of elements that can be held without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        usize::min(self.data.capacity(), self.meta.len())
+    }
+
+    /// Adds `value` to the back as a singleton.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque = partitions::PartitionVecDeque::new();
+    /// deque.push_back('a');
+    /// deque.push_back('b');
+    ///
+    /// assert!(deque.back() == Some(&'b'));
+    /// assert!(deque.amount_of_sets() == 2);
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        self.reserve_slot();
+        let slot = (self.head + self.len()) % self.meta.len();
+
+        self.meta[slot] = Metadata::new(slot);
+        self.data.push_back(value);
+    }
+
+    /// Adds `value` to the front as a singleton.
+    ///
+    /// The positions of all other elements move up by one but their sets are not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque = partitions::PartitionVecDeque::new();
+    /// deque.push_back('b');
+    /// deque.push_back('c');
+    /// deque.union(0, 1);
+    /// deque.push_front('a');
+    ///
+    /// assert!(deque.front() == Some(&'a'));
+    /// assert!(deque.same_set(1, 2));
+    /// assert!(deque.is_singleton(0));
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        self.reserve_slot();
+        self.head = (self.head + self.meta.len() - 1) % self.meta.len();
+
+        self.meta[self.head] = Metadata::new(self.head);
+        self.data.push_front(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set
+    /// that the last element belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = (0..3).collect();
+    /// deque.union(0, 2);
+    ///
+    /// assert!(deque.pop_back() == Some(2));
+    /// assert!(deque.is_singleton(0));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        let last = self.len().checked_sub(1)?;
+        self.make_singleton(last);
+
+        self.data.pop_back()
+    }
+
+    /// Removes the first element and returns it, or `None` if it is empty.
+    ///
+    /// The positions of all other elements move down by one but their sets are not changed.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set
+    /// that the first element belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = (0..4).collect();
+    /// deque.union(0, 2);
+    /// deque.union(2, 3);
+    ///
+    /// assert!(deque.pop_front() == Some(0));
+    /// assert!(deque.same_set(1, 2));
+    /// assert!(deque.is_singleton(0));
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.make_singleton(0);
+
+        self.head = (self.head + 1) % self.meta.len();
+        self.data.pop_front()
+    }
+
+    /// Returns a reference to the first value or `None` if it is empty.
+    #[inline]
+    #[must_use]
+    pub fn front(&self) -> Option<&T> {
+        self.data.front()
+    }
+
+    /// Returns a reference to the last value or `None` if it is empty.
+    #[inline]
+    #[must_use]
+    pub fn back(&self) -> Option<&T> {
+        self.data.back()
+    }
+
+    /// Returns a reference to the value at `index` or `None` if it is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.data.get(index)
+    }
+
+    /// Returns a mutable reference to the value at `index` or `None` if it is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.data.get_mut(index)
+    }
+
+    /// Returns an iterator over the values from the front to the back.
+    #[inline]
+    #[must_use]
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns an iterator over the values from the front to the back that allows
+    /// modifying them.
+    #[inline]
+    pub fn iter_mut(&mut self) -> vec_deque::IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index`.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = (0..4).collect();
+    /// deque.union(1, 2);
+    /// deque.union(2, 3);
+    ///
+    /// assert!(deque.len_of_set(1) == 3);
+    /// assert!(deque.amount_of_sets() == 2);
+    /// ```
+    pub fn union(&mut self, first_index: usize, second_index: usize) {
+        union_roots_in(&self.meta, self.slot(first_index), self.slot(second_index));
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = (0..3).collect();
+    /// deque.union(0, 2);
+    ///
+    /// assert!(deque.same_set(2, 0));
+    /// assert!(!deque.same_set(1, 0));
+    /// ```
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        find_in(&self.meta, self.slot(first_index)) == find_in(&self.meta, self.slot(second_index))
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in different sets.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn other_sets(&self, first_index: usize, second_index: usize) -> bool {
+        !self.same_set(first_index, second_index)
+    }
+
+    /// Removes the element at `index` from its set and gives it its own set.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = (0..4).collect();
+    /// deque.union(1, 2);
+    /// deque.union(2, 3);
+    /// deque.make_singleton(2);
+    ///
+    /// assert!(deque.is_singleton(2));
+    /// assert!(deque.same_set(1, 3));
+    /// ```
+    pub fn make_singleton(&mut self, index: usize) {
+        make_singleton_in(&self.meta, self.slot(index));
+    }
+
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: usize) -> bool {
+        let slot = self.slot(index);
+
+        self.meta[slot].link() == slot
+    }
+
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[must_use]
+    pub fn len_of_set(&self, index: usize) -> usize {
+        self.set(index).count()
+    }
+
+    /// Returns the amount of sets.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        (0..self.len())
+            .filter(|&index| {
+                let slot = self.slot(index);
+                find_in(&self.meta, slot) == slot
+            })
+            .count()
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &value)` where `i` is the current position.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut deque: partitions::PartitionVecDeque<_> = "abcd".chars().collect();
+    /// deque.union(1, 3);
+    /// deque.pop_front();
+    ///
+    /// let mut set: Vec<_> = deque.set(2).collect();
+    /// set.sort_unstable();
+    /// assert!(set == [(0, &'b'), (2, &'d')]);
+    /// ```
+    #[must_use]
+    pub fn set(&self, index: usize) -> Set<'_, T> {
+        Set {
+            deque: self,
+            members: Members::new(&self.meta, find_in(&self.meta, self.slot(index))),
+        }
+    }
+
+    /// Returns the slot of the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    fn slot(&self, index: usize) -> usize {
+        assert!(
+            index < self.len(),
+            "The index {} is out of bounds for a length of {}.",
+            index,
+            self.len()
+        );
+
+        (self.head + index) % self.meta.len()
+    }
+
+    /// Returns the position of the element in `slot`.
+    fn position(&self, slot: usize) -> usize {
+        (slot + self.meta.len() - self.head) % self.meta.len()
+    }
+
+    /// Makes sure there is a slot that is not used.
+    ///
+    /// If every slot is used the amount of slots is doubled and the elements are moved to
+    /// the slots at their positions, this is `O(n)` but happens rarely enough to be amortized.
+    fn reserve_slot(&mut self) {
+        if self.len() < self.meta.len() {
+            return;
+        }
+
+        let meta = (0..usize::max(2 * self.meta.len(), 4))
+            .map(|slot| {
+                let new = Metadata::new(slot);
+                if slot < self.len() {
+                    let old = &self.meta[self.slot(slot)];
+                    new.set_parent(self.position(old.parent()));
+                    new.set_link(self.position(old.link()));
+                    new.set_rank(old.rank());
+                }
+
+                new
+            })
+            .collect();
+
+        self.meta = meta;
+        self.head = 0;
+    }
+}
+
+impl<T> Default for PartitionVecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for PartitionVecDeque<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = formatter.debug_list();
+        let mut labels = HashMap::new();
+
+        for (index, value) in self.data.iter().enumerate() {
+            let root = find_in(&self.meta, self.slot(index));
+            let next_label = labels.len();
+            let label = *labels.entry(root).or_insert(next_label);
+            builder.entry(&format_args!("{value:?} => {label}"));
+        }
+
+        builder.finish()
+    }
+}
+
+impl<T> FromIterator<T> for PartitionVecDeque<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut deque = Self::new();
+        deque.extend(iter);
+
+        deque
+    }
+}
+
+impl<T> Extend<T> for PartitionVecDeque<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PartitionVecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> vec_deque::Iter<'a, T> {
+        self.data.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PartitionVecDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = vec_deque::IterMut<'a, T>;
+
+    fn into_iter(self) -> vec_deque::IterMut<'a, T> {
+        self.data.iter_mut()
+    }
+}
+
+impl<T> ops::Index<usize> for PartitionVecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T> ops::IndexMut<usize> for PartitionVecDeque<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+/// An iterator over a set in a `PartitionVecDeque<T>`.
+///
+/// This struct is created by the [`set`] method on [`PartitionVecDeque<T>`].
+/// See its documentation for more.
+///
+/// [`set`]: struct.PartitionVecDeque.html#method.set
+/// [`PartitionVecDeque<T>`]: struct.PartitionVecDeque.html
+#[derive(Debug)]
+pub struct Set<'a, T: 'a> {
+    deque: &'a PartitionVecDeque<T>,
+    members: Members<'a>,
+}
+
+impl<T> Clone for Set<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            deque: self.deque,
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Set<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        let index = self.deque.position(self.members.next()?);
+
+        Some((index, &self.deque.data[index]))
+    }
+}
+
+impl<T> FusedIterator for Set<'_, T> {}
@@ -0,0 +1,959 @@
+//! A [disjoint-sets/union-find] implementation of a vector that stores few elements inline.
+//!
+//! See [`SmallPartitionVec<T, N>`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`SmallPartitionVec<T, N>`]: struct.SmallPartitionVec.html
+
+use {
+    crate::disjoint_sets::{
+        forest::{
+            find_final_in, find_in, fmt_labeled, make_singleton_in, shift_down_in, shift_up_in,
+            truncate_in, union_roots_in, Set,
+        },
+        metadata::Metadata,
+        partition_vec::{AllSets, AllSetsMut, PartitionVec, SetMut},
+        union_find::UnionFind,
+    },
+    std::{fmt, iter::FromIterator, mem::MaybeUninit, ops, ptr, slice},
+};
+
+/// A [disjoint-sets/union-find] implementation of a vector partitioned in sets that stores
+/// up to `N` elements inline.
+///
+/// The values and metadata of the first `N` elements are stored inside the
+/// `SmallPartitionVec<T, N>` itself so creating and filling a small partition does not
+/// allocate.
+/// When more than `N` elements are pushed everything is moved to a [`PartitionVec<T>`]
+/// on the heap, [`shrink_to_fit`] moves the elements back inline once they fit again.
+/// The sets are kept in both directions.
+///
+/// Only part of the methods of a `PartitionVec<T>` are available: adding and removing values
+/// anywhere, the set operations and iterating over one or all sets, with or without changing
+/// the values.
+/// The values can be read and changed as a slice through `Deref`.
+/// Use [`into_partition_vec`] for anything else, like the parallel iterators, the methods
+/// that reorder the values or the specialized unions.
+///
+/// While the elements are stored inline nothing allocates, except for a `push` or `insert`
+/// that moves the elements to the heap, `all_sets`, `all_sets_mut` and `into_partition_vec`.
+///
+/// Run `cargo bench --bench small_partition_vec` to compare creating many small partitions
+/// with a `PartitionVec<T>`.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::SmallPartitionVec;
+///
+/// let mut small: SmallPartitionVec<char, 4> = "abcd".chars().collect();
+/// small.union(0, 2);
+/// assert!(small.is_inline());
+///
+/// small.push('e');
+/// small.union(4, 2);
+/// assert!(!small.is_inline());
+///
+/// small.pop();
+/// small.shrink_to_fit();
+/// assert!(small.is_inline());
+/// assert!(small.same_set(0, 2));
+/// assert!(small.amount_of_sets() == 3);
+///
+/// // Debugging shows a label for each set, counting up from the first element.
+/// assert!(format!("{small:?}") == "['a' => 0, 'b' => 1, 'c' => 0, 'd' => 2]");
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+/// [`shrink_to_fit`]: struct.SmallPartitionVec.html#method.shrink_to_fit
+/// [`into_partition_vec`]: struct.SmallPartitionVec.html#method.into_partition_vec
+pub struct SmallPartitionVec<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+/// Where the elements of a `SmallPartitionVec<T, N>` are stored.
+enum Storage<T, const N: usize> {
+    Inline(InlineParts<T, N>),
+    Heap(PartitionVec<T>),
+}
+
+/// The inline values and metadata of a `SmallPartitionVec<T, N>`.
+struct InlineParts<T, const N: usize> {
+    /// The first `len` values are initialized.
+    data: [MaybeUninit<T>; N],
+    /// The metadata of the first `len` values, the rest is unused.
+    meta: [Metadata; N],
+    len: usize,
+}
+
+impl<T, const N: usize> InlineParts<T, N> {
+    fn new() -> Self {
+        Self {
+            data: std::array::from_fn(|_| MaybeUninit::uninit()),
+            meta: std::array::from_fn(Metadata::new),
+            len: 0,
+        }
+    }
+
+    fn data(&self) -> &[T] {
+        // The first `len` values are initialized.
+        unsafe { slice::from_raw_parts(self.data.as_ptr().cast::<T>(), self.len) }
+    }
+
+    fn data_mut(&mut self) -> &mut [T] {
+        // The first `len` values are initialized.
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr().cast::<T>(), self.len) }
+    }
+
+    /// Adds `value` as a singleton or returns it if there is no room left.
+    fn push(&mut self, value: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(value);
+        }
+
+        self.data[self.len].write(value);
+        self.meta[self.len] = Metadata::new(self.len);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let last_index = self.len.checked_sub(1)?;
+        make_singleton_in(&self.meta[..self.len], last_index);
+
+        self.len = last_index;
+        // The value was initialized and is no longer counted by `len` so it is not read again.
+        unsafe { Some(self.data[last_index].assume_init_read()) }
+    }
+
+    /// Inserts `value` at `index` as a singleton or returns it if there is no room left.
+    fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        assert!(
+            index <= self.len,
+            "The index {} is out of bounds for a length of {}.",
+            index,
+            self.len
+        );
+        if self.len == N {
+            return Err(value);
+        }
+
+        shift_up_in(&self.meta[..self.len], index);
+        self.data[self.len].write(value);
+        self.data[index..=self.len].rotate_right(1);
+        self.meta[index..=self.len].rotate_right(1);
+        self.meta[index] = Metadata::new(index);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        assert!(
+            index < self.len,
+            "The index {} is out of bounds for a length of {}.",
+            index,
+            self.len
+        );
+
+        make_singleton_in(&self.meta[..self.len], index);
+        shift_down_in(&self.meta[..self.len], index);
+        self.data[index..self.len].rotate_left(1);
+        self.meta[index..self.len].rotate_left(1);
+
+        self.len -= 1;
+        // The value was initialized and is no longer counted by `len` so it is not read again.
+        unsafe { self.data[self.len].assume_init_read() }
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len {
+            return;
+        }
+        truncate_in(&self.meta[..self.len], new_len);
+
+        let old_len = self.len;
+        self.len = new_len;
+        // The values after `new_len` were initialized and are no longer counted by `len`.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.data.as_mut_ptr().add(new_len).cast::<T>(),
+                old_len - new_len,
+            ));
+        }
+    }
+
+    /// Returns the values, which can be changed, together with the metadata.
+    fn parts_mut(&mut self) -> (&mut [T], &[Metadata]) {
+        // The first `len` values are initialized.
+        let data =
+            unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr().cast::<T>(), self.len) };
+
+        (data, &self.meta[..self.len])
+    }
+
+    /// Moves the values and metadata into `Vec`s.
+    fn into_vecs(mut self) -> (Vec<T>, Vec<Metadata>) {
+        let len = self.len;
+        // The values are moved out so `drop` must not drop them again.
+        self.len = 0;
+
+        let data = self.data[..len]
+            .iter()
+            .map(|value| unsafe { value.assume_init_read() })
+            .collect();
+        let meta = self.meta[..len].to_vec();
+
+        (data, meta)
+    }
+
+    /// Moves the values and metadata of `Vec`s with at most `N` elements inline.
+    fn from_vecs(data: Vec<T>, meta: Vec<Metadata>) -> Self {
+        let mut parts = Self::new();
+
+        for (index, value) in data.into_iter().enumerate() {
+            parts.data[index].write(value);
+            parts.len += 1;
+        }
+        for (slot, meta) in parts.meta.iter_mut().zip(meta) {
+            *slot = meta;
+        }
+
+        parts
+    }
+}
+
+impl<T, const N: usize> Clone for InlineParts<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut parts = Self::new();
+
+        for (slot, value) in parts.data.iter_mut().zip(self.data()) {
+            slot.write(value.clone());
+            parts.len += 1;
+        }
+        parts.meta.clone_from(&self.meta);
+
+        parts
+    }
+}
+
+impl<T, const N: usize> Drop for InlineParts<T, N> {
+    fn drop(&mut self) {
+        // The first `len` values are initialized and are not used after this.
+        unsafe { ptr::drop_in_place(self.data_mut()) }
+    }
+}
+
+impl<T, const N: usize> SmallPartitionVec<T, N> {
+    /// Constructs a new, empty `SmallPartitionVec<T, N>` that stores its elements inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let small = partitions::SmallPartitionVec::<(), 8>::new();
+    ///
+    /// assert!(small.is_empty());
+    /// assert!(small.is_inline());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::Inline(InlineParts::new()),
+        }
+    }
+
+    /// Constructs a new, empty `SmallPartitionVec<T, N>` with room for `capacity` elements.
+    ///
+    /// The elements are stored on the heap if `capacity` is more than `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use partitions::SmallPartitionVec;
+    ///
+    /// assert!(SmallPartitionVec::<(), 4>::with_capacity(4).is_inline());
+    /// assert!(!SmallPartitionVec::<(), 4>::with_capacity(5).is_inline());
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > N {
+            Self {
+                storage: Storage::Heap(PartitionVec::with_capacity(capacity)),
+            }
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Returns `true` if the elements are stored inline instead of on the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small = partitions::SmallPartitionVec::<usize, 2>::new();
+    /// small.extend(0..2);
+    /// assert!(small.is_inline());
+    ///
+    /// small.push(2);
+    /// assert!(!small.is_inline());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.storage, Storage::Inline(_))
+    }
+
+    /// Returns the number of elements the `SmallPartitionVec<T, N>` can hold without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let small = partitions::SmallPartitionVec::<(), 3>::new();
+    ///
+    /// assert!(small.capacity() == 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        match self.storage {
+            Storage::Inline(_) => N,
+            Storage::Heap(ref partition_vec) => partition_vec.capacity(),
+        }
+    }
+
+    /// Adds `value` to the end as a singleton.
+    ///
+    /// The elements are moved to the heap when there are already `N` elements inline.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small = partitions::SmallPartitionVec::<char, 1>::new();
+    /// small.push('a');
+    /// small.push('b');
+    ///
+    /// assert!(small[..] == ['a', 'b']);
+    /// assert!(small.is_singleton(1));
+    /// ```
+    pub fn push(&mut self, value: T) {
+        let value = match self.storage {
+            Storage::Inline(ref mut parts) => match parts.push(value) {
+                Ok(()) => return,
+                Err(value) => value,
+            },
+            Storage::Heap(ref mut partition_vec) => return partition_vec.push(value),
+        };
+
+        self.spill().push(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if it is empty.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set
+    /// that the last element belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..3).collect();
+    /// small.union(1, 2);
+    ///
+    /// assert!(small.pop() == Some(2));
+    /// assert!(small.is_singleton(1));
+    /// assert!(small.len() == 2);
+    /// ```
+    pub fn pop(&mut self) -> Option<T> {
+        match self.storage {
+            Storage::Inline(ref mut parts) => parts.pop(),
+            Storage::Heap(ref mut partition_vec) => partition_vec.pop(),
+        }
+    }
+
+    /// Inserts `value` at `index` as a singleton, shifting all elements after it to the right.
+    ///
+    /// The elements are moved to the heap when there are already `N` elements inline.
+    /// This will take `O(n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is greater than the length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = "abc".chars().collect();
+    /// small.union(0, 2);
+    /// small.insert(1, 'z');
+    ///
+    /// assert!(small[..] == ['a', 'z', 'b', 'c']);
+    /// assert!(small.same_set(0, 3));
+    /// assert!(small.is_singleton(1));
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        let value = match self.storage {
+            Storage::Inline(ref mut parts) => match parts.insert(index, value) {
+                Ok(()) => return,
+                Err(value) => value,
+            },
+            Storage::Heap(ref mut partition_vec) => return partition_vec.insert(index, value),
+        };
+
+        self.spill().insert(index, value);
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it to the left.
+    ///
+    /// This will take `O(n + m)` time where `m` is the size of the set that `index` belongs to.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = "abcd".chars().collect();
+    /// small.union(1, 3);
+    ///
+    /// assert!(small.remove(0) == 'a');
+    /// assert!(small[..] == ['b', 'c', 'd']);
+    /// assert!(small.same_set(0, 2));
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        match self.storage {
+            Storage::Inline(ref mut parts) => parts.remove(index),
+            Storage::Heap(ref mut partition_vec) => partition_vec.remove(index),
+        }
+    }
+
+    /// Keeps the first `new_len` elements with their sets and drops the rest.
+    ///
+    /// This has no effect if `new_len` is at least the length and it does not move the
+    /// elements back inline, use [`shrink_to_fit`] for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(0, 3);
+    /// small.union(1, 3);
+    /// small.truncate(2);
+    ///
+    /// assert!(small.len() == 2);
+    /// assert!(small.same_set(0, 1));
+    /// ```
+    ///
+    /// [`shrink_to_fit`]: #method.shrink_to_fit
+    pub fn truncate(&mut self, new_len: usize) {
+        match self.storage {
+            Storage::Inline(ref mut parts) => parts.truncate(new_len),
+            Storage::Heap(ref mut partition_vec) => partition_vec.truncate(new_len),
+        }
+    }
+
+    /// Removes all elements, the elements are stored inline afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 2> = (0..5).collect();
+    /// small.clear();
+    ///
+    /// assert!(small.is_empty());
+    /// assert!(small.is_inline());
+    /// ```
+    pub fn clear(&mut self) {
+        self.storage = Storage::Inline(InlineParts::new());
+    }
+
+    /// Moves the elements back inline if there are at most `N` of them
+    /// and otherwise shrinks the heap allocation as much as possible.
+    ///
+    /// The sets are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 3> = (0..4).collect();
+    /// small.union(0, 1);
+    /// small.pop();
+    /// assert!(!small.is_inline());
+    ///
+    /// small.shrink_to_fit();
+    /// assert!(small.is_inline());
+    /// assert!(small.same_set(0, 1));
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        match self.storage {
+            Storage::Inline(_) => {}
+            Storage::Heap(ref mut partition_vec) if partition_vec.len() > N => {
+                partition_vec.shrink_to_fit();
+            }
+            Storage::Heap(_) => {
+                let storage =
+                    std::mem::replace(&mut self.storage, Storage::Inline(InlineParts::new()));
+                if let Storage::Heap(partition_vec) = storage {
+                    let (data, meta) = partition_vec.into_parts();
+                    self.storage = Storage::Inline(InlineParts::from_vecs(data, meta));
+                }
+            }
+        }
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index`.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 2);
+    /// small.union(2, 3);
+    ///
+    /// assert!(small.len_of_set(1) == 3);
+    /// ```
+    pub fn union(&mut self, first_index: usize, second_index: usize) {
+        union_roots_in(self.meta(), first_index, second_index);
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 3);
+    ///
+    /// assert!(small.same_set(3, 1));
+    /// assert!(!small.same_set(0, 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        find_in(self.meta(), first_index) == find_in(self.meta(), second_index)
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in different sets.
+    ///
+    /// This method will be executed in `O(α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 3);
+    ///
+    /// assert!(small.other_sets(0, 1));
+    /// assert!(!small.other_sets(3, 1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn other_sets(&self, first_index: usize, second_index: usize) -> bool {
+        !self.same_set(first_index, second_index)
+    }
+
+    /// Removes the element at `index` from its set and gives it its own set.
+    ///
+    /// This method will be executed in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 2);
+    /// small.union(2, 3);
+    /// small.make_singleton(2);
+    ///
+    /// assert!(small.is_singleton(2));
+    /// assert!(small.same_set(1, 3));
+    /// ```
+    pub fn make_singleton(&mut self, index: usize) {
+        make_singleton_in(self.meta(), index);
+    }
+
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// This will be done in `O(1)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 3);
+    ///
+    /// assert!(small.is_singleton(0));
+    /// assert!(!small.is_singleton(1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: usize) -> bool {
+        self.meta()[index].link() == index
+    }
+
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// This will be done in `O(m)` time where `m` is the size of the set of `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(0, 3);
+    ///
+    /// assert!(small.len_of_set(3) == 2);
+    /// assert!(small.len_of_set(1) == 1);
+    /// ```
+    #[must_use]
+    pub fn len_of_set(&self, index: usize) -> usize {
+        self.set(index).count()
+    }
+
+    /// Returns the amount of sets.
+    ///
+    /// This method will be executed in `O(n α(n))` time where `α` is the inverse
+    /// Ackermann function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(0, 3);
+    ///
+    /// assert!(small.amount_of_sets() == 3);
+    /// ```
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        // Every set has exactly one root, counting them needs no allocation.
+        let meta = self.meta();
+
+        (0..meta.len())
+            .filter(|&index| find_in(meta, index) == index)
+            .count()
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &value)`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = "abcd".chars().collect();
+    /// small.union(0, 2);
+    ///
+    /// let mut set: Vec<_> = small.set(2).collect();
+    /// set.sort_unstable();
+    /// assert!(set == [(0, &'a'), (2, &'c')]);
+    /// ```
+    #[must_use]
+    pub fn set(&self, index: usize) -> Set<'_, T> {
+        let (data, meta) = self.parts();
+
+        Set::new(data, meta, find_final_in(meta, index))
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to that allows
+    /// modifying the values.
+    ///
+    /// The iterator returned yields pairs `(i, &mut value)`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 2);
+    ///
+    /// for (_, value) in small.set_mut(1) {
+    ///     *value *= 10;
+    /// }
+    /// assert!(small[..] == [0, 10, 20, 3]);
+    /// ```
+    pub fn set_mut(&mut self, index: usize) -> SetMut<'_, T> {
+        let (data, meta) = self.parts_mut();
+        let root = find_final_in(meta, index);
+
+        SetMut::new(data, meta, root)
+    }
+
+    /// Returns an iterator over all sets, each set is returned once as a [`Set`] iterator.
+    ///
+    /// The sets are returned in order by their first member and this allocates room to
+    /// remember which sets were returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = "abcd".chars().collect();
+    /// small.union(1, 3);
+    ///
+    /// let lens: Vec<_> = small.all_sets().map(Iterator::count).collect();
+    /// assert!(lens == [1, 2, 1]);
+    /// ```
+    ///
+    /// [`Set`]: ../partition_vec/struct.Set.html
+    #[must_use]
+    pub fn all_sets(&self) -> AllSets<'_, T> {
+        let (data, meta) = self.parts();
+
+        AllSets::new(data, meta)
+    }
+
+    /// Returns an iterator over all sets that allows modifying the values, each set is
+    /// returned once as a [`SetMut`] iterator.
+    ///
+    /// The sets are returned in order by their first member and this allocates room to
+    /// remember which sets were returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(1, 3);
+    ///
+    /// for (label, set) in small.all_sets_mut().enumerate() {
+    ///     for (_, value) in set {
+    ///         *value = label;
+    ///     }
+    /// }
+    /// assert!(small[..] == [0, 1, 2, 1]);
+    /// ```
+    ///
+    /// [`SetMut`]: ../partition_vec/struct.SetMut.html
+    pub fn all_sets_mut(&mut self) -> AllSetsMut<'_, T> {
+        let (data, meta) = self.parts_mut();
+
+        AllSetsMut::new(data, meta)
+    }
+
+    /// Converts the `SmallPartitionVec<T, N>` into a `PartitionVec<T>` with the same sets.
+    ///
+    /// This allocates if the elements are stored inline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut small: partitions::SmallPartitionVec<_, 4> = (0..4).collect();
+    /// small.union(0, 3);
+    ///
+    /// let partition_vec = small.into_partition_vec();
+    /// assert!(partition_vec.same_set(0, 3));
+    /// assert!(partition_vec.amount_of_sets() == 3);
+    /// ```
+    #[must_use]
+    pub fn into_partition_vec(self) -> PartitionVec<T> {
+        match self.storage {
+            Storage::Inline(parts) => {
+                let (data, meta) = parts.into_vecs();
+                PartitionVec::from_parts(data, meta)
+            }
+            Storage::Heap(partition_vec) => partition_vec,
+        }
+    }
+
+    /// Moves the elements to the heap and returns the `PartitionVec<T>` holding them.
+    fn spill(&mut self) -> &mut PartitionVec<T> {
+        let storage = std::mem::replace(&mut self.storage, Storage::Heap(PartitionVec::new()));
+        if let Storage::Inline(parts) = storage {
+            let (mut data, mut meta) = parts.into_vecs();
+            data.reserve(N);
+            meta.reserve(N);
+            self.storage = Storage::Heap(PartitionVec::from_parts(data, meta));
+        }
+
+        match self.storage {
+            Storage::Heap(ref mut partition_vec) => partition_vec,
+            Storage::Inline(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the values together with the metadata.
+    fn parts(&self) -> (&[T], &[Metadata]) {
+        match self.storage {
+            Storage::Inline(ref parts) => (parts.data(), &parts.meta[..parts.len]),
+            Storage::Heap(ref partition_vec) => partition_vec.parts(),
+        }
+    }
+
+    /// Returns the values, which can be changed, together with the metadata.
+    fn parts_mut(&mut self) -> (&mut [T], &[Metadata]) {
+        match self.storage {
+            Storage::Inline(ref mut parts) => parts.parts_mut(),
+            Storage::Heap(ref mut partition_vec) => partition_vec.parts_mut(),
+        }
+    }
+
+    /// Returns the metadata of the elements.
+    fn meta(&self) -> &[Metadata] {
+        self.parts().1
+    }
+}
+
+impl<T, const N: usize> Default for SmallPartitionVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Clone for SmallPartitionVec<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let storage = match self.storage {
+            Storage::Inline(ref parts) => Storage::Inline(parts.clone()),
+            Storage::Heap(ref partition_vec) => Storage::Heap(partition_vec.clone()),
+        };
+
+        Self { storage }
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for SmallPartitionVec<T, N>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.storage {
+            Storage::Inline(ref parts) => fmt_labeled(
+                parts.data(),
+                &parts.meta[..parts.len],
+                &mut [None; N],
+                formatter,
+            ),
+            Storage::Heap(ref partition_vec) => partition_vec.fmt(formatter),
+        }
+    }
+}
+
+impl<T, const N: usize> From<PartitionVec<T>> for SmallPartitionVec<T, N> {
+    /// Keeps the sets and moves the elements inline if there are at most `N` of them.
+    fn from(partition_vec: PartitionVec<T>) -> Self {
+        let mut small = Self {
+            storage: Storage::Heap(partition_vec),
+        };
+        small.shrink_to_fit();
+
+        small
+    }
+}
+
+impl<T, const N: usize> From<SmallPartitionVec<T, N>> for PartitionVec<T> {
+    fn from(small: SmallPartitionVec<T, N>) -> Self {
+        small.into_partition_vec()
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallPartitionVec<T, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut small = Self::new();
+        small.extend(iter);
+
+        small
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallPartitionVec<T, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, const N: usize> ops::Deref for SmallPartitionVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.parts().0
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for SmallPartitionVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self.storage {
+            Storage::Inline(ref mut parts) => parts.data_mut(),
+            Storage::Heap(ref mut partition_vec) => partition_vec,
+        }
+    }
+}
+
+impl<T, const N: usize> UnionFind for SmallPartitionVec<T, N> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.meta().len()
+    }
+
+    #[inline]
+    fn union(&mut self, first_index: usize, second_index: usize) -> bool {
+        union_roots_in(self.meta(), first_index, second_index).is_some()
+    }
+
+    #[inline]
+    fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        SmallPartitionVec::same_set(self, first_index, second_index)
+    }
+
+    #[inline]
+    fn make_singleton(&mut self, index: usize) {
+        SmallPartitionVec::make_singleton(self, index);
+    }
+
+    #[inline]
+    fn amount_of_sets(&self) -> usize {
+        SmallPartitionVec::amount_of_sets(self)
+    }
+}
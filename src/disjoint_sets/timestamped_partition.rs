@@ -0,0 +1,269 @@
+//! A [disjoint-sets/union-find] implementation that remembers when elements became connected.
+//!
+//! See [`TimestampedPartition`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`TimestampedPartition`]: struct.TimestampedPartition.html
+
+/// A [disjoint-sets/union-find] implementation where every union is numbered.
+///
+/// Every call to [`union`] is a step, the first call is step `1`, the second call is step `2`
+/// and so on, also when the elements were already in the same set.
+/// The [`when_connected`] method answers after which step two elements were first in
+/// the same set.
+///
+/// This is done by storing the step at which every tree edge was made.
+/// The steps increase towards the root of a tree so the step at which two elements became
+/// connected is the largest step on the paths from both elements to the element where
+/// these paths meet.
+/// No path compression is done because it would remove these edges, the trees are only kept
+/// low by joining by rank.
+/// This makes all methods take `O(log n)` time instead of the `O(α(n))` time of a
+/// [`PartitionVec<T>`].
+///
+/// Unlike a `PartitionVec<T>` this does not store values and elements can not leave their set.
+///
+/// # Examples
+///
+/// ```
+/// use partitions::TimestampedPartition;
+///
+/// let mut partition = TimestampedPartition::new(5);
+/// partition.union(0, 1);
+/// partition.union(2, 3);
+/// partition.union(1, 2);
+///
+/// assert!(partition.when_connected(0, 1) == Some(1));
+/// assert!(partition.when_connected(0, 3) == Some(3));
+/// assert!(partition.when_connected(0, 4) == None);
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`union`]: struct.TimestampedPartition.html#method.union
+/// [`when_connected`]: struct.TimestampedPartition.html#method.when_connected
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimestampedPartition {
+    /// The parent of every element, a root is its own parent.
+    parent: Vec<usize>,
+    /// The rank of every element, only used for roots.
+    rank: Vec<usize>,
+    /// The step at which every element was given its parent, unused for roots.
+    step: Vec<usize>,
+    /// The amount of calls to `union` so far.
+    steps: usize,
+}
+
+impl TimestampedPartition {
+    /// Constructs a new `TimestampedPartition` with `len` elements that each have their own set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let partition = partitions::TimestampedPartition::new(3);
+    ///
+    /// assert!(partition.len() == 3);
+    /// assert!(partition.steps() == 0);
+    /// assert!(!partition.same_set(0, 2));
+    /// ```
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            step: vec![0; len],
+            steps: 0,
+        }
+    }
+
+    /// Returns the amount of elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let partition = partitions::TimestampedPartition::new(4);
+    ///
+    /// assert!(partition.len() == 4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(partitions::TimestampedPartition::new(0).is_empty());
+    /// assert!(!partitions::TimestampedPartition::new(1).is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the amount of calls to [`union`] so far, which is the number of the last step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition = partitions::TimestampedPartition::new(3);
+    /// partition.union(0, 1);
+    /// partition.union(1, 0);
+    ///
+    /// assert!(partition.steps() == 2);
+    /// ```
+    ///
+    /// [`union`]: struct.TimestampedPartition.html#method.union
+    #[inline]
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index` as the next step.
+    ///
+    /// Returns `true` if two different sets were joined and `false` if both indices were
+    /// already in the same set, the step is counted in both cases.
+    ///
+    /// This method will be executed in `O(log n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition = partitions::TimestampedPartition::new(3);
+    ///
+    /// assert!(partition.union(0, 1));
+    /// assert!(!partition.union(1, 0));
+    /// assert!(partition.union(1, 2));
+    /// assert!(partition.when_connected(0, 2) == Some(3));
+    /// ```
+    pub fn union(&mut self, first_index: usize, second_index: usize) -> bool {
+        self.steps += 1;
+
+        let i = self.find(first_index);
+        let j = self.find(second_index);
+
+        if i == j {
+            return false;
+        }
+
+        let (child, root) = if self.rank[i] < self.rank[j] {
+            (i, j)
+        } else {
+            (j, i)
+        };
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+        self.parent[child] = root;
+        self.step[child] = self.steps;
+
+        true
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// This method will be executed in `O(log n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition = partitions::TimestampedPartition::new(3);
+    /// partition.union(0, 2);
+    ///
+    /// assert!(partition.same_set(2, 0));
+    /// assert!(!partition.same_set(1, 0));
+    /// ```
+    #[must_use]
+    pub fn same_set(&self, first_index: usize, second_index: usize) -> bool {
+        self.find(first_index) == self.find(second_index)
+    }
+
+    /// Returns the step after which `first_index` and `second_index` were first in the same set,
+    /// or `None` if they are not in the same set.
+    ///
+    /// An element is connected to itself at step `0`, before any union.
+    ///
+    /// This method will be executed in `O(log n)` time.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut partition = partitions::TimestampedPartition::new(4);
+    /// partition.union(0, 1);
+    /// partition.union(0, 1);
+    /// partition.union(2, 1);
+    ///
+    /// assert!(partition.when_connected(3, 3) == Some(0));
+    /// assert!(partition.when_connected(1, 0) == Some(1));
+    /// assert!(partition.when_connected(0, 2) == Some(3));
+    /// assert!(partition.when_connected(0, 3) == None);
+    /// ```
+    #[must_use]
+    pub fn when_connected(&self, first_index: usize, second_index: usize) -> Option<usize> {
+        assert!(
+            first_index < self.len() && second_index < self.len(),
+            "The index is out of bounds."
+        );
+        let (mut i, mut j) = (first_index, second_index);
+
+        let mut latest = 0;
+        // We always move up the edge that was made first, this edge can not be above
+        // the element where both paths meet.
+        while i != j {
+            let step_i = if self.parent[i] == i {
+                None
+            } else {
+                Some(self.step[i])
+            };
+            let step_j = if self.parent[j] == j {
+                None
+            } else {
+                Some(self.step[j])
+            };
+
+            match (step_i, step_j) {
+                (None, None) => return None,
+                (Some(step_i), Some(step_j)) if step_i < step_j => {
+                    latest = step_i;
+                    i = self.parent[i];
+                }
+                (Some(step_i), None) => {
+                    latest = step_i;
+                    i = self.parent[i];
+                }
+                (_, Some(step_j)) => {
+                    latest = step_j;
+                    j = self.parent[j];
+                }
+            }
+        }
+
+        Some(latest)
+    }
+
+    /// Returns the root of the tree of `index`.
+    fn find(&self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            index = self.parent[index];
+        }
+
+        index
+    }
+}
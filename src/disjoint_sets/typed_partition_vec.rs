@@ -0,0 +1,490 @@
+//! A [disjoint-sets/union-find] implementation of a vector indexed by a typed index.
+//!
+//! See [`TypedPartitionVec<T, I>`] and [`Idx`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`TypedPartitionVec<T, I>`]: struct.TypedPartitionVec.html
+//! [`Idx`]: trait.Idx.html
+
+use {
+    crate::disjoint_sets::partition_vec::{PartitionVec, Set},
+    std::{fmt, iter::FusedIterator, marker::PhantomData, ops},
+};
+
+/// A type that can be used as the index of a [`TypedPartitionVec<T, I>`].
+///
+/// Converting an index to a `usize` and back should give the same index.
+/// The [`impl_idx!`] macro implements this trait for newtypes around a `usize`.
+///
+/// [`TypedPartitionVec<T, I>`]: struct.TypedPartitionVec.html
+/// [`impl_idx!`]: ../../macro.impl_idx.html
+pub trait Idx: Copy {
+    /// Creates the index for the element at position `index`.
+    fn from_usize(index: usize) -> Self;
+
+    /// Returns the position of the element of this index.
+    fn into_usize(self) -> usize;
+}
+
+impl Idx for usize {
+    #[inline]
+    fn from_usize(index: usize) -> Self {
+        index
+    }
+
+    #[inline]
+    fn into_usize(self) -> usize {
+        self
+    }
+}
+
+/// Implements [`Idx`] for newtypes around a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate partitions;
+/// #
+/// # fn main() {
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct NodeId(usize);
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct EdgeId(usize);
+///
+/// impl_idx!(NodeId, EdgeId);
+///
+/// use partitions::typed_partition_vec::Idx;
+///
+/// assert!(NodeId::from_usize(3) == NodeId(3));
+/// assert!(EdgeId(5).into_usize() == 5);
+/// # }
+/// ```
+///
+/// [`Idx`]: typed_partition_vec/trait.Idx.html
+#[macro_export]
+macro_rules! impl_idx {
+    ($($name: ident),* $(,)*) => {
+        $(
+            impl $crate::typed_partition_vec::Idx for $name {
+                #[inline]
+                fn from_usize(index: usize) -> Self {
+                    $name(index)
+                }
+
+                #[inline]
+                fn into_usize(self) -> usize {
+                    self.0
+                }
+            }
+        )*
+    };
+}
+
+/// A [`PartitionVec<T>`] whose elements are identified by an index of type `I`.
+///
+/// When there are several partitions over different kinds of elements a newtype index
+/// for every kind makes sure that an index of one partition is never given to another.
+/// Every method that takes or returns an index of an element uses `I` instead of `usize`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate partitions;
+/// #
+/// # fn main() {
+/// use partitions::TypedPartitionVec;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct NodeId(usize);
+/// impl_idx!(NodeId);
+///
+/// let mut nodes = TypedPartitionVec::<&str, NodeId>::new();
+/// let a = nodes.push("a");
+/// let b = nodes.push("b");
+/// let c = nodes.push("c");
+///
+/// nodes.union(a, c);
+/// assert!(nodes.same_set(c, a));
+/// assert!(!nodes.same_set(a, b));
+/// assert!(nodes[c] == "c");
+///
+/// let mut set: Vec<_> = nodes.set(a).map(|(id, _)| id.0).collect();
+/// set.sort_unstable();
+/// assert!(set == [0, 2]);
+/// # }
+/// ```
+///
+/// Indices of another type are rejected by the compiler:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate partitions;
+/// #
+/// # fn main() {
+/// use partitions::TypedPartitionVec;
+///
+/// #[derive(Clone, Copy)]
+/// struct NodeId(usize);
+/// #[derive(Clone, Copy)]
+/// struct EdgeId(usize);
+/// impl_idx!(NodeId, EdgeId);
+///
+/// let mut nodes = TypedPartitionVec::<(), NodeId>::new();
+/// nodes.push(());
+/// nodes.push(());
+///
+/// nodes.union(EdgeId(0), EdgeId(1));
+/// # }
+/// ```
+///
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+pub struct TypedPartitionVec<T, I: Idx = usize> {
+    partition_vec: PartitionVec<T>,
+    marker: PhantomData<fn(I) -> I>,
+}
+
+impl<T, I: Idx> TypedPartitionVec<T, I> {
+    /// Constructs a new, empty `TypedPartitionVec<T, I>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let typed = partitions::TypedPartitionVec::<(), usize>::new();
+    ///
+    /// assert!(typed.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from(PartitionVec::new())
+    }
+
+    /// Constructs a new, empty `TypedPartitionVec<T, I>` with room for `capacity` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let typed = partitions::TypedPartitionVec::<(), usize>::with_capacity(10);
+    ///
+    /// assert!(typed.len() == 0);
+    /// assert!(typed.as_partition_vec().capacity() >= 10);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(PartitionVec::with_capacity(capacity))
+    }
+
+    /// Returns the amount of elements.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.partition_vec.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.partition_vec.is_empty()
+    }
+
+    /// Adds `value` to the end as a singleton and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements overflows a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut typed = partitions::TypedPartitionVec::<char, usize>::new();
+    ///
+    /// assert!(typed.push('a') == 0);
+    /// assert!(typed.push('b') == 1);
+    /// ```
+    pub fn push(&mut self, value: T) -> I {
+        let index = self.partition_vec.len();
+        self.partition_vec.push(value);
+
+        I::from_usize(index)
+    }
+
+    /// Returns a reference to the value at `index` or `None` if it is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut typed = partitions::TypedPartitionVec::<char, usize>::new();
+    /// let index = typed.push('a');
+    ///
+    /// assert!(typed.get(index) == Some(&'a'));
+    /// assert!(typed.get(1) == None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: I) -> Option<&T> {
+        self.partition_vec.get(index.into_usize())
+    }
+
+    /// Returns a mutable reference to the value at `index` or `None` if it is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+        self.partition_vec.get_mut(index.into_usize())
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index`.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut typed: partitions::TypedPartitionVec<(), usize> = vec![(); 3].into_iter().collect();
+    /// typed.union(0, 2);
+    ///
+    /// assert!(typed.same_set(2, 0));
+    /// ```
+    #[inline]
+    pub fn union(&mut self, first_index: I, second_index: I) {
+        self.partition_vec
+            .union(first_index.into_usize(), second_index.into_usize());
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn same_set(&self, first_index: I, second_index: I) -> bool {
+        self.partition_vec
+            .same_set(first_index.into_usize(), second_index.into_usize())
+    }
+
+    /// Returns `true` if `first_index` and `second_index` are in different sets.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn other_sets(&self, first_index: I, second_index: I) -> bool {
+        self.partition_vec
+            .other_sets(first_index.into_usize(), second_index.into_usize())
+    }
+
+    /// Removes the element at `index` from its set and gives it its own set.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    pub fn make_singleton(&mut self, index: I) {
+        self.partition_vec.make_singleton(index.into_usize());
+    }
+
+    /// Returns `true` if `index` is the only element of its set.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn is_singleton(&self, index: I) -> bool {
+        self.partition_vec.is_singleton(index.into_usize())
+    }
+
+    /// Returns the amount of elements in the set that `index` belongs to.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn len_of_set(&self, index: I) -> usize {
+        self.partition_vec.len_of_set(index.into_usize())
+    }
+
+    /// Returns the amount of sets.
+    #[inline]
+    #[must_use]
+    pub fn amount_of_sets(&self) -> usize {
+        self.partition_vec.amount_of_sets()
+    }
+
+    /// Returns an iterator over the elements of the set that `index` belongs to.
+    ///
+    /// The iterator returned yields pairs `(i, &value)`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn set(&self, index: I) -> TypedSet<'_, T, I> {
+        TypedSet {
+            set: self.partition_vec.set(index.into_usize()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over all elements together with their index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let typed: partitions::TypedPartitionVec<char, usize> = "ab".chars().collect();
+    ///
+    /// assert!(typed.iter().collect::<Vec<_>>() == [(0, &'a'), (1, &'b')]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (I, &T)> {
+        self.partition_vec
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (I::from_usize(index), value))
+    }
+
+    /// Returns an iterator over all elements together with their index that allows
+    /// modifying the values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (I, &mut T)> {
+        self.partition_vec
+            .iter_mut()
+            .enumerate()
+            .map(|(index, value)| (I::from_usize(index), value))
+    }
+
+    /// Returns the `PartitionVec<T>` with the values and sets.
+    #[inline]
+    #[must_use]
+    pub fn as_partition_vec(&self) -> &PartitionVec<T> {
+        &self.partition_vec
+    }
+
+    /// Converts into the `PartitionVec<T>` with the values and sets.
+    #[inline]
+    #[must_use]
+    pub fn into_partition_vec(self) -> PartitionVec<T> {
+        self.partition_vec
+    }
+}
+
+impl<T, I: Idx> Default for TypedPartitionVec<T, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, I: Idx> Clone for TypedPartitionVec<T, I>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from(self.partition_vec.clone())
+    }
+}
+
+impl<T, I: Idx> fmt::Debug for TypedPartitionVec<T, I>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.partition_vec, formatter)
+    }
+}
+
+impl<T, I: Idx> PartialEq for TypedPartitionVec<T, I>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.partition_vec == other.partition_vec
+    }
+}
+
+impl<T, I: Idx> Eq for TypedPartitionVec<T, I> where T: Eq {}
+
+impl<T, I: Idx> From<PartitionVec<T>> for TypedPartitionVec<T, I> {
+    fn from(partition_vec: PartitionVec<T>) -> Self {
+        Self {
+            partition_vec,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I: Idx> From<TypedPartitionVec<T, I>> for PartitionVec<T> {
+    fn from(typed: TypedPartitionVec<T, I>) -> Self {
+        typed.partition_vec
+    }
+}
+
+impl<T, I: Idx> std::iter::FromIterator<T> for TypedPartitionVec<T, I> {
+    fn from_iter<J>(iter: J) -> Self
+    where
+        J: IntoIterator<Item = T>,
+    {
+        Self::from(iter.into_iter().collect::<PartitionVec<T>>())
+    }
+}
+
+impl<T, I: Idx> Extend<T> for TypedPartitionVec<T, I> {
+    fn extend<J>(&mut self, iter: J)
+    where
+        J: IntoIterator<Item = T>,
+    {
+        self.partition_vec.extend(iter);
+    }
+}
+
+impl<T, I: Idx> ops::Index<I> for TypedPartitionVec<T, I> {
+    type Output = T;
+
+    fn index(&self, index: I) -> &T {
+        &self.partition_vec[index.into_usize()]
+    }
+}
+
+impl<T, I: Idx> ops::IndexMut<I> for TypedPartitionVec<T, I> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.partition_vec[index.into_usize()]
+    }
+}
+
+/// An iterator over a set in a `TypedPartitionVec<T, I>`.
+///
+/// This struct is created by the [`set`] method on [`TypedPartitionVec<T, I>`].
+/// See its documentation for more.
+///
+/// [`set`]: struct.TypedPartitionVec.html#method.set
+/// [`TypedPartitionVec<T, I>`]: struct.TypedPartitionVec.html
+#[derive(Debug)]
+pub struct TypedSet<'a, T: 'a, I> {
+    set: Set<'a, T>,
+    marker: PhantomData<fn(I) -> I>,
+}
+
+impl<T, I> Clone for TypedSet<'_, T, I> {
+    fn clone(&self) -> Self {
+        Self {
+            set: self.set.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, I: Idx> Iterator for TypedSet<'a, T, I> {
+    type Item = (I, &'a T);
+
+    fn next(&mut self) -> Option<(I, &'a T)> {
+        let (index, value) = self.set.next()?;
+
+        Some((I::from_usize(index), value))
+    }
+}
+
+impl<T, I: Idx> FusedIterator for TypedSet<'_, T, I> {}
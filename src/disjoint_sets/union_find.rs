@@ -0,0 +1,86 @@
+//! A trait abstracting over the [disjoint-sets/union-find] implementations of this crate.
+//!
+//! See [`UnionFind`] for more information.
+//!
+//! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+//! [`UnionFind`]: trait.UnionFind.html
+
+/// The operations shared by every [disjoint-sets/union-find] implementation of this crate.
+///
+/// This allows algorithms like Kruskal's algorithm to be written once and be used with
+/// a [`PartitionVec<T>`] as well as a [`PartitionArray<T, N>`].
+/// Elements are identified by their index which should be below the `len` of the structure.
+///
+/// The trait has no generic methods so it is object safe and can be used as
+/// `&mut dyn UnionFind` when monomorphization is not wanted.
+///
+/// # Examples
+///
+/// The example uses a `PartitionVec<T>` so it only runs with the `std` feature.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use partitions::{PartitionArray, PartitionVec, UnionFind};
+///
+/// // Returns the total weight of a minimum spanning forest.
+/// fn kruskal<U: UnionFind>(sets: &mut U, edges: &mut [(usize, usize, u32)]) -> u32 {
+///     edges.sort_unstable_by_key(|&(_, _, weight)| weight);
+///
+///     let mut total = 0;
+///     for &(first, second, weight) in edges.iter() {
+///         if sets.union(first, second) {
+///             total += weight;
+///         }
+///     }
+///
+///     total
+/// }
+///
+/// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 7)];
+///
+/// let mut partition_vec = PartitionVec::from(vec![(); 4]);
+/// assert!(kruskal(&mut partition_vec, &mut edges.clone()) == 10);
+///
+/// let mut partition_array = PartitionArray::new([(); 4]);
+/// assert!(kruskal(&mut partition_array, &mut edges.clone()) == 10);
+/// ```
+///
+/// [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
+/// [`PartitionVec<T>`]: ../partition_vec/struct.PartitionVec.html
+/// [`PartitionArray<T, N>`]: ../partition_array/struct.PartitionArray.html
+pub trait UnionFind {
+    /// Returns the amount of elements.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Joins the sets of the `first_index` and the `second_index`.
+    ///
+    /// Returns `true` if two different sets were joined and `false` if both
+    /// indices were already in the same set.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    fn union(&mut self, first_index: usize, second_index: usize) -> bool;
+
+    /// Returns `true` if `first_index` and `second_index` are in the same set.
+    ///
+    /// # Panics
+    ///
+    /// If `first_index` or `second_index` is out of bounds.
+    fn same_set(&self, first_index: usize, second_index: usize) -> bool;
+
+    /// Removes the element at `index` from its set and gives it its own set.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    fn make_singleton(&mut self, index: usize);
+
+    /// Returns the amount of sets.
+    fn amount_of_sets(&self) -> usize;
+}
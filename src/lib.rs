@@ -36,68 +36,93 @@
 //! features = ["compact"]
 //! ```
 //!
+//! The [`PartitionArray<T, N>`] and the [`UnionFind`] trait do not need the standard library
+//! or an allocator.
+//! Disabling the default `std` feature makes the crate `no_std` with only these two left:
+//! ```toml
+//! [dependencies.partitions]
+//! version = "0.2"
+//! default-features = false
+//! ```
+//!
 //! [disjoint-sets/union-find]: https://en.wikipedia.org/wiki/Disjoint-set_data_structure
 //! [`PartitionVec<T>`]: partition_vec/struct.PartitionVec.html
 //! [`union`]: partition_vec/struct.PartitionVec.html#method.union
 //! [`same_set`]: partition_vec/struct.PartitionVec.html#method.same_set
 //! [`set`]: partition_vec/struct.PartitionVec.html#method.set
 //! [`make_singleton`]: partition_vec/struct.PartitionVec.html#method.make_singleton
+//! [`PartitionArray<T, N>`]: partition_array/struct.PartitionArray.html
+//! [`UnionFind`]: union_find/trait.UnionFind.html
 
 //#![warn(missing_docs)]
-#![cfg_attr(feature = "cargo-clippy", warn(clippy::pedantic))]
+#![warn(clippy::pedantic)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
 extern crate bit_vec;
+#[cfg(feature = "std")]
+extern crate core;
 
 #[cfg(feature = "rayon")]
 extern crate rayon;
 
 #[cfg(feature = "proptest")]
+#[cfg_attr(test, macro_use)]
 extern crate proptest;
 
-/// We count the amount of expresions given to this macro.
-#[doc(hidden)]
-#[macro_export]
-macro_rules! partitions_count_expr {
-    () => { 0usize };
-    ($_single: expr) => { 1usize };
-    // Even amount of expresions.
-    ($($first: expr, $_second: expr),*) => {
-        (partitions_count_expr![$($first),*] << 1usize)
-    };
-    // Odd amount of expresions.
-    ($_single: expr, $($first: expr, $_second: expr),*) => {
-        (partitions_count_expr![$($first),*] << 1usize) | 1
-    };
-}
+#[cfg(feature = "rand")]
+extern crate rand;
 
 /// A convenient macro to create a `BitVec` similar to `vec!`.
+#[cfg(feature = "std")]
 macro_rules! bit_vec {
     ($element: expr; $len: expr) => {
         bit_vec::BitVec::from_elem($len, $element)
     };
     ($($value: expr),*) => {
-        {
-            let len = partitions_count_expr![$($value),*];
-            let mut bit_vec = bit_vec::BitVec::with_capacity(len);
-
-            $(
-                bit_vec.push($value);
-            )*
-
-            bit_vec
-        }
+        [$($value),*].iter().cloned().collect::<bit_vec::BitVec>()
     };
     ($($value: expr,)*) => {
-        bit_vec![$($value),*];
+        bit_vec![$($value),*]
     };
 }
 
+#[cfg(feature = "std")]
+pub mod algorithms;
 mod disjoint_sets;
+#[cfg(feature = "std")]
 mod partition_map;
+#[cfg(all(test, feature = "std"))]
+mod test_helpers;
 
+#[cfg(feature = "std")]
+pub use disjoint_sets::offline_connectivity::{self, OfflineConnectivity};
+pub use disjoint_sets::partition_array::{self, PartitionArray};
+#[cfg(feature = "std")]
+pub use disjoint_sets::partition_grid::{self, Connectivity, PartitionGrid};
+#[cfg(feature = "std")]
+pub use disjoint_sets::partition_slab::{self, PartitionSlab};
+#[cfg(feature = "std")]
 pub use disjoint_sets::partition_vec::{self, PartitionVec};
+#[cfg(feature = "std")]
+pub use disjoint_sets::partition_vec_deque::{self, PartitionVecDeque};
+#[cfg(feature = "std")]
+pub use disjoint_sets::small_partition_vec::{self, SmallPartitionVec};
+#[cfg(feature = "std")]
+pub use disjoint_sets::timestamped_partition::{self, TimestampedPartition};
+#[cfg(feature = "std")]
+pub use disjoint_sets::typed_partition_vec::{self, Idx, TypedPartitionVec};
+pub use disjoint_sets::union_find::{self, UnionFind};
+#[cfg(feature = "std")]
 pub use partition_map::partition_btree_map::{self, PartitionBTreeMap};
+#[cfg(feature = "std")]
+pub use partition_map::partition_btree_set::{self, PartitionBTreeSet};
+#[cfg(feature = "std")]
 pub use partition_map::partition_hash_map::{self, PartitionHashMap};
+#[cfg(feature = "std")]
+pub use partition_map::partition_hash_set::{self, PartitionHashSet};
+#[cfg(feature = "std")]
+pub use partition_map::MissingKey;
 
 /// This takes an mutable reference and return a mutable reference with a different lifetime.
 ///
@@ -105,6 +130,1321 @@ pub use partition_map::partition_hash_map::{self, PartitionHashMap};
 /// comment explaining why it is necessary.
 /// The main motivation for making a function for this is that the code is not
 /// intuitive and this makes the intend clearer.
-unsafe fn extend_mut<'a, 'b, T: ?Sized>(ptr: &'a mut T) -> &'b mut T {
-    &mut *(ptr as *mut T)
+#[cfg(feature = "std")]
+unsafe fn extend_mut<'a, T: ?Sized>(ptr: &mut T) -> &'a mut T {
+    &mut *std::ptr::from_mut(ptr)
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod tests {
+    use algorithms::{
+        first_cycle_edge, is_bipartite, offline_lca, two_color, CycleDetector, TreeError,
+    };
+    use impl_idx;
+    use partition_array::PartitionArrayIndices;
+    use partition_vec::{DecodeError, Op};
+    use proptest::{collection, prelude::*};
+    use test_helpers::{apply, Operation};
+    use {
+        Connectivity, OfflineConnectivity, PartitionArray, PartitionGrid, PartitionSlab,
+        PartitionVec, PartitionVecDeque, SmallPartitionVec, TimestampedPartition,
+        TypedPartitionVec, UnionFind,
+    };
+
+    /// The length of a `PartitionVec<T>` with a list of operations on it.
+    fn operations() -> impl Strategy<Value = (usize, Vec<Operation>)> {
+        (1..64usize).prop_flat_map(|len| {
+            let operation = prop_oneof![
+                3 => (0..len, 0..len).prop_map(|(first, second)| Operation::Union(first, second)),
+                1 => (0..len).prop_map(Operation::MakeSingleton),
+            ];
+            (Just(len), collection::vec(operation, 0..2 * len))
+        })
+    }
+
+    /// Applies the operations to a `PartitionVec<()>` of the given length.
+    fn build(len: usize, operations: &[Operation]) -> PartitionVec<()> {
+        let mut partition_vec = PartitionVec::from(vec![(); len]);
+        for operation in operations {
+            operation.apply(&mut partition_vec);
+            partition_vec.assert_invariants();
+        }
+
+        partition_vec
+    }
+
+    /// Returns a label for every index where indices share a label if they share a set.
+    ///
+    /// This is a slow but obviously correct model of the operations.
+    fn model(len: usize, operations: &[Operation]) -> Vec<usize> {
+        let mut labels: Vec<usize> = (0..len).collect();
+        let mut next_label = len;
+        for operation in operations {
+            match *operation {
+                Operation::Union(first, second) => {
+                    let (old, new) = (labels[second], labels[first]);
+                    for label in &mut labels {
+                        if *label == old {
+                            *label = new;
+                        }
+                    }
+                }
+                Operation::MakeSingleton(index) => {
+                    labels[index] = next_label;
+                    next_label += 1;
+                }
+            }
+        }
+
+        labels
+    }
+
+    /// Returns a label for every index where indices share a label if they are in the same run
+    /// of equal values, following a run-length encoding of `values`.
+    fn run_labels(values: &[u8]) -> Vec<usize> {
+        let mut runs: Vec<(u8, usize)> = Vec::new();
+        for &value in values {
+            match runs.last_mut() {
+                Some(&mut (last, ref mut len)) if last == value => *len += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+
+        runs.iter()
+            .enumerate()
+            .flat_map(|(run, &(_, len))| std::iter::repeat_n(run, len))
+            .collect()
+    }
+
+    /// Checks that the sets of `partition_vec` are the sets given by `labels`.
+    ///
+    /// This also follows every link and parent so it panics if any of them is out of bounds.
+    fn assert_sets<T>(
+        partition_vec: &PartitionVec<T>,
+        labels: &[usize],
+    ) -> Result<(), TestCaseError> {
+        prop_assert_eq!(partition_vec.len(), labels.len());
+        for first in 0..labels.len() {
+            let mut members: Vec<usize> =
+                partition_vec.set(first).map(|(index, _)| index).collect();
+            members.sort_unstable();
+            let expected: Vec<usize> = (0..labels.len())
+                .filter(|&index| labels[index] == labels[first])
+                .collect();
+            prop_assert_eq!(members, expected);
+
+            for second in 0..labels.len() {
+                prop_assert_eq!(
+                    partition_vec.same_set(first, second),
+                    labels[first] == labels[second]
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Labels every cell of `cells` with the first index of its region found by flood fill.
+    fn flood_fill(width: usize, cells: &[bool], connectivity: Connectivity) -> Vec<usize> {
+        let height = cells.len() / width;
+        let mut labels = vec![usize::MAX; cells.len()];
+        for start in 0..cells.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            labels[start] = start;
+            let mut stack = vec![start];
+            while let Some(index) = stack.pop() {
+                let (x, y) = (index % width, index / width);
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        let diagonal = nx != x && ny != y;
+                        let neighbor = ny * width + nx;
+                        if (connectivity == Connectivity::Eight || !diagonal)
+                            && labels[neighbor] == usize::MAX
+                            && cells[neighbor] == cells[index]
+                        {
+                            labels[neighbor] = start;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        labels
+    }
+
+    /// Checks that a `PartitionArray<(), N>` has the same sets as a `PartitionVec<()>`
+    /// after applying `operations` with indices modulo `N`.
+    fn check_partition_array<const N: usize>(
+        operations: &[Operation],
+    ) -> Result<(), TestCaseError> {
+        let mut partition_array = PartitionArrayIndices::<N>::default();
+        let mut partition_vec = PartitionVec::from(vec![(); N]);
+        if N > 0 {
+            let operations: Vec<Operation> = operations
+                .iter()
+                .map(|operation| operation.wrap(N))
+                .collect();
+            apply(&mut partition_array, &operations);
+            apply(&mut partition_vec, &operations);
+        }
+
+        prop_assert_eq!(partition_array.len(), N);
+        prop_assert_eq!(
+            partition_array.amount_of_sets(),
+            partition_vec.amount_of_sets()
+        );
+        for first in 0..N {
+            prop_assert_eq!(
+                partition_array.len_of_set(first),
+                partition_vec.len_of_set(first)
+            );
+            for second in 0..N {
+                prop_assert_eq!(
+                    partition_array.same_set(first, second),
+                    partition_vec.same_set(first, second)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// An index newtype for `TypedPartitionVec` tests.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct NodeId(usize);
+
+    impl_idx!(NodeId);
+
+    proptest! {
+        #[test]
+        fn operations_match_model((len, operations) in operations()) {
+            let partition_vec = build(len, &operations);
+            assert_sets(&partition_vec, &model(len, &operations))?;
+        }
+
+        #[test]
+        fn partition_array_matches_partition_vec((len, operations) in operations()) {
+            // The indices are wrapped to fit in the array.
+            let operations: Vec<Operation> = operations.iter().map(|operation| operation.wrap(8)).collect();
+            let partition_vec = build(len.max(8), &operations);
+            let mut partition_array = PartitionArray::new([(); 8]);
+            apply(&mut partition_array, &operations);
+
+            for first in 0..8 {
+                let mut expected: Vec<usize> = partition_vec.set(first).map(|(index, ())| index).collect();
+                expected.sort_unstable();
+                let mut members: Vec<usize> = partition_array.set(first).map(|(index, ())| index).collect();
+                members.sort_unstable();
+                prop_assert_eq!(members, expected);
+                prop_assert_eq!(partition_array.is_singleton(first), partition_vec.is_singleton(first));
+
+                for second in 0..8 {
+                    prop_assert_eq!(
+                        partition_array.same_set(first, second),
+                        partition_vec.same_set(first, second)
+                    );
+                    prop_assert_eq!(
+                        partition_array.find(first) == partition_array.find(second),
+                        partition_vec.same_set(first, second)
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn union_find_implementors_agree((_, operations) in operations()) {
+            let mut partition_vec = PartitionVec::from(vec![(); 8]);
+            let mut partition_array = PartitionArray::new([(); 8]);
+            let first_sets: &mut dyn UnionFind = &mut partition_vec;
+            let second_sets: &mut dyn UnionFind = &mut partition_array;
+
+            for operation in operations {
+                match operation.wrap(8) {
+                    Operation::Union(first, second) => prop_assert_eq!(
+                        first_sets.union(first, second),
+                        second_sets.union(first, second)
+                    ),
+                    operation @ Operation::MakeSingleton(_) => {
+                        operation.apply(first_sets);
+                        operation.apply(second_sets);
+                    }
+                }
+                prop_assert_eq!(first_sets.amount_of_sets(), second_sets.amount_of_sets());
+            }
+
+            for first in 0..8 {
+                for second in 0..8 {
+                    prop_assert_eq!(first_sets.same_set(first, second), second_sets.same_set(first, second));
+                }
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "rayon")]
+        fn par_all_sets_mut_matches_all_sets_mut((len, operations) in operations()) {
+            use rayon::prelude::*;
+
+            let mut sequential = PartitionVec::from(vec![0; len]);
+            apply(&mut sequential, &operations);
+            let mut parallel = sequential.clone();
+
+            for (set_number, set) in sequential.all_sets_mut().enumerate() {
+                for (_, value) in set {
+                    *value = set_number;
+                }
+            }
+            parallel.par_all_sets_mut().enumerate().for_each(|(set_number, set)| {
+                for (_, value) in set {
+                    *value = set_number;
+                }
+            });
+
+            prop_assert_eq!(parallel.as_slice(), sequential.as_slice());
+            parallel.assert_invariants();
+        }
+
+        #[test]
+        #[cfg(feature = "rayon")]
+        fn par_components_match_sets((len, operations) in operations()) {
+            let partition_vec = build(len, &operations);
+            let labels = model(len, &operations);
+
+            let components = partition_vec.par_components();
+            prop_assert_eq!(components.len(), partition_vec.amount_of_sets());
+            for component in components {
+                let mut expected: Vec<usize> = partition_vec.set(component[0]).map(|(index, ())| index).collect();
+                expected.sort_unstable();
+                let mut component = component;
+                component.sort_unstable();
+                prop_assert!(component.iter().all(|&index| labels[index] == labels[component[0]]));
+                prop_assert_eq!(component, expected);
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "rayon")]
+        fn par_union_all_matches_model((len, operations) in operations()) {
+            let edges: Vec<(usize, usize)> = operations
+                .iter()
+                .filter_map(|operation| match *operation {
+                    Operation::Union(first, second) => Some((first, second)),
+                    Operation::MakeSingleton(_) => None,
+                })
+                .collect();
+            let operations: Vec<Operation> = edges
+                .iter()
+                .map(|&(first, second)| Operation::Union(first, second))
+                .collect();
+
+            let mut partition_vec = PartitionVec::from(vec![(); len]);
+            partition_vec.par_union_all(&edges);
+            partition_vec.assert_invariants();
+
+            assert_sets(&partition_vec, &model(len, &operations))?;
+        }
+
+        #[test]
+        fn union_adjacent_if_matches_run_lengths(values in collection::vec(0..3u8, 0..64)) {
+            let mut partition_vec: PartitionVec<u8> = values.iter().collect();
+            let joined = partition_vec.union_adjacent_if(|first, second| first == second);
+            partition_vec.assert_invariants();
+
+            let labels = run_labels(&values);
+            prop_assert_eq!(joined, values.len() - partition_vec.amount_of_sets());
+            assert_sets(&partition_vec, &labels)?;
+        }
+
+        #[test]
+        fn union_adjacent_if_in_matches_run_lengths(
+            values in collection::vec(0..3u8, 0..64),
+            start in 0..64usize,
+            end in 0..64usize,
+        ) {
+            let (start, end) = (start % (values.len() + 1), end % (values.len() + 1));
+            let (start, end) = (start.min(end), start.max(end));
+            let mut partition_vec: PartitionVec<u8> = values.iter().collect();
+            partition_vec.union_adjacent_if_in(start..end, |first, second| first == second);
+            partition_vec.assert_invariants();
+
+            // Indices outside of the range are in their own run.
+            let mut labels: Vec<usize> = (0..values.len()).map(|index| index + values.len()).collect();
+            for (label, run) in labels[start..end].iter_mut().zip(run_labels(&values[start..end])) {
+                *label = run;
+            }
+            assert_sets(&partition_vec, &labels)?;
+        }
+
+        #[test]
+        fn union_range_matches_pairwise_unions(
+            (len, mut operations) in operations(),
+            ranges in collection::vec((0..64usize, 0..64usize), 0..8),
+        ) {
+            let mut partition_vec = build(len, &operations);
+            for (start, end) in ranges {
+                let (start, end) = (start % (len + 1), end % (len + 1));
+                let (start, end) = (start.min(end), start.max(end));
+                partition_vec.union_range(start..end);
+                partition_vec.assert_invariants();
+                operations.extend((start + 1..end).map(|index| Operation::Union(index - 1, index)));
+            }
+
+            assert_sets(&partition_vec, &model(len, &operations))?;
+        }
+
+        #[test]
+        fn drain_set_keeps_other_sets((len, operations) in operations(), index in 0..64usize) {
+            let index = index % len;
+            let mut partition_vec = build(len, &operations);
+            let mut labels = model(len, &operations);
+            let len_of_set = partition_vec.len_of_set(index);
+
+            prop_assert_eq!(partition_vec.drain_set(index).count(), len_of_set);
+            partition_vec.assert_invariants();
+            prop_assert_eq!(partition_vec.len(), len - len_of_set);
+
+            let label = labels[index];
+            labels.retain(|&other| other != label);
+            assert_sets(&partition_vec, &labels)?;
+        }
+
+        #[test]
+        fn cap_set_sizes_splits_large_sets((len, operations) in operations(), max in 1..6usize) {
+            let original = build(len, &operations);
+            let mut partition_vec = original.clone();
+            let expected: usize = original
+                .all_sets()
+                .map(|set| set.count().div_ceil(max) - 1)
+                .sum();
+
+            prop_assert_eq!(partition_vec.cap_set_sizes(max), expected);
+            partition_vec.assert_invariants();
+            prop_assert!(partition_vec.refines(&original));
+            prop_assert_eq!(partition_vec.amount_of_sets(), original.amount_of_sets() + expected);
+            for index in 0..len {
+                prop_assert!(partition_vec.len_of_set(index) <= max);
+                if original.len_of_set(index) <= max {
+                    prop_assert_eq!(partition_vec.len_of_set(index), original.len_of_set(index));
+                }
+            }
+        }
+
+        #[test]
+        fn mutations_keep_lengths_equal(
+            mutations in collection::vec((0..12u8, any::<usize>(), any::<usize>()), 0..128),
+        ) {
+            let mut partition_vec = PartitionVec::with_capacity(4);
+            for (mutation, first, second) in mutations {
+                let len = partition_vec.len();
+                match mutation {
+                    0 => partition_vec.push(first),
+                    1 => {
+                        partition_vec.pop();
+                    }
+                    2 => partition_vec.insert(first % (len + 1), second),
+                    3 if len > 0 => {
+                        partition_vec.remove(first % len);
+                    }
+                    4 => partition_vec.truncate(first % (len + 1)),
+                    5 => partition_vec.resize(first % 16, second),
+                    6 if len > 0 => partition_vec.union(first % len, second % len),
+                    7 if len > 0 => partition_vec.make_singleton(first % len),
+                    8 if len > 0 => {
+                        partition_vec.drain_set(first % len);
+                    }
+                    9 => {
+                        let mut other = PartitionVec::from(vec![first; second % 4]);
+                        partition_vec.append(&mut other);
+                        other.assert_meta_data_len_match();
+                    }
+                    10 => partition_vec.shrink_to_fit(),
+                    11 => partition_vec.reserve(first % 16),
+                    _ => partition_vec.clear(),
+                }
+                partition_vec.assert_meta_data_len_match();
+                partition_vec.assert_invariants();
+            }
+        }
+
+        #[test]
+        fn dedup_keeps_sets_of_kept_elements(
+            (len, operations) in operations(),
+            values in collection::vec(0..3u8, 64),
+        ) {
+            let mut partition_vec: PartitionVec<u8> = values[..len].iter().collect();
+            apply(&mut partition_vec, &operations);
+            let labels = model(len, &operations);
+
+            let mut expected_values = values[..len].to_vec();
+            expected_values.dedup();
+            let expected_labels: Vec<usize> = (0..len)
+                .filter(|&i| i == 0 || values[i] != values[i - 1])
+                .map(|i| labels[i])
+                .collect();
+
+            partition_vec.dedup();
+            partition_vec.assert_invariants();
+            prop_assert_eq!(partition_vec.as_slice(), &expected_values[..]);
+            assert_sets(&partition_vec, &expected_labels)?;
+        }
+
+        #[test]
+        fn union_by_external_rank_keeps_preferred_root(
+            (len, operations) in operations(),
+            weights in collection::vec(0..4u8, 64),
+        ) {
+            let mut partition_vec = PartitionVec::from(vec![(); len]);
+            let prefer = |first: usize, second: usize| weights[first].cmp(&weights[second]);
+            for operation in &operations {
+                match *operation {
+                    Operation::Union(first, second) => {
+                        let (first_root, second_root) = (partition_vec.find(first), partition_vec.find(second));
+                        partition_vec.union_by_external_rank(first, second, prefer);
+                        let root = partition_vec.find(first);
+                        if weights[first_root] != weights[second_root] {
+                            prop_assert_eq!(root, std::cmp::max_by_key(first_root, second_root, |&root| weights[root]));
+                        }
+                    }
+                    Operation::MakeSingleton(index) => partition_vec.make_singleton(index),
+                }
+                partition_vec.assert_invariants();
+            }
+
+            assert_sets(&partition_vec, &model(len, &operations))?;
+        }
+
+        #[test]
+        fn when_connected_matches_replay((len, operations) in operations()) {
+            let unions: Vec<(usize, usize)> = operations
+                .iter()
+                .filter_map(|operation| match *operation {
+                    Operation::Union(first, second) => Some((first, second)),
+                    Operation::MakeSingleton(_) => None,
+                })
+                .collect();
+
+            let mut partition = TimestampedPartition::new(len);
+            let mut replay = PartitionVec::from(vec![(); len]);
+            let mut expected: Vec<Vec<Option<usize>>> = (0..len)
+                .map(|first| (0..len).map(|second| if first == second { Some(0) } else { None }).collect())
+                .collect();
+            for (step, &(first, second)) in unions.iter().enumerate() {
+                prop_assert_eq!(partition.union(first, second), !replay.same_set(first, second));
+                replay.union(first, second);
+                for (first, row) in expected.iter_mut().enumerate() {
+                    for (second, connected) in row.iter_mut().enumerate() {
+                        if connected.is_none() && replay.same_set(first, second) {
+                            *connected = Some(step + 1);
+                        }
+                    }
+                }
+            }
+
+            prop_assert_eq!(partition.steps(), unions.len());
+            for (first, row) in expected.iter().enumerate() {
+                for (second, &connected) in row.iter().enumerate() {
+                    prop_assert_eq!(partition.when_connected(first, second), connected);
+                }
+            }
+        }
+
+        #[test]
+        fn nth_in_set_visits_every_member((len, operations) in operations()) {
+            let partition_vec = build(len, &operations);
+
+            for index in 0..len {
+                let len_of_set = partition_vec.len_of_set(index);
+                let mut members: Vec<usize> = (0..len_of_set)
+                    .map(|n| partition_vec.nth_in_set(index, n).unwrap())
+                    .collect();
+                members.sort_unstable();
+                let mut expected: Vec<usize> = partition_vec.set(index).map(|(i, ())| i).collect();
+                expected.sort_unstable();
+
+                prop_assert_eq!(partition_vec.nth_in_set(index, 0), Some(index));
+                prop_assert_eq!(members, expected);
+                prop_assert_eq!(partition_vec.nth_in_set(index, len_of_set), None);
+            }
+        }
+
+        #[test]
+        fn offline_connectivity_matches_recompute(
+            len in 1..16usize,
+            events in collection::vec((0..4u8, any::<usize>(), any::<usize>()), 0..64),
+        ) {
+            let mut timeline = OfflineConnectivity::new(len);
+            let mut active: Vec<(usize, usize)> = Vec::new();
+            let mut expected = Vec::new();
+            for (kind, first, second) in events {
+                let (first, second) = (first % len, second % len);
+                match kind {
+                    0 | 1 => {
+                        timeline.union(first, second);
+                        active.push((first, second));
+                    }
+                    2 if !active.is_empty() => {
+                        let (first, second) = active.remove(first % active.len());
+                        timeline.remove(second, first);
+                    }
+                    _ => {
+                        prop_assert_eq!(timeline.same_set(first, second), expected.len());
+                        let partition_vec = PartitionVec::rebuild_without(len, &[], &active);
+                        expected.push(partition_vec.same_set(first, second));
+                    }
+                }
+            }
+
+            prop_assert_eq!(timeline.answer(), expected);
+        }
+
+        #[test]
+        fn rebuild_without_matches_remaining_unions(
+            (len, operations) in operations(),
+            removed in collection::vec(any::<usize>(), 0..8),
+        ) {
+            let mut unions: Vec<(usize, usize)> = operations
+                .iter()
+                .filter_map(|operation| match *operation {
+                    Operation::Union(first, second) => Some((first, second)),
+                    Operation::MakeSingleton(_) => None,
+                })
+                .collect();
+            let all_unions = unions.clone();
+            let mut removed_unions = Vec::new();
+            for index in removed {
+                if !unions.is_empty() {
+                    let (first, second) = unions.remove(index % unions.len());
+                    removed_unions.push((second, first));
+                }
+            }
+
+            let rebuilt = PartitionVec::rebuild_without(len, &removed_unions, &all_unions);
+            let mut expected = PartitionVec::from(vec![(); len]);
+            for &(first, second) in &unions {
+                expected.union(first, second);
+            }
+            prop_assert!(rebuilt.refines(&expected) && expected.refines(&rebuilt));
+        }
+
+        #[test]
+        fn offline_lca_matches_upward_walk(
+            parents in collection::vec(any::<usize>(), 0..48),
+            queries in collection::vec((any::<usize>(), any::<usize>()), 0..32),
+            extra in any::<(usize, usize)>(),
+        ) {
+            // Node `i + 1` gets a parent below it so the parents always form a tree.
+            let len = parents.len() + 1;
+            let parents: Vec<usize> = parents.iter().enumerate().map(|(i, &parent)| parent % (i + 1)).collect();
+            let mut tree = vec![Vec::new(); len];
+            for (i, &parent) in parents.iter().enumerate() {
+                tree[parent].push(i + 1);
+            }
+            let parent = |node: usize| parents[node - 1];
+            let depth = |mut node: usize| {
+                let mut depth = 0;
+                while node != 0 {
+                    node = parent(node);
+                    depth += 1;
+                }
+                depth
+            };
+
+            let queries: Vec<(usize, usize)> = queries.iter().map(|&(first, second)| (first % len, second % len)).collect();
+            let expected: Vec<usize> = queries
+                .iter()
+                .map(|&(mut first, mut second)| {
+                    while first != second {
+                        if depth(first) >= depth(second) {
+                            first = parent(first);
+                        } else {
+                            second = parent(second);
+                        }
+                    }
+                    first
+                })
+                .collect();
+            prop_assert_eq!(offline_lca(&tree, 0, &queries), Ok(expected));
+
+            // Any extra edge reaches a node a second time.
+            let (from, to) = (extra.0 % len, extra.1 % len);
+            tree[from].push(to);
+            let result = offline_lca(&tree, 0, &queries);
+            prop_assert!(matches!(result, Err(TreeError::MultipleParents(_))));
+        }
+
+        #[test]
+        fn union_by_key_matches_model((len, operations) in operations(), keys in collection::vec(0..8usize, 64)) {
+            let mut partition_vec = build(len, &operations);
+            let mut labels = model(len, &operations);
+
+            partition_vec.union_by_key(|index, ()| keys[index]);
+            for first in 0..len {
+                for second in 0..len {
+                    if keys[first] == keys[second] {
+                        let (old, new) = (labels[second], labels[first]);
+                        for label in &mut labels {
+                            if *label == old {
+                                *label = new;
+                            }
+                        }
+                    }
+                }
+            }
+
+            partition_vec.assert_invariants();
+            assert_sets(&partition_vec, &labels)?;
+        }
+
+        #[test]
+        fn cycle_detector_matches_model(
+            len in 1..16usize,
+            edges in collection::vec((any::<usize>(), any::<usize>()), 0..32),
+        ) {
+            let edges: Vec<(usize, usize)> = edges.iter().map(|&(first, second)| (first % len, second % len)).collect();
+            let mut cycles = CycleDetector::new(len);
+            let mut array_cycles = CycleDetector::from_components(PartitionArray::new([(); 16]));
+            let mut labels: Vec<usize> = (0..len).collect();
+            let mut first_cycle = None;
+            for (index, &(first, second)) in edges.iter().enumerate() {
+                let closes = labels[first] == labels[second];
+                prop_assert_eq!(cycles.adds_cycle(first, second), closes);
+                prop_assert_eq!(array_cycles.adds_cycle(first, second), closes);
+                if closes {
+                    first_cycle = first_cycle.or(Some(index));
+                } else {
+                    let (old, new) = (labels[second], labels[first]);
+                    for label in &mut labels {
+                        if *label == old {
+                            *label = new;
+                        }
+                    }
+                }
+            }
+
+            prop_assert_eq!(first_cycle_edge(&edges), first_cycle);
+            let small: SmallPartitionVec<(), 16> = vec![(); len].into_iter().collect();
+            prop_assert_eq!(CycleDetector::from_components(small).first_cycle_edge(&edges), first_cycle);
+        }
+
+        #[test]
+        fn eq_exact_implies_eq((len, operations) in operations(), split in 0..128usize) {
+            let partition_vec = build(len, &operations);
+            let clone = partition_vec.clone();
+            prop_assert!(partition_vec.eq_exact(&clone));
+            prop_assert!(partition_vec == clone);
+
+            // The same unions in another order give the same sets.
+            let split = split % (operations.len() + 1);
+            let reordered_operations: Vec<Operation> = operations[split..]
+                .iter()
+                .chain(&operations[..split])
+                .cloned()
+                .collect();
+            let reordered = build(len, &reordered_operations);
+            if partition_vec.eq_exact(&reordered) {
+                prop_assert!(partition_vec == reordered);
+            }
+            let (labels, reordered_labels) = (model(len, &operations), model(len, &reordered_operations));
+            let same_sets = (0..len).all(|first| {
+                (0..len).all(|second| {
+                    (labels[first] == labels[second]) == (reordered_labels[first] == reordered_labels[second])
+                })
+            });
+            prop_assert_eq!(partition_vec == reordered, same_sets);
+        }
+
+        #[test]
+        fn two_color_matches_brute_force(
+            len in 1..9usize,
+            edges in collection::vec((any::<usize>(), any::<usize>()), 0..12),
+        ) {
+            let edges: Vec<(usize, usize)> = edges.iter().map(|&(first, second)| (first % len, second % len)).collect();
+            let valid = |colors: &[bool], edges: &[(usize, usize)]| {
+                edges.iter().all(|&(first, second)| colors[first] != colors[second])
+            };
+            // Every prefix of the edges is checked against all colorings.
+            let bipartite = |edges: &[(usize, usize)]| {
+                (0..1u32 << len).any(|mask| {
+                    let colors: Vec<bool> = (0..len).map(|index| mask & (1 << index) != 0).collect();
+                    valid(&colors, edges)
+                })
+            };
+
+            prop_assert_eq!(is_bipartite(len, &edges), bipartite(&edges));
+            match two_color(len, &edges) {
+                Ok(colors) => prop_assert!(valid(&colors, &edges)),
+                Err(odd_cycle) => {
+                    prop_assert!(bipartite(&edges[..odd_cycle.edge]));
+                    prop_assert!(!bipartite(&edges[..=odd_cycle.edge]));
+                }
+            }
+        }
+
+        #[test]
+        fn into_sets_matches_all_sets((len, operations) in operations()) {
+            let mut partition_vec: PartitionVec<usize> = (0..len).collect();
+            apply(&mut partition_vec, &operations);
+            let expected: Vec<Vec<usize>> = partition_vec
+                .all_sets()
+                .map(|set| {
+                    let mut values: Vec<usize> = set.map(|(_, &value)| value).collect();
+                    values.sort_unstable();
+                    values
+                })
+                .collect();
+
+            let sets: Vec<Vec<usize>> = partition_vec.into_sets().collect();
+            prop_assert_eq!(sets, expected);
+        }
+
+        #[test]
+        fn process_ops_matches_individual_calls(
+            (len, operations) in operations(),
+            queries in collection::vec((any::<usize>(), any::<usize>()), 64),
+        ) {
+            // Every operation is followed by a query.
+            let ops: Vec<Op> = operations
+                .iter()
+                .zip(&queries)
+                .flat_map(|(operation, &(first, second))| {
+                    let op = match *operation {
+                        Operation::Union(first, second) => Op::Union(first, second),
+                        Operation::MakeSingleton(index) => Op::MakeSingleton(index),
+                    };
+                    vec![op, Op::SameSet(first % len, second % len)]
+                })
+                .collect();
+
+            let mut individual = PartitionVec::from(vec![(); len]);
+            let mut expected = Vec::new();
+            for &op in &ops {
+                match op {
+                    Op::Union(first, second) => individual.union(first, second),
+                    Op::SameSet(first, second) => expected.push(individual.same_set(first, second)),
+                    Op::MakeSingleton(index) => individual.make_singleton(index),
+                }
+            }
+
+            let mut partition_vec = PartitionVec::from(vec![(); len]);
+            prop_assert_eq!(partition_vec.process_ops(ops), expected);
+            prop_assert!(partition_vec == individual);
+        }
+
+        #[test]
+        fn label_regions_matches_flood_fill(
+            width in 1..8usize,
+            cells in collection::vec(any::<bool>(), 0..64),
+        ) {
+            let len = cells.len() / width * width;
+            let cells = &cells[..len];
+            let rows: Vec<&[bool]> = cells.chunks(width).collect();
+
+            for &connectivity in &[Connectivity::Four, Connectivity::Eight] {
+                let mut grid = PartitionGrid::from_rows(&rows);
+                grid.label_regions(|first, second| first == second, connectivity);
+                let expected = flood_fill(width, cells, connectivity);
+
+                for first in 0..len {
+                    for second in 0..len {
+                        let first_cell = (first % width, first / width);
+                        let second_cell = (second % width, second / width);
+                        prop_assert_eq!(
+                            grid.same_region(first_cell, second_cell),
+                            expected[first] == expected[second]
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn concat_keeps_sets_apart(
+            (first_len, first_operations) in operations(),
+            (second_len, second_operations) in operations(),
+        ) {
+            let first = build(first_len, &first_operations);
+            let second = build(second_len, &second_operations);
+            let amount = first.amount_of_sets() + second.amount_of_sets();
+
+            let both = first.concat(second);
+            prop_assert_eq!(both.len(), first_len + second_len);
+            prop_assert_eq!(both.amount_of_sets(), amount);
+            for index in 0..first_len {
+                for other in first_len..both.len() {
+                    prop_assert!(!both.same_set(index, other));
+                }
+            }
+        }
+
+        #[test]
+        fn small_partition_vec_matches_partition_vec(
+            (len, operations) in operations(),
+            pops in 0..64usize,
+        ) {
+            let mut small: SmallPartitionVec<(), 8> = vec![(); len].into_iter().collect();
+            apply(&mut small, &operations);
+            prop_assert_eq!(small.is_inline(), len <= 8);
+            assert_sets(&small.clone().into_partition_vec(), &model(len, &operations))?;
+
+            let mut expected = build(len, &operations);
+            for _ in 0..pops {
+                prop_assert_eq!(small.pop().is_some(), expected.pop().is_some());
+            }
+            small.shrink_to_fit();
+            prop_assert_eq!(small.is_inline(), small.len() <= 8);
+            prop_assert_eq!(small.len(), expected.len());
+            prop_assert_eq!(small.amount_of_sets(), expected.amount_of_sets());
+            for first in 0..small.len() {
+                for second in 0..small.len() {
+                    prop_assert_eq!(small.same_set(first, second), expected.same_set(first, second));
+                }
+            }
+
+            let mut small = SmallPartitionVec::<(), 8>::from(expected.clone());
+            small.extend(vec![(); 9]);
+            expected.extend(vec![(); 9]);
+            prop_assert!(!small.is_inline());
+            prop_assert!(small.into_partition_vec() == expected);
+        }
+
+        #[test]
+        fn small_partition_vec_edits_match_partition_vec(
+            (len, operations) in operations(),
+            edits in collection::vec((0..3u8, any::<usize>()), 0..16),
+        ) {
+            let mut small: SmallPartitionVec<usize, 8> = (0..len).collect();
+            let mut expected: PartitionVec<usize> = (0..len).collect();
+            apply(&mut small, &operations);
+            apply(&mut expected, &operations);
+
+            for (kind, index) in edits {
+                match kind {
+                    0 => {
+                        let index = index % (expected.len() + 1);
+                        small.insert(index, len + index);
+                        expected.insert(index, len + index);
+                    }
+                    _ if expected.is_empty() => {}
+                    1 => {
+                        let index = index % expected.len();
+                        prop_assert_eq!(small.remove(index), expected.remove(index));
+                    }
+                    _ => {
+                        let new_len = index % expected.len();
+                        small.truncate(new_len);
+                        expected.truncate(new_len);
+                    }
+                }
+                prop_assert!(small[..] == expected[..]);
+            }
+
+            for index in 0..small.len() {
+                let mut members: Vec<usize> = small.set_mut(index).map(|(i, _)| i).collect();
+                members.sort_unstable();
+                let mut expected_members: Vec<usize> = expected.set(index).map(|(i, _)| i).collect();
+                expected_members.sort_unstable();
+                prop_assert_eq!(members, expected_members);
+            }
+            prop_assert_eq!(small.all_sets().count(), expected.amount_of_sets());
+            prop_assert_eq!(format!("{small:?}"), format!("{expected:?}"));
+            prop_assert!(small.into_partition_vec() == expected);
+        }
+
+        #[test]
+        fn resize_with_matches_resize(
+            (len, operations) in operations(),
+            new_len in 0..128usize,
+        ) {
+            let mut expected = build(len, &operations);
+            expected.resize(new_len, ());
+            let mut resized = build(len, &operations);
+            resized.resize_with(new_len, || ());
+
+            prop_assert!(resized == expected);
+            prop_assert!(resized.validate().is_ok());
+        }
+
+        #[test]
+        fn partition_array_matches_partition_vec_at_sizes((_, operations) in operations()) {
+            check_partition_array::<0>(&operations)?;
+            check_partition_array::<1>(&operations)?;
+            check_partition_array::<2>(&operations)?;
+            check_partition_array::<5>(&operations)?;
+            check_partition_array::<16>(&operations)?;
+        }
+
+        #[test]
+        fn enumerate_chunks_and_windows_match_slices(
+            values in collection::vec(any::<u8>(), 0..64),
+            size in 1..10usize,
+        ) {
+            let partition_vec = PartitionVec::from(values.clone());
+
+            for (start, chunk) in partition_vec.enumerate_chunks(size) {
+                prop_assert_eq!(start % size, 0);
+                prop_assert_eq!(chunk, &values[start..(start + size).min(values.len())]);
+            }
+            prop_assert_eq!(partition_vec.enumerate_chunks(size).count(), values.len().div_ceil(size));
+
+            for (start, window) in partition_vec.enumerate_windows(size) {
+                prop_assert_eq!(window, &values[start..start + size]);
+            }
+            prop_assert_eq!(
+                partition_vec.enumerate_windows(size).count(),
+                (values.len() + 1).saturating_sub(size)
+            );
+        }
+
+        #[test]
+        fn typed_partition_vec_matches_partition_vec((len, operations) in operations()) {
+            let mut typed: TypedPartitionVec<(), NodeId> = vec![(); len].into_iter().collect();
+            for operation in &operations {
+                match *operation {
+                    Operation::Union(first, second) => typed.union(NodeId(first), NodeId(second)),
+                    Operation::MakeSingleton(index) => typed.make_singleton(NodeId(index)),
+                }
+            }
+
+            let expected = build(len, &operations);
+            prop_assert!(typed.as_partition_vec() == &expected);
+            for index in 0..len {
+                let mut members: Vec<usize> = typed.set(NodeId(index)).map(|(id, ())| id.0).collect();
+                members.sort_unstable();
+                let mut expected_members: Vec<usize> = expected.set(index).map(|(i, ())| i).collect();
+                expected_members.sort_unstable();
+                prop_assert_eq!(members, expected_members);
+            }
+        }
+
+        #[test]
+        fn partition_vec_deque_slides_like_model(
+            steps in collection::vec((0..4u8, any::<usize>(), any::<usize>()), 0..256),
+            window in 1..16usize,
+        ) {
+            use std::collections::VecDeque;
+
+            let mut deque = PartitionVecDeque::new();
+            // Every live element has the label of its set.
+            let mut labels = VecDeque::new();
+            let mut next_label = 0;
+
+            for (kind, first, second) in steps {
+                match kind {
+                    0 => {
+                        deque.push_back(next_label);
+                        labels.push_back(next_label);
+                        next_label += 1;
+                    }
+                    1 => {
+                        deque.push_front(next_label);
+                        labels.push_front(next_label);
+                        next_label += 1;
+                    }
+                    _ if labels.is_empty() => {}
+                    2 => {
+                        let (first, second) = (first % labels.len(), second % labels.len());
+                        deque.union(first, second);
+                        let (old, new) = (labels[first], labels[second]);
+                        for label in &mut labels {
+                            if *label == old {
+                                *label = new;
+                            }
+                        }
+                    }
+                    _ => {
+                        let index = first % labels.len();
+                        deque.make_singleton(index);
+                        labels[index] = next_label;
+                        next_label += 1;
+                    }
+                }
+
+                while labels.len() > window {
+                    if next_label % 2 == 0 {
+                        prop_assert!(deque.pop_front().is_some());
+                        labels.pop_front();
+                    } else {
+                        prop_assert!(deque.pop_back().is_some());
+                        labels.pop_back();
+                    }
+                }
+
+                prop_assert_eq!(deque.len(), labels.len());
+                for first in 0..labels.len() {
+                    let members = deque.set(first).map(|(index, _)| index).collect::<Vec<_>>();
+                    let expected = (0..labels.len()).filter(|&index| labels[index] == labels[first]).count();
+                    prop_assert_eq!(members.len(), expected);
+                    for second in 0..labels.len() {
+                        prop_assert_eq!(deque.same_set(first, second), labels[first] == labels[second]);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn structure_hash_follows_sets(
+            (len, operations) in operations(),
+            (other_len, other_operations) in operations(),
+            values in collection::vec(any::<u8>(), 64),
+        ) {
+            let partition_vec = build(len, &operations);
+            let mut valued = PartitionVec::from(values[..len].to_vec());
+            valued.copy_partition_from(&partition_vec);
+            prop_assert_eq!(valued.structure_hash(), partition_vec.structure_hash());
+
+            let other = build(other_len, &other_operations);
+            if other == partition_vec {
+                prop_assert_eq!(other.structure_hash(), partition_vec.structure_hash());
+            }
+        }
+
+        #[test]
+        fn partition_slab_matches_model(
+            steps in collection::vec((0..5u8, any::<usize>(), any::<usize>()), 0..256),
+            compact_at in 0..256usize,
+        ) {
+            use std::collections::BTreeMap;
+
+            let mut slab = PartitionSlab::new();
+            // The value and the label of the set of every live key.
+            let mut live: BTreeMap<usize, (String, usize)> = BTreeMap::new();
+            let mut next = 0;
+
+            for (step, (kind, first, second)) in steps.into_iter().enumerate() {
+                let keys: Vec<usize> = live.keys().copied().collect();
+                match kind {
+                    0 | 1 => {
+                        let key = slab.insert(next.to_string());
+                        prop_assert!(!live.contains_key(&key));
+                        live.insert(key, (next.to_string(), next));
+                        next += 1;
+                    }
+                    _ if keys.is_empty() => {}
+                    2 => {
+                        let key = keys[first % keys.len()];
+                        prop_assert_eq!(slab.remove(key), live.remove(&key).map(|(value, _)| value));
+                        prop_assert!(slab.get(key).is_none());
+                        prop_assert!(slab.remove(key).is_none());
+                    }
+                    3 => {
+                        let (first, second) = (keys[first % keys.len()], keys[second % keys.len()]);
+                        slab.union(first, second);
+                        let (old, new) = (live[&first].1, live[&second].1);
+                        for entry in live.values_mut() {
+                            if entry.1 == old {
+                                entry.1 = new;
+                            }
+                        }
+                    }
+                    _ => {
+                        let key = keys[first % keys.len()];
+                        slab.make_singleton(key);
+                        if let Some(entry) = live.get_mut(&key) {
+                            entry.1 = next;
+                        }
+                        next += 1;
+                    }
+                }
+
+                if step == compact_at {
+                    let new_keys = slab.compact();
+                    prop_assert_eq!(new_keys.iter().flatten().count(), live.len());
+                    live = live
+                        .into_iter()
+                        .filter_map(|(key, entry)| Some((new_keys[key]?, entry)))
+                        .collect();
+                }
+
+                prop_assert_eq!(slab.len(), live.len());
+                let values: Vec<(usize, &String)> = live.iter().map(|(&key, (value, _))| (key, value)).collect();
+                prop_assert_eq!(slab.iter().collect::<Vec<_>>(), values);
+                for (&key, &(_, label)) in &live {
+                    let mut members: Vec<usize> = slab.set(key).map(|(member, _)| member).collect();
+                    members.sort_unstable();
+                    let expected: Vec<usize> = live
+                        .iter()
+                        .filter(|&(_, entry)| entry.1 == label)
+                        .map(|(&member, _)| member)
+                        .collect();
+                    prop_assert_eq!(members, expected);
+                }
+            }
+
+            let clone = slab.clone();
+            prop_assert_eq!(clone.iter().collect::<Vec<_>>(), slab.iter().collect::<Vec<_>>());
+            prop_assert_eq!(clone.amount_of_sets(), slab.amount_of_sets());
+        }
+
+        #[test]
+        fn map_keeps_sets((len, operations) in operations()) {
+            let labels = model(len, &operations);
+            let indices: PartitionVec<usize> = build(len, &operations).map_ref(|()| 0);
+
+            let borrowed = indices.map_ref(|&value| value + 1);
+            assert_sets(&borrowed, &labels)?;
+            prop_assert!(borrowed.iter().all(|&value| value == 1));
+
+            let owned = indices.map(|value| value.to_string());
+            assert_sets(&owned, &labels)?;
+            prop_assert!(owned.iter().all(|value| value == "0"));
+        }
+
+        #[test]
+        fn retain_mut_keeps_sets_of_survivors(
+            (len, operations) in operations(),
+            keep in collection::vec(any::<bool>(), 64),
+        ) {
+            let labels = model(len, &operations);
+            let mut partition_vec: PartitionVec<usize> = build(len, &operations).map_ref(|()| 0);
+            for (i, value) in partition_vec.iter_mut().enumerate() {
+                *value = i;
+            }
+
+            let mut visited = Vec::new();
+            partition_vec.retain_mut(|value| {
+                let index = *value;
+                visited.push(index);
+                *value += len;
+
+                keep[index]
+            });
+
+            let kept: Vec<usize> = (0..len).filter(|&i| keep[i]).collect();
+            let kept_labels: Vec<usize> = kept.iter().map(|&i| labels[i]).collect();
+            prop_assert!(visited == (0..len).collect::<Vec<_>>());
+            prop_assert!(partition_vec.iter().map(|&value| value - len).eq(kept.iter().copied()));
+            assert_sets(&partition_vec, &kept_labels)?;
+            prop_assert!(partition_vec.validate().is_ok());
+        }
+
+        #[test]
+        fn from_grid_matches_flood_fill(
+            width in 1..8usize,
+            cells in collection::vec(any::<bool>(), 0..64),
+        ) {
+            let height = cells.len() / width;
+            let cells = &cells[..width * height];
+
+            let partition_vec =
+                PartitionVec::from_grid(width, height, |first, second| cells[first] == cells[second]);
+
+            assert_sets(&partition_vec, &flood_fill(width, cells, Connectivity::Four))?;
+        }
+
+        #[test]
+        fn freeze_keeps_sets((len, operations) in operations()) {
+            let frozen = build(len, &operations).freeze();
+            let labels = model(len, &operations);
+
+            for first in 0..len {
+                for second in 0..len {
+                    prop_assert_eq!(frozen.same_set(first, second), labels[first] == labels[second]);
+                }
+                prop_assert_eq!(frozen.representative(frozen.representative(first)), frozen.representative(first));
+            }
+        }
+
+        #[test]
+        fn diff_matches_refines(
+            (len, operations) in operations(),
+            later in collection::vec((0..64usize, 0..64usize), 0..16),
+        ) {
+            let before = build(len, &operations);
+            let mut after = before.clone();
+            for (first, second) in later {
+                after.union(first % len, second % len);
+            }
+
+            let same = before.diff(&before);
+            prop_assert!(same.merged.is_empty() && same.split.is_empty());
+            prop_assert_eq!(same.unchanged.len(), before.amount_of_sets());
+
+            // Only unions happened so no set was split.
+            let diff = before.diff(&after);
+            prop_assert!(diff.split.is_empty());
+            prop_assert_eq!(diff.unchanged.len() + diff.merged.iter().map(|event| event.parts.len()).sum::<usize>(), before.amount_of_sets());
+            prop_assert_eq!(diff.unchanged.len() + diff.merged.len(), after.amount_of_sets());
+
+            let reverse = after.diff(&before);
+            prop_assert!(reverse.merged.is_empty());
+            prop_assert_eq!(reverse.split.len(), diff.merged.len());
+        }
+
+        #[test]
+        fn bytes_round_trip((len, operations) in operations(), cut in 0..1024usize) {
+            let mut partition_vec: PartitionVec<String> = (0..len).map(|i| "x".repeat(i % 4)).collect();
+            partition_vec.copy_partition_from(&build(len, &operations));
+
+            let bytes = partition_vec.to_bytes(|value, bytes| bytes.extend(value.as_bytes()));
+            let decode = |bytes: &[u8]| String::from_utf8(bytes.to_vec()).ok();
+            let decoded = PartitionVec::from_bytes(&bytes, decode).unwrap();
+            decoded.assert_invariants();
+            prop_assert!(decoded == partition_vec);
+
+            // The labels do not depend on the representatives.
+            prop_assert_eq!(decoded.to_bytes(|value, bytes| bytes.extend(value.as_bytes())), bytes.clone());
+
+            let cut = cut % bytes.len();
+            prop_assert_eq!(PartitionVec::from_bytes(&bytes[..cut], decode), Err(DecodeError::UnexpectedEnd));
+
+            let mut bumped = bytes.clone();
+            bumped[4] += 1;
+            prop_assert_eq!(PartitionVec::from_bytes(&bumped, decode), Err(DecodeError::UnsupportedVersion(2)));
+
+            let mut trailing = bytes;
+            trailing.push(0);
+            prop_assert_eq!(PartitionVec::from_bytes(&trailing, decode), Err(DecodeError::TrailingBytes(1)));
+        }
+
+        #[test]
+        fn truncate_keeps_sets((len, operations) in operations(), new_len in 0..64usize) {
+            let new_len = new_len % (len + 1);
+            let mut partition_vec = build(len, &operations);
+            let mut labels = model(len, &operations);
+
+            partition_vec.truncate(new_len);
+            partition_vec.assert_invariants();
+            labels.truncate(new_len);
+
+            assert_sets(&partition_vec, &labels)?;
+        }
+
+        #[test]
+        fn operations_after_truncate(
+            (len, operations) in operations(),
+            (new_len, later) in operations(),
+        ) {
+            let new_len = new_len.min(len);
+            let later: Vec<Operation> = later
+                .into_iter()
+                .filter(|operation| match *operation {
+                    Operation::Union(first, second) => first < new_len && second < new_len,
+                    Operation::MakeSingleton(index) => index < new_len,
+                })
+                .collect();
+
+            let mut partition_vec = build(len, &operations);
+            partition_vec.truncate(new_len);
+            // The model starts over from the sets that are left after truncating.
+            let mut labels = model(len, &operations);
+            labels.truncate(new_len);
+            let mut model_operations: Vec<Operation> = (0..new_len)
+                .filter_map(|index| {
+                    (0..index)
+                        .find(|&other| labels[other] == labels[index])
+                        .map(|other| Operation::Union(other, index))
+                })
+                .collect();
+
+            for operation in &later {
+                operation.apply(&mut partition_vec);
+                partition_vec.assert_invariants();
+            }
+            model_operations.extend(later);
+
+            assert_sets(&partition_vec, &model(new_len, &model_operations))?;
+        }
+    }
 }
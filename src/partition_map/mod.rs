@@ -1,16 +1,20 @@
+/// A reference to a key stored in the `PartitionVec` of a map.
+///
+/// Comparing and hashing is done on the key it points to, so it behaves like the key itself
+/// which is required for the `Borrow` implementation below.
 #[repr(transparent)]
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone)]
 struct UnboundedRef<K>(std::ptr::NonNull<K>)
 where
     K: ?Sized;
 
-impl<'a, K> UnboundedRef<K>
+impl<K> UnboundedRef<K>
 where
     K: ?Sized,
 {
     #[inline]
-    unsafe fn from(reference: &'a K) -> Self {
-        UnboundedRef(reference.into())
+    unsafe fn new(ptr: *const K) -> Self {
+        UnboundedRef(std::ptr::NonNull::new_unchecked(ptr.cast_mut()))
     }
 
     #[inline]
@@ -19,6 +23,48 @@ where
     }
 }
 
+impl<K> PartialEq for UnboundedRef<K>
+where
+    K: PartialEq + ?Sized,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<K> Eq for UnboundedRef<K> where K: Eq + ?Sized {}
+
+impl<K> PartialOrd for UnboundedRef<K>
+where
+    K: Ord + ?Sized,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for UnboundedRef<K>
+where
+    K: Ord + ?Sized,
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
+impl<K> std::hash::Hash for UnboundedRef<K>
+where
+    K: std::hash::Hash + ?Sized,
+{
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state);
+    }
+}
+
 impl<K, Q> std::borrow::Borrow<Transparent<Q>> for UnboundedRef<K>
 where
     K: std::borrow::Borrow<Q> + ?Sized,
@@ -43,9 +89,34 @@ fn coerce<Q>(value: &Q) -> &Transparent<Q>
 where
     Q: ?Sized,
 {
-    unsafe { &*(value as *const Q as *const Transparent<Q>) }
+    unsafe { &*(std::ptr::from_ref(value) as *const Transparent<Q>) }
+}
+
+/// The error returned when a key that is needed for an operation is not present in a map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MissingKey {
+    /// The first key is not present.
+    First,
+    /// The second key is not present.
+    Second,
+    /// Both keys are not present.
+    Both,
 }
 
+impl std::fmt::Display for MissingKey {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message = match self {
+            MissingKey::First => "the first key is not present in the map",
+            MissingKey::Second => "the second key is not present in the map",
+            MissingKey::Both => "both keys are not present in the map",
+        };
+
+        formatter.write_str(message)
+    }
+}
+
+impl std::error::Error for MissingKey {}
+
 macro_rules! partition_map {
     (
         #[$doc: meta]
@@ -64,6 +135,7 @@ macro_rules! partition_map {
             crate::{
                 PartitionVec,
                 partition_map::{
+                    MissingKey,
                     UnboundedRef,
                     coerce,
                 },
@@ -71,7 +143,6 @@ macro_rules! partition_map {
         };
 
         #[$doc]
-        #[derive(Clone)]
         pub struct $struct<K, V$(, $generic = $default)*> {
             map: $map_struct<UnboundedRef<K>, usize $(, $generic)*>,
             vec: PartitionVec<(K, V)>,
@@ -104,6 +175,59 @@ macro_rules! partition_map {
                 self.vec.union(self.map[coerce(first_key)], self.map[coerce(second_key)]);
             }
 
+            /// Joins the sets of the `first_key` and the `second_key` if both are present.
+            ///
+            /// Returns whether the two sets were joined, this is `false` if the keys already
+            /// shared a set.
+            ///
+            /// # Errors
+            ///
+            /// If one or both of the keys are not present in the map.
+            /// The map is not changed in this case.
+            pub fn try_union<Q1, Q2>(
+                &mut self,
+                first_key: &Q1,
+                second_key: &Q2,
+            ) -> Result<bool, MissingKey> where
+                K: Borrow<Q1> + Borrow<Q2>,
+                Q1: $($key_bounds)* + ?Sized,
+                Q2: $($key_bounds)* + ?Sized,
+            {
+                let first_index = self.map.get(coerce(first_key)).copied();
+                let second_index = self.map.get(coerce(second_key)).copied();
+
+                match (first_index, second_index) {
+                    (Some(first_index), Some(second_index)) => {
+                        let joined = self.vec.other_sets(first_index, second_index);
+                        self.vec.union(first_index, second_index);
+
+                        Ok(joined)
+                    },
+                    (None, Some(_)) => Err(MissingKey::First),
+                    (Some(_), None) => Err(MissingKey::Second),
+                    (None, None) => Err(MissingKey::Both),
+                }
+            }
+
+            /// Joins the sets of all the given keys into one set.
+            ///
+            /// Keys that are not present in the map are skipped.
+            pub fn union_all_keys<'q, I, Q>(&mut self, keys: I) where
+                I: IntoIterator<Item = &'q Q>,
+                K: Borrow<Q>,
+                Q: $($key_bounds)* + ?Sized + 'q,
+            {
+                let map = &self.map;
+                let mut indices = keys.into_iter()
+                    .filter_map(|key| map.get(coerce(key)).copied());
+
+                if let Some(first_index) = indices.next() {
+                    for index in indices {
+                        self.vec.union(first_index, index);
+                    }
+                }
+            }
+
             #[inline]
             pub fn same_set<Q1, Q2>(&self, first_key: &Q1, second_key: &Q2) -> bool where
                 K: Borrow<Q1> + Borrow<Q2>,
@@ -174,14 +298,52 @@ macro_rules! partition_map {
             pub fn clear(&mut self) {
                 self.map.clear();
                 self.vec.clear_lazy_removed();
+                self.last_removed = !0;
             }
 
-            pub fn entry(&mut self, key: K) -> Entry<K, V> {
-                let entry = unsafe { self.map.entry(UnboundedRef::from(&key)) };
+            /// Removes all entries and returns an iterator over them.
+            ///
+            /// The map is empty as soon as this is called and keeps its allocations.
+            /// The entries that are not yielded are dropped when the iterator is dropped.
+            pub fn drain(&mut self) -> Drain<'_, K, V> {
+                // The keys point into `vec` so they are removed before `vec` is moved.
+                self.map.clear();
+                self.last_removed = !0;
+
+                let vec = std::mem::take(&mut self.vec);
+                let len = vec.len() - (0..vec.len()).filter(|&i| vec.is_lazy_removed(i)).count();
+
+                Drain {
+                    vec,
+                    target: &mut self.vec,
+                    index: 0,
+                    len,
+                }
+            }
+
+            pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+                if self.last_removed == !0 {
+                    self.reserve_in_vec(1);
+
+                    unsafe {
+                        self.last_removed = self.vec.len();
+                        self.vec.push_lazy_removed();
+                    }
+                }
+
+                // We move the key in the free spot so the entry of the map can point to it.
+                unsafe {
+                    std::ptr::write(&raw mut self.vec[self.last_removed].0, key);
+                }
+                let entry = unsafe {
+                    self.map.entry(self.key_at(self.last_removed))
+                };
 
                 match entry {
                     $map_mod::Entry::Occupied(occupied) => {
-                        drop(key);
+                        unsafe {
+                            drop(std::ptr::read(&self.vec[self.last_removed].0));
+                        }
 
                         Entry::Occupied(OccupiedEntry {
                             entry: occupied,
@@ -190,14 +352,6 @@ macro_rules! partition_map {
                         })
                     },
                     $map_mod::Entry::Vacant(vacant) => {
-                        if self.last_removed == !0 {
-                            unsafe {
-                                self.last_removed = self.vec.len();
-                                self.vec.push_lazy_removed();
-                            }
-                        }
-                        self.vec[self.last_removed].0 = key;
-
                         Entry::Vacant(VacantEntry {
                             entry: vacant,
                             vec: &mut self.vec,
@@ -221,6 +375,27 @@ macro_rules! partition_map {
                 self.vec.get_mut(*self.map.get(coerce(key))?).map(|(_key, value)| value)
             }
 
+            /// Returns mutable references to the values of all the given keys at once.
+            ///
+            /// Returns `None` if any of the keys is not present or if a key is given twice.
+            pub fn get_many_mut<Q, const N: usize>(
+                &mut self,
+                keys: [&Q; N],
+            ) -> Option<[&mut V; N]> where
+                K: Borrow<Q>,
+                Q: $($key_bounds)* + ?Sized,
+            {
+                let mut indices = [0; N];
+                for (index, key) in indices.iter_mut().zip(keys.iter()) {
+                    *index = *self.map.get(coerce(*key))?;
+                }
+
+                // A key given twice has the same index twice which is rejected here.
+                let entries = self.vec.get_disjoint_mut(indices).ok()?;
+
+                Some(entries.map(|(_key, value)| value))
+            }
+
             pub fn contains_key<Q>(&self, key: &Q) -> bool where
                 K: Borrow<Q>,
                 Q: $($key_bounds)* + ?Sized,
@@ -235,6 +410,8 @@ macro_rules! partition_map {
                 } else {
                     let index;
                     if self.last_removed == !0 {
+                        self.reserve_in_vec(1);
+
                         index = self.vec.len();
                         self.vec.push(
                             (key, value)
@@ -248,7 +425,7 @@ macro_rules! partition_map {
                     }
 
                     unsafe {
-                        self.map.insert(UnboundedRef::from(&self.vec[index].0), index);
+                        self.map.insert(self.key_at(index), index);
                     }
 
                     None
@@ -277,40 +454,125 @@ macro_rules! partition_map {
                 unsafe { Some(self.vec.lazy_remove(index, last_removed)) }
             }
 
-            #[must_use] pub fn keys(&self) -> Keys<K, V> {
+            #[must_use] pub fn keys(&self) -> Keys<'_, K, V> {
                 Keys {
                     iter: self.map.keys(),
                     marker: std::marker::PhantomData,
                 }
             }
 
-            #[must_use] pub fn values(&self) -> Values<K, V> {
+            #[must_use] pub fn values(&self) -> Values<'_, K, V> {
                 Values {
                     iter: self.map.values(),
                     vec: &self.vec,
                 }
             }
 
-            pub fn values_mut(&mut self) -> ValuesMut<K, V> {
+            pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
                 ValuesMut {
                     iter: self.map.values(),
                     vec: &mut self.vec,
                 }
             }
 
-            #[must_use] pub fn iter(&self) -> Iter<K, V> {
+            #[must_use] pub fn iter(&self) -> Iter<'_, K, V> {
                 Iter {
                     iter: self.map.values(),
                     vec: &self.vec,
                 }
             }
 
-            pub fn iter_mut(&mut self) -> IterMut<K, V> {
+            pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
                 IterMut {
                     iter: self.map.values_mut(),
                     vec: &mut self.vec,
                 }
             }
+
+            /// Returns the index in `vec` of the given key.
+            pub(crate) fn index_of<Q>(&self, key: &Q) -> Option<usize> where
+                K: Borrow<Q>,
+                Q: $($key_bounds)* + ?Sized,
+            {
+                self.map.get(coerce(key)).copied()
+            }
+
+            /// Returns the `PartitionVec` holding the entries, this includes the removed ones.
+            pub(crate) fn partition_vec(&self) -> &PartitionVec<(K, V)> {
+                &self.vec
+            }
+
+            /// Makes sure `vec` can hold `additional` more elements without reallocating.
+            ///
+            /// The keys in `map` point into `vec` so these are updated if `vec` reallocates.
+            fn reserve_in_vec(&mut self, additional: usize) {
+                if self.vec.capacity() - self.vec.len() < additional {
+                    self.vec.reserve(additional);
+                    self.relocate_keys();
+                }
+            }
+
+            /// Returns a reference to the key at `index` of `vec` to store in `map`.
+            ///
+            /// The pointer is derived from the buffer of `vec` instead of from a reference to
+            /// the element, so it is not invalidated when other elements are borrowed mutably.
+            ///
+            /// The element at `index` has to be initialized.
+            #[inline]
+            unsafe fn key_at(&self, index: usize) -> UnboundedRef<K> {
+                UnboundedRef::new(&raw const (*self.vec.data_ptr().add(index)).0)
+            }
+
+            /// Updates the keys in `map` to point to the current location of the keys in `vec`.
+            ///
+            /// This has to be called every time `vec` might have reallocated.
+            /// The old keys are not dereferenced.
+            fn relocate_keys(&mut self) {
+                let indices: Vec<usize> = self.map.values().copied().collect();
+                self.map.clear();
+
+                for index in indices {
+                    unsafe {
+                        self.map.insert(self.key_at(index), index);
+                    }
+                }
+            }
+        }
+
+        impl<K, V$(, $generic)*> Clone for $struct<K, V$(, $generic)*> where
+            K: $($key_bounds)* + Clone,
+            V: Clone,
+            $($generic: $bound + Clone,)*
+        {
+            fn clone(&self) -> Self {
+                let mut clone = Self {
+                    map: self.map.clone(),
+                    vec: PartitionVec::with_capacity(self.vec.len()),
+                    last_removed: self.last_removed,
+                };
+                clone.vec.clone_lazy_removed_from(&self.vec);
+                clone.relocate_keys();
+
+                clone
+            }
+
+            /// Reuses both the map and the vec of `self`.
+            ///
+            /// The map is refilled instead of cloned so its allocation is kept,
+            /// this means `self` keeps its own hasher.
+            fn clone_from(&mut self, source: &Self) {
+                // Make sure we never hold keys pointing to values that are about to be dropped.
+                self.map.clear();
+                self.last_removed = !0;
+
+                self.vec.clone_lazy_removed_from(&source.vec);
+                for &index in source.map.values() {
+                    unsafe {
+                        self.map.insert(self.key_at(index), index);
+                    }
+                }
+                self.last_removed = source.last_removed;
+            }
         }
 
         impl<K, V$(, $generic)*> Default for $struct<K, V$(, $generic)*> where
@@ -355,6 +617,9 @@ macro_rules! partition_map {
             fn extend<I>(&mut self, iter: I) where
                 I: IntoIterator<Item = (K, V)>,
             {
+                let iter = iter.into_iter();
+                self.reserve_for_extend(iter.size_hint().0);
+
                 for (key, value) in iter {
                     self.insert(key, value);
                 }
@@ -369,6 +634,9 @@ macro_rules! partition_map {
             fn extend<I>(&mut self, iter: I) where
                 I: IntoIterator<Item = (&'a K, &'a V)>,
             {
+                let iter = iter.into_iter();
+                self.reserve_for_extend(iter.size_hint().0);
+
                 for (&key, &value) in iter {
                     self.insert(key, value);
                 }
@@ -457,7 +725,7 @@ macro_rules! partition_map {
                 }
             }
 
-            pub fn and_modify<F>(mut self, f: F) -> Self where
+            #[must_use] pub fn and_modify<F>(mut self, f: F) -> Self where
                 F: FnOnce(&mut V),
             {
                 if let Entry::Occupied(occupied) = &mut self {
@@ -501,7 +769,6 @@ macro_rules! partition_map {
                 unsafe {
                     let key = std::ptr::read(&self.vec[*self.last_removed].0);
 
-                    drop(std::ptr::read(&self.entry));
                     std::mem::forget(self);
 
                     key
@@ -510,17 +777,19 @@ macro_rules! partition_map {
 
             pub fn insert(self, value: V) -> &'a mut V {
                 unsafe {
-                    let key = std::ptr::read(&self.vec[*self.last_removed].0);
-                    let index = *self.last_removed;
+                    // The fields are moved out below. Moving `self` into `mem::forget` after
+                    // that would reborrow them and invalidate the copies.
+                    let mut this = std::mem::ManuallyDrop::new(self);
+                    let key = std::ptr::read(&this.vec[*this.last_removed].0);
+                    let index = *this.last_removed;
 
-                    *self.last_removed = self.vec.insert_over_lazy_removed(
+                    *this.last_removed = this.vec.insert_over_lazy_removed(
                         index,
                         (key, value)
                     );
 
-                    let entry = std::ptr::read(&self.entry);
-                    let vec = std::ptr::read(&self.vec);
-                    std::mem::forget(self);
+                    let entry = std::ptr::read(&this.entry);
+                    let vec = std::ptr::read(&this.vec);
                     entry.insert(index);
 
                     &mut vec[index].1
@@ -726,6 +995,56 @@ macro_rules! partition_map {
             }
         }
 
+        /// If this is leaked the entries are leaked as well and the map stays empty.
+        pub struct Drain<'a, K: 'a, V: 'a> {
+            vec: PartitionVec<(K, V)>,
+            target: &'a mut PartitionVec<(K, V)>,
+            index: usize,
+            len: usize,
+        }
+
+        impl<'a, K, V> Iterator for Drain<'a, K, V> {
+            type Item = (K, V);
+
+            fn next(&mut self) -> Option<(K, V)> {
+                while self.index < self.vec.len() {
+                    let index = self.index;
+                    self.index += 1;
+
+                    if !self.vec.is_lazy_removed(index) {
+                        self.len -= 1;
+
+                        return unsafe { Some(std::ptr::read(&self.vec[index])) };
+                    }
+                }
+
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<'a, K, V> FusedIterator for Drain<'a, K, V> {}
+
+        impl<'a, K, V> Drop for Drain<'a, K, V> {
+            fn drop(&mut self) {
+                while let Some(_) = self.next() {}
+
+                // We give the allocation back to the map.
+                unsafe { self.vec.set_len(0); }
+                std::mem::swap(self.target, &mut self.vec);
+            }
+        }
+
         #[derive(Clone)]
         pub struct Iter<'a, K: 'a, V: 'a> {
             iter: $map_mod::Values<'a, UnboundedRef<K>, usize>,
@@ -789,5 +1108,298 @@ macro_rules! partition_map {
     };
 }
 
+macro_rules! partition_set {
+    (
+        #[$doc: meta]
+        $struct: ident <T$(, $generic: tt: $bound: tt = $default: tt)*>
+        $map_mod: ident
+        $map_struct: ident
+        $($value_bounds: tt)*
+    ) => {
+        use {
+            std::{
+                fmt,
+                borrow::Borrow,
+                iter::{FromIterator, FusedIterator},
+                ops::Range,
+            },
+            crate::{
+                PartitionVec,
+                partition_vec,
+                $map_mod::{self, $map_struct},
+            },
+        };
+
+        #[$doc]
+        pub struct $struct<T$(, $generic = $default)*> {
+            map: $map_struct<T, ()$(, $generic)*>,
+        }
+
+        impl<T> $struct<T$(, $default)*> where
+            T: $($value_bounds)*,
+        {
+            #[inline]
+            #[must_use] pub fn new() -> Self {
+                Self {
+                    map: $map_struct::new(),
+                }
+            }
+        }
+
+        impl<T$(, $generic)*> $struct<T$(, $generic)*> where
+            T: $($value_bounds)*,
+            $($generic: $bound,)*
+        {
+            /// Adds `value` as a singleton.
+            ///
+            /// Returns `false` and leaves the set unchanged if `value` was already present.
+            pub fn insert(&mut self, value: T) -> bool {
+                if self.map.contains_key(&value) {
+                    false
+                } else {
+                    self.map.insert(value, ());
+                    true
+                }
+            }
+
+            /// Removes `value` from its class.
+            ///
+            /// Returns whether `value` was present.
+            pub fn remove<Q>(&mut self, value: &Q) -> bool where
+                T: Borrow<Q>,
+                Q: $($value_bounds)* + ?Sized,
+            {
+                self.map.remove(value).is_some()
+            }
+
+            #[inline]
+            pub fn contains<Q>(&self, value: &Q) -> bool where
+                T: Borrow<Q>,
+                Q: $($value_bounds)* + ?Sized,
+            {
+                self.map.contains_key(value)
+            }
+
+            /// Joins the classes of `first_value` and `second_value`.
+            ///
+            /// Values that are not present yet are inserted first.
+            pub fn union<Q1, Q2>(&mut self, first_value: &Q1, second_value: &Q2) where
+                T: Borrow<Q1> + Borrow<Q2>,
+                Q1: $($value_bounds)* + ToOwned<Owned = T> + ?Sized,
+                Q2: $($value_bounds)* + ToOwned<Owned = T> + ?Sized,
+            {
+                if !self.map.contains_key(first_value) {
+                    self.map.insert(first_value.to_owned(), ());
+                }
+                if !self.map.contains_key(second_value) {
+                    self.map.insert(second_value.to_owned(), ());
+                }
+
+                self.map.union(first_value, second_value);
+            }
+
+            /// Returns whether `first_value` and `second_value` are in the same class.
+            ///
+            /// Returns `None` if either of the values is not present.
+            pub fn same_set<Q1, Q2>(
+                &self,
+                first_value: &Q1,
+                second_value: &Q2,
+            ) -> Option<bool> where
+                T: Borrow<Q1> + Borrow<Q2>,
+                Q1: $($value_bounds)* + ?Sized,
+                Q2: $($value_bounds)* + ?Sized,
+            {
+                let first_index = self.map.index_of(first_value)?;
+                let second_index = self.map.index_of(second_value)?;
+
+                Some(self.map.partition_vec().same_set(first_index, second_index))
+            }
+
+            /// Returns an iterator over the class of `value` in arbitrary order.
+            ///
+            /// Returns `None` if `value` is not present.
+            pub fn set<Q>(&self, value: &Q) -> Option<Class<'_, T>> where
+                T: Borrow<Q>,
+                Q: $($value_bounds)* + ?Sized,
+            {
+                let index = self.map.index_of(value)?;
+
+                Some(Class {
+                    set: self.map.partition_vec().set(index),
+                })
+            }
+
+            /// Returns an iterator over all the classes, each class is an iterator itself.
+            #[must_use] pub fn classes(&self) -> Classes<'_, T> {
+                let vec = self.map.partition_vec();
+
+                Classes {
+                    vec,
+                    done: bit_vec![false; vec.len()],
+                    range: 0..vec.len(),
+                }
+            }
+
+            /// Returns an iterator over all the values in arbitrary order.
+            #[must_use] pub fn iter(&self) -> $map_mod::Keys<'_, T, ()> {
+                self.map.keys()
+            }
+
+            #[inline]
+            #[must_use] pub fn len(&self) -> usize {
+                self.map.len()
+            }
+
+            #[inline]
+            #[must_use] pub fn is_empty(&self) -> bool {
+                self.map.is_empty()
+            }
+
+            #[inline]
+            pub fn clear(&mut self) {
+                self.map.clear();
+            }
+        }
+
+        impl<T$(, $generic)*> Clone for $struct<T$(, $generic)*> where
+            T: $($value_bounds)* + Clone,
+            $($generic: $bound + Clone,)*
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    map: self.map.clone(),
+                }
+            }
+        }
+
+        impl<T$(, $generic)*> Default for $struct<T$(, $generic)*> where
+            T: $($value_bounds)*,
+            $($generic: $bound + Default,)*
+        {
+            fn default() -> Self {
+                Self {
+                    map: $map_struct::default(),
+                }
+            }
+        }
+
+        impl<T$(, $generic)*> fmt::Debug for $struct<T$(, $generic)*> where
+            T: $($value_bounds)* + fmt::Debug,
+            $($generic: $bound,)*
+        {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.debug_set().entries(self.classes()).finish()
+            }
+        }
+
+        impl<T$(, $generic)*> Extend<T> for $struct<T$(, $generic)*> where
+            T: $($value_bounds)*,
+            $($generic: $bound,)*
+        {
+            fn extend<I>(&mut self, iter: I) where
+                I: IntoIterator<Item = T>,
+            {
+                for value in iter {
+                    self.insert(value);
+                }
+            }
+        }
+
+        impl<T$(, $generic)*> FromIterator<T> for $struct<T$(, $generic)*> where
+            T: $($value_bounds)*,
+            $($generic: $bound + Default,)*
+        {
+            fn from_iter<I>(iter: I) -> Self where
+                I: IntoIterator<Item = T>,
+            {
+                let mut set = Self::default();
+                set.extend(iter);
+
+                set
+            }
+        }
+
+        impl<'a, T$(, $generic)*> IntoIterator for &'a $struct<T$(, $generic)*> where
+            T: $($value_bounds)*,
+            $($generic: $bound,)*
+        {
+            type Item = &'a T;
+            type IntoIter = $map_mod::Keys<'a, T, ()>;
+
+            fn into_iter(self) -> $map_mod::Keys<'a, T, ()> {
+                self.iter()
+            }
+        }
+
+        /// An iterator over the values in one class.
+        pub struct Class<'a, T: 'a> {
+            set: partition_vec::Set<'a, (T, ())>,
+        }
+
+        impl<'a, T> Iterator for Class<'a, T> {
+            type Item = &'a T;
+
+            #[inline]
+            fn next(&mut self) -> Option<&'a T> {
+                self.set.next().map(|(_index, (value, ()))| value)
+            }
+        }
+
+        impl<'a, T> Clone for Class<'a, T> {
+            fn clone(&self) -> Self {
+                Class {
+                    set: self.set.clone(),
+                }
+            }
+        }
+
+        impl<'a, T> FusedIterator for Class<'a, T> {}
+
+        impl<'a, T> fmt::Debug for Class<'a, T> where
+            T: fmt::Debug,
+        {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.debug_set().entries(self.clone()).finish()
+            }
+        }
+
+        /// An iterator over all the classes.
+        pub struct Classes<'a, T: 'a> {
+            vec: &'a PartitionVec<(T, ())>,
+            done: bit_vec::BitVec,
+            range: Range<usize>,
+        }
+
+        impl<'a, T> Iterator for Classes<'a, T> {
+            type Item = Class<'a, T>;
+
+            fn next(&mut self) -> Option<Class<'a, T>> {
+                for index in &mut self.range {
+                    // The slots of removed values are not part of any class.
+                    if self.vec.is_lazy_removed(index) {
+                        continue;
+                    }
+
+                    let root = self.vec.find(index);
+                    if !self.done.get(root).unwrap_or(true) {
+                        self.done.set(root, true);
+
+                        return Some(Class {
+                            set: self.vec.set(index),
+                        });
+                    }
+                }
+
+                None
+            }
+        }
+
+        impl<'a, T> FusedIterator for Classes<'a, T> {}
+    };
+}
+
 pub mod partition_btree_map;
+pub mod partition_btree_set;
 pub mod partition_hash_map;
+pub mod partition_hash_set;
@@ -1,4 +1,7 @@
-use std::collections::btree_map::{self, BTreeMap};
+use {
+    crate::partition_vec::MemoryUsage,
+    std::collections::btree_map::{self, BTreeMap},
+};
 
 partition_map![
     /// This is a `PartitionBTreeMap`.
@@ -7,6 +10,50 @@ partition_map![
     BTreeMap
     Ord
 ];
+
+impl<K, V> PartitionBTreeMap<K, V>
+where
+    K: Ord,
+{
+    /// Returns the amount of heap memory used by the map.
+    ///
+    /// The memory of the `BTreeMap` used to find the keys is estimated from its length.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let entry_bytes = std::mem::size_of::<(UnboundedRef<K>, usize)>();
+
+        MemoryUsage {
+            len: self.len(),
+            map_bytes: self.map.len() * entry_bytes,
+            ..self.vec.memory_usage()
+        }
+    }
+
+    /// Returns the entry with the smallest key, or `None` if the map is empty.
+    #[must_use]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let (_, &index) = self.map.first_key_value()?;
+        let (key, value) = &self.vec[index];
+
+        Some((key, value))
+    }
+
+    /// Returns the entry with the largest key, or `None` if the map is empty.
+    #[must_use]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let (_, &index) = self.map.last_key_value()?;
+        let (key, value) = &self.vec[index];
+
+        Some((key, value))
+    }
+
+    /// This is used by the `Extend` implementations to reserve room for the new elements.
+    ///
+    /// A `BTreeMap` can not reserve memory so we only reserve room in the `PartitionVec`.
+    fn reserve_for_extend(&mut self, additional: usize) {
+        self.reserve_in_vec(additional);
+    }
+}
 /*
 impl<K, V> PartitionBTreeMap<K, V> where
     K: Ord,
@@ -0,0 +1,7 @@
+partition_set![
+    /// This is a `PartitionBTreeSet`, an equivalence relation over values without payloads.
+    PartitionBTreeSet<T>
+    partition_btree_map
+    PartitionBTreeMap
+    Ord
+];
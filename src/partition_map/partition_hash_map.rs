@@ -1,6 +1,9 @@
-use std::{
-    collections::hash_map::{self, HashMap, RandomState},
-    hash::{BuildHasher, Hash},
+use {
+    crate::partition_vec::MemoryUsage,
+    std::{
+        collections::hash_map::{self, HashMap, RandomState},
+        hash::{BuildHasher, Hash},
+    },
 };
 
 partition_map![
@@ -52,15 +55,35 @@ where
 
     pub fn reserve(&mut self, additional: usize) {
         self.map.reserve(additional);
-        self.vec.reserve(additional);
+        self.reserve_in_vec(additional);
     }
 
     pub fn shrink_to_fit(&mut self) {
         self.map.shrink_to_fit();
         self.vec.shrink_to_fit();
+        self.relocate_keys();
+    }
+
+    /// Returns the amount of heap memory used by the map.
+    ///
+    /// The memory of the `HashMap` used to find the keys is estimated from its capacity.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let entry_bytes = std::mem::size_of::<(UnboundedRef<K>, usize)>() + 1;
+
+        MemoryUsage {
+            len: self.len(),
+            map_bytes: self.map.capacity() * entry_bytes,
+            ..self.vec.memory_usage()
+        }
     }
 
     pub fn hasher(&self) -> &S {
         self.map.hasher()
     }
+
+    /// This is used by the `Extend` implementations to reserve room for the new elements.
+    fn reserve_for_extend(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
 }
@@ -0,0 +1,44 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+};
+
+partition_set![
+    /// This is a `PartitionHashSet`, an equivalence relation over values without payloads.
+    PartitionHashSet<T, S: BuildHasher = RandomState>
+    partition_hash_map
+    PartitionHashMap
+    Eq + Hash
+];
+
+impl<T> PartitionHashSet<T, RandomState>
+where
+    T: Eq + Hash,
+{
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: PartitionHashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<T, S> PartitionHashSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: PartitionHashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+}
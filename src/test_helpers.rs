@@ -0,0 +1,37 @@
+//! Helpers shared by the tests of the different modules.
+
+use UnionFind;
+
+/// An operation that changes the sets of a `UnionFind` structure.
+#[derive(Clone, Debug)]
+pub enum Operation {
+    Union(usize, usize),
+    MakeSingleton(usize),
+}
+
+impl Operation {
+    /// Returns this operation with every index taken modulo `len`.
+    pub fn wrap(&self, len: usize) -> Self {
+        match *self {
+            Operation::Union(first, second) => Operation::Union(first % len, second % len),
+            Operation::MakeSingleton(index) => Operation::MakeSingleton(index % len),
+        }
+    }
+
+    /// Applies this operation to `sets`.
+    pub fn apply<U: UnionFind + ?Sized>(&self, sets: &mut U) {
+        match *self {
+            Operation::Union(first, second) => {
+                sets.union(first, second);
+            }
+            Operation::MakeSingleton(index) => sets.make_singleton(index),
+        }
+    }
+}
+
+/// Applies the operations to `sets` in order.
+pub fn apply<U: UnionFind + ?Sized>(sets: &mut U, operations: &[Operation]) {
+    for operation in operations {
+        operation.apply(sets);
+    }
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "std")]
+
+extern crate partitions;
+
+use partitions::PartitionVec;
+
+/// The bytes of the values `a, c, a` with the sets `{0, 2}` and `{1}`, one byte per value.
+///
+/// These bytes are fixed so a build with the `compact` feature and a build without it
+/// both have to write and read exactly this.
+#[rustfmt::skip]
+const BYTES: [u8; 5 + 8 * 7 + 3] = [
+    b'P', b'V', b'E', b'C', 1,
+    3, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0,
+    1, 0, 0, 0, 0, 0, 0, 0, b'a',
+    1, 0, 0, 0, 0, 0, 0, 0, b'c',
+    1, 0, 0, 0, 0, 0, 0, 0, b'a',
+];
+
+fn partition_vec() -> PartitionVec<u8> {
+    let mut partition_vec = PartitionVec::from(vec![b'a', b'c', b'a']);
+    partition_vec.union(2, 0);
+
+    partition_vec
+}
+
+#[test]
+fn to_bytes_writes_fixed_format() {
+    let bytes = partition_vec().to_bytes(|&value, bytes| bytes.push(value));
+
+    assert_eq!(bytes[..], BYTES[..]);
+}
+
+#[test]
+fn from_bytes_reads_fixed_format() {
+    let decoded = PartitionVec::from_bytes(&BYTES, |bytes| match *bytes {
+        [value] => Some(value),
+        _ => None,
+    });
+
+    assert_eq!(decoded, Ok(partition_vec()));
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "std")]
+
+extern crate partitions;
+
+mod without_macro_use {
+    use partitions::partition_vec;
+
+    #[test]
+    fn repeated_element() {
+        let partition_vec = partition_vec!['a'; 3];
+
+        assert!(partition_vec.as_slice() == ['a', 'a', 'a']);
+        assert!(partition_vec.amount_of_sets() == 3);
+    }
+
+    #[test]
+    fn list() {
+        let empty: partitions::PartitionVec<u8> = partition_vec![];
+        assert!(empty.is_empty());
+
+        let single = partition_vec![1];
+        assert!(single.as_slice() == [1]);
+        assert!(single.capacity() == 1);
+
+        let partition_vec = partition_vec![1, 2, 3];
+        assert!(partition_vec.as_slice() == [1, 2, 3]);
+        assert!(partition_vec.capacity() == 3);
+        assert!(partition_vec.amount_of_sets() == 3);
+
+        let trailing = partition_vec![1, 2, 3, 4,];
+        assert!(trailing.as_slice() == [1, 2, 3, 4]);
+        assert!(trailing.capacity() == 4);
+    }
+
+    #[test]
+    fn list_with_sets() {
+        let partition_vec = partition_vec![
+            'a' => 0,
+            'b' => 1,
+            'c' => 0
+        ];
+        assert!(partition_vec.as_slice() == ['a', 'b', 'c']);
+        assert!(partition_vec.capacity() == 3);
+        assert!(partition_vec.same_set(0, 2));
+        assert!(!partition_vec.same_set(0, 1));
+
+        let trailing = partition_vec![
+            'a' => "first",
+            'b' => "first",
+            'c' => "second",
+            'd' => "third",
+            'e' => "second",
+        ];
+        assert!(trailing.capacity() == 5);
+        assert!(trailing.amount_of_sets() == 3);
+        assert!(trailing.same_set(2, 4));
+    }
+
+    #[test]
+    fn expression_position() {
+        fn len_of_sets() -> usize {
+            partition_vec![(); 4].len_of_set(0) + partition_vec![() => 0, () => 0].len_of_set(0)
+        }
+
+        assert!(len_of_sets() == 3);
+    }
+}
@@ -0,0 +1,301 @@
+#![cfg(feature = "std")]
+
+extern crate partitions;
+
+use partitions::{PartitionBTreeMap, PartitionHashMap};
+
+#[test]
+fn union_all_keys() {
+    let mut map = PartitionHashMap::new();
+    for (key, value) in ["a", "b", "c", "d", "e"].iter().zip(0..) {
+        map.insert(key.to_string(), value);
+    }
+
+    map.union_all_keys(["a", "c", "e"].iter().copied());
+
+    assert!(map.same_set("a", "c"));
+    assert!(map.same_set("a", "e"));
+    assert!(map.other_sets("a", "b"));
+    assert!(map.other_sets("a", "d"));
+    assert!(map.len_of_set("c") == 3);
+    assert!(map.amount_of_sets() == 3);
+}
+
+#[test]
+fn union_all_keys_skips_missing_keys() {
+    let mut map = PartitionBTreeMap::new();
+    for key in 0..4 {
+        map.insert(key, ());
+    }
+
+    map.union_all_keys(&[7, 1, 9, 3]);
+    assert!(map.same_set(&1, &3));
+    assert!(map.amount_of_sets() == 3);
+
+    map.union_all_keys(&[7, 2, 9]);
+    assert!(map.is_singleton(&2));
+    assert!(map.amount_of_sets() == 3);
+
+    map.union_all_keys(&[]);
+    assert!(map.amount_of_sets() == 3);
+}
+
+#[test]
+fn keys_survive_reallocation() {
+    let mut map = PartitionHashMap::new();
+    for key in 0..100 {
+        map.insert(key.to_string(), key);
+    }
+    for key in 100..200 {
+        *map.entry(key.to_string()).or_insert(0) += key;
+    }
+    for key in (0..200).step_by(3) {
+        assert!(map.remove(&key.to_string()) == Some(key));
+    }
+    for key in 200..300 {
+        *map.entry(key.to_string()).or_insert(0) += key;
+    }
+
+    for key in 0..300 {
+        let value = map.get(&key.to_string());
+        if key < 200 && key % 3 == 0 {
+            assert!(value.is_none());
+        } else {
+            assert!(value == Some(&key));
+        }
+    }
+    assert!(map.len() == 300 - 67);
+}
+
+#[test]
+fn try_union() {
+    use partitions::MissingKey;
+
+    let mut map = PartitionHashMap::new();
+    map.insert("a", 1);
+    map.insert("b", 2);
+    map.insert("c", 3);
+
+    assert!(map.try_union("a", "b") == Ok(true));
+    assert!(map.try_union("b", "a") == Ok(false));
+    assert!(map.same_set("a", "b"));
+
+    assert!(map.try_union("a", "x") == Err(MissingKey::Second));
+    assert!(map.try_union("x", "c") == Err(MissingKey::First));
+    assert!(map.try_union("x", "y") == Err(MissingKey::Both));
+    assert!(map.is_singleton("c"));
+    assert!(map.amount_of_sets() == 2);
+}
+
+#[test]
+fn get_many_mut() {
+    let mut map = PartitionHashMap::new();
+    map.insert("a", 1);
+    map.insert("b", 2);
+    map.insert("c", 3);
+    map.union("a", "c");
+
+    if let Some([a, b, c]) = map.get_many_mut(["a", "b", "c"]) {
+        std::mem::swap(a, b);
+        *c += 10;
+    } else {
+        panic!("all keys are present and distinct");
+    }
+    assert!(map["a"] == 2);
+    assert!(map["b"] == 1);
+    assert!(map["c"] == 13);
+
+    assert!(map.get_many_mut(["a", "x"]).is_none());
+    assert!(map.get_many_mut(["b", "b"]).is_none());
+    assert!(map.get_many_mut::<str, 0>([]).is_some());
+}
+
+#[test]
+fn memory_usage() {
+    let mut map = PartitionHashMap::with_capacity(16);
+    for key in 0..10u64 {
+        map.insert(key, key);
+    }
+    map.remove(&3);
+
+    let memory_usage = map.memory_usage();
+    assert!(memory_usage.len == 9);
+    assert!(memory_usage.capacity >= 16);
+    assert!(memory_usage.data_bytes >= 16 * std::mem::size_of::<(u64, u64)>());
+    assert!(memory_usage.map_bytes > 0);
+    assert!(memory_usage.total_bytes() > memory_usage.data_bytes + memory_usage.meta_bytes);
+
+    let mut map = PartitionBTreeMap::new();
+    assert!(map.memory_usage().map_bytes == 0);
+    map.insert("a", ());
+    map.insert("b", ());
+    let single = map.memory_usage().map_bytes;
+    map.insert("c", ());
+    map.insert("d", ());
+    assert!(map.memory_usage().map_bytes == 2 * single);
+    assert!(!map.memory_usage().to_string().is_empty());
+}
+
+#[test]
+fn extend_reserves_once() {
+    let mut map = PartitionHashMap::new();
+    map.extend((0..1000).map(|key| (key, key)));
+
+    // Growing one element at a time would have left a power of two as capacity.
+    assert!(map.memory_usage().capacity == 1000);
+    assert!(map.len() == 1000);
+    assert!(map.capacity() >= 1000);
+
+    let pairs: Vec<(u32, char)> = (0..100).zip("abcdefghij".chars().cycle()).collect();
+    let mut map = PartitionBTreeMap::new();
+    map.extend(pairs.iter().map(|(key, value)| (key, value)));
+    assert!(map.memory_usage().capacity == 100);
+    assert!(map.get(&12) == Some(&'c'));
+}
+
+#[test]
+fn clone_skips_removed_entries() {
+    let mut map = PartitionHashMap::new();
+    for key in 0..10 {
+        map.insert(key.to_string(), vec![key]);
+    }
+    map.union("1", "2");
+    map.union("3", "4");
+    map.remove("3");
+    map.remove("7");
+
+    let mut clone = map.clone();
+    drop(map);
+    assert!(clone.len() == 8);
+    assert!(clone.get("7").is_none());
+    assert!(clone.get("4") == Some(&vec![4]));
+    assert!(clone.same_set("1", "2"));
+    assert!(clone.len_of_set("4") == 1);
+
+    // Entries reuse the slots that were lazily removed in the original.
+    clone.insert("10".to_string(), vec![10]);
+    clone.insert("11".to_string(), vec![11]);
+    assert!(clone.get("10") == Some(&vec![10]));
+    assert!(clone.get("11") == Some(&vec![11]));
+    assert!(clone.len() == 10);
+}
+
+#[test]
+fn clone_from_reuses_allocations() {
+    let mut template = PartitionHashMap::new();
+    for key in 0..20 {
+        template.insert(key, key * 2);
+    }
+    template.union(&3, &5);
+    template.remove(&8);
+
+    let mut scratch = PartitionHashMap::with_capacity(64);
+    scratch.insert(100, 100);
+    let capacity = scratch.capacity();
+
+    for _ in 0..3 {
+        scratch.clone_from(&template);
+        scratch.union(&5, &6);
+
+        assert!(scratch.capacity() == capacity);
+        assert!(scratch.get(&100).is_none());
+        assert!(scratch.get(&8).is_none());
+        assert!(scratch[&19] == 38);
+        assert!(scratch.same_set(&3, &6));
+    }
+    assert!(!template.same_set(&3, &6));
+
+    let mut template = PartitionBTreeMap::new();
+    template.insert("a", 1);
+    template.insert("b", 2);
+    template.union("a", "b");
+    let mut scratch = PartitionBTreeMap::new();
+    scratch.insert("c", 3);
+    scratch.clone_from(&template);
+    assert!(scratch.get("c").is_none());
+    assert!(scratch.same_set("a", "b"));
+}
+
+/// A value that counts how many times it was dropped.
+struct DropCounter<'a>(&'a std::cell::Cell<usize>);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn drain() {
+    let drops = std::cell::Cell::new(0);
+    let mut map = PartitionHashMap::new();
+    for key in 0..10 {
+        map.insert(key, DropCounter(&drops));
+    }
+    map.union(&1, &2);
+    map.remove(&3);
+    map.remove(&7);
+    assert!(drops.get() == 2);
+
+    let mut keys: Vec<i32> = map.drain().map(|(key, _)| key).collect();
+    keys.sort_unstable();
+    assert!(keys == [0, 1, 2, 4, 5, 6, 8, 9]);
+    assert!(drops.get() == 10);
+    assert!(map.is_empty());
+
+    // The map can be used again and keeps its allocation.
+    let capacity = map.capacity();
+    for key in 0..10 {
+        map.insert(key, DropCounter(&drops));
+    }
+    assert!(map.capacity() == capacity);
+    assert!(map.len() == 10);
+    assert!(map.is_singleton(&1));
+
+    // Entries that are not yielded are dropped with the iterator.
+    {
+        let mut drain = map.drain();
+        assert!(drain.len() == 10);
+        drain.next();
+        drain.next();
+        assert!(drain.len() == 8);
+    }
+    assert!(drops.get() == 20);
+    assert!(map.is_empty());
+    assert!(map.get(&0).is_none());
+
+    map.insert(0, DropCounter(&drops));
+    drop(map);
+    assert!(drops.get() == 21);
+
+    let mut map = PartitionBTreeMap::new();
+    map.insert("a", DropCounter(&drops));
+    map.insert("b", DropCounter(&drops));
+    std::mem::forget(map.drain());
+    assert!(map.is_empty());
+    drop(map);
+    assert!(drops.get() == 21);
+}
+
+#[test]
+fn first_and_last_key_value() {
+    let mut map = PartitionBTreeMap::new();
+    assert!(map.first_key_value().is_none());
+    assert!(map.last_key_value().is_none());
+
+    for key in &[5, 2, 9, 7, 1] {
+        map.insert(*key, key * 10);
+    }
+    map.union(&1, &9);
+    assert!(map.first_key_value() == Some((&1, &10)));
+    assert!(map.last_key_value() == Some((&9, &90)));
+
+    // Removed entries leave a lazily removed slot behind which is skipped.
+    map.remove(&1);
+    map.remove(&9);
+    assert!(map.first_key_value() == Some((&2, &20)));
+    assert!(map.last_key_value() == Some((&7, &70)));
+
+    map.insert(0, 0);
+    assert!(map.first_key_value() == Some((&0, &0)));
+}
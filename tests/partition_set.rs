@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+extern crate partitions;
+
+use partitions::{PartitionBTreeSet, PartitionHashSet};
+
+#[test]
+fn union_inserts_missing_values() {
+    let mut aliases = PartitionHashSet::new();
+    assert!(aliases.insert("colour".to_string()));
+    assert!(!aliases.insert("colour".to_string()));
+
+    aliases.union("color", "colour");
+    aliases.union("hue", "tint");
+
+    assert!(aliases.len() == 4);
+    assert!(aliases.same_set("color", "colour") == Some(true));
+    assert!(aliases.same_set("color", "hue") == Some(false));
+    assert!(aliases.same_set("color", "shade").is_none());
+}
+
+#[test]
+fn borrowed_lookups() {
+    let mut aliases: PartitionHashSet<String> = ["a", "b", "c"]
+        .iter()
+        .map(|value| value.to_string())
+        .collect();
+
+    aliases.union("a", &"c".to_string());
+
+    assert!(aliases.contains("a"));
+    assert!(!aliases.contains("d"));
+    assert!(aliases.same_set(&"a".to_string(), "c") == Some(true));
+
+    assert!(aliases.remove("c"));
+    assert!(!aliases.remove("c"));
+    assert!(aliases.set("c").is_none());
+    assert!(aliases.set("a").unwrap().count() == 1);
+}
+
+#[test]
+fn classes_cover_every_value_once() {
+    let mut set = PartitionBTreeSet::new();
+    for value in 0..10 {
+        set.union(&value, &(value % 3));
+    }
+    set.remove(&9);
+
+    let mut classes: Vec<Vec<i32>> = set
+        .classes()
+        .map(|class| {
+            let mut class: Vec<i32> = class.copied().collect();
+            class.sort_unstable();
+            class
+        })
+        .collect();
+    classes.sort();
+
+    assert!(classes == [vec![0, 3, 6], vec![1, 4, 7], vec![2, 5, 8]]);
+
+    let mut class: Vec<i32> = set.set(&4).unwrap().copied().collect();
+    class.sort_unstable();
+    assert!(class == [1, 4, 7]);
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "std")]
+
+extern crate partitions;
+
+use partitions::{PartitionVec, SmallPartitionVec};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Counts the allocations made by the current thread so tests running in parallel do not
+/// influence each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // The count is not available while the thread is being torn down.
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the amount of allocations made by `f` on this thread.
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Builds the sets `{0, 2, 4, 6}`, `{1, 3}`, `{5}` and `{7}` and queries them.
+fn union_and_query<F, S>(
+    push: F,
+    union: fn(&mut S, usize, usize),
+    same_set: fn(&S, usize, usize) -> bool,
+    sets: &mut S,
+) where
+    F: Fn(&mut S, u32),
+{
+    for value in 0..8 {
+        push(sets, value);
+    }
+    for index in (2..8).step_by(2) {
+        union(sets, 0, index);
+    }
+    union(sets, 1, 3);
+
+    assert!(same_set(sets, 6, 2));
+    assert!(!same_set(sets, 5, 7));
+}
+
+#[test]
+fn inline_operations_do_not_allocate() {
+    let mut small = SmallPartitionVec::<u32, 8>::new();
+
+    let count = allocations(|| {
+        union_and_query(
+            |small, value| small.push(value),
+            |small, first, second| small.union(first, second),
+            |small, first, second| small.same_set(first, second),
+            &mut small,
+        );
+        assert!(small.amount_of_sets() == 4);
+        assert!(small.len_of_set(4) == 4);
+        assert!(small.set(1).map(|(_, &value)| value).sum::<u32>() == 4);
+        small.make_singleton(2);
+        assert!(small.pop() == Some(7));
+        assert!(small.remove(0) == 0);
+        small.insert(0, 8);
+        for (_, value) in small.set_mut(1) {
+            *value += 1;
+        }
+        small.truncate(3);
+        small.clear();
+    });
+
+    assert!(small.is_inline());
+    assert!(count == 0);
+}
+
+#[test]
+fn partition_vec_allocates_for_the_same_work() {
+    let mut partition_vec = PartitionVec::new();
+
+    let count = allocations(|| {
+        union_and_query(
+            |partition_vec, value| partition_vec.push(value),
+            |partition_vec, first, second| {
+                partition_vec.union(first, second);
+            },
+            |partition_vec, first, second| partition_vec.same_set(first, second),
+            &mut partition_vec,
+        );
+    });
+
+    assert!(count > 0);
+}
+
+#[test]
+fn only_spilling_allocates() {
+    let mut small: SmallPartitionVec<u32, 4> = (0..4).collect();
+    small.union(0, 3);
+
+    assert!(allocations(|| small.push(4)) > 0);
+    assert!(!small.is_inline());
+
+    small.pop();
+    small.shrink_to_fit();
+    assert!(small.is_inline());
+    assert!(allocations(|| small.union(1, 2)) == 0);
+    assert!(small.same_set(0, 3));
+}